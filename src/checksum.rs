@@ -0,0 +1,299 @@
+//! Checksums over regions of the binary, described in a JSON file given with `--checksums`.
+//!
+//! ```json
+//! {
+//!     "update_on_write": true,
+//!     "regions": [
+//!         { "name": "Main", "start": "0x0", "end": "0x7FFC", "address": "0x7FFC", "algorithm": "crc32" }
+//!     ]
+//! }
+//! ```
+
+use std::{
+    io::{Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::Path,
+};
+
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// How the checksum of a region is worked out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// Sum of the bytes, keeping the lowest byte
+    Sum8,
+    /// Sum of the bytes, keeping the lowest 2 bytes
+    Sum16,
+    /// Sum of the bytes, keeping the lowest 4 bytes
+    Sum32,
+    /// CRC-16/CCITT-FALSE, polynomial 0x1021 starting from 0xFFFF
+    Crc16Ccitt,
+    /// CRC-32 as used by zip and PNG
+    Crc32,
+}
+
+impl Algorithm {
+    /// Bytes the checksum takes up when stored
+    pub fn size(self) -> usize {
+        match self {
+            Self::Sum8 => 1,
+            Self::Sum16 | Self::Crc16Ccitt => 2,
+            Self::Sum32 | Self::Crc32 => 4,
+        }
+    }
+
+    pub fn compute(self, data: &[u8]) -> u32 {
+        match self {
+            Self::Sum8 | Self::Sum16 | Self::Sum32 => {
+                let sum = data.iter().fold(0u32, |sum, &b| sum.wrapping_add(b as u32));
+                match self.size() {
+                    4 => sum,
+                    size => sum & ((1 << (size * 8)) - 1),
+                }
+            }
+            Self::Crc16Ccitt => crc16_ccitt(data) as u32,
+            Self::Crc32 => crc32(data),
+        }
+    }
+}
+
+fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Address given as a number or a `0x` prefixed hex string
+fn address<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Address {
+        Number(u64),
+        Text(String),
+    }
+    match Address::deserialize(deserializer)? {
+        Address::Number(n) => Ok(n),
+        Address::Text(text) => {
+            let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                None => text.parse().ok(),
+            };
+            parsed.ok_or_else(|| D::Error::custom(format!("\"{text}\" is not an address")))
+        }
+    }
+}
+
+/// Bytes covered by a checksum and where it is stored
+#[derive(Debug, Clone, Deserialize)]
+pub struct Region {
+    pub name: String,
+    /// First byte covered
+    #[serde(deserialize_with = "address")]
+    pub start: u64,
+    /// Byte after the last one covered
+    #[serde(deserialize_with = "address")]
+    pub end: u64,
+    /// Where the checksum is stored, its bytes are skipped when they lie inside the region
+    #[serde(deserialize_with = "address")]
+    pub address: u64,
+    pub algorithm: Algorithm,
+    /// Store the checksum most significant byte first, values are little endian otherwise
+    #[serde(default)]
+    pub big_endian: bool,
+}
+
+impl Region {
+    /// Bytes holding the stored checksum
+    pub fn stored_range(&self) -> Range<u64> {
+        self.address..self.address + self.algorithm.size() as u64
+    }
+
+    /// Checksum of the region as it is in the binary now.
+    pub fn compute<R: Read + Seek>(&self, bin: &mut R) -> std::io::Result<u32> {
+        let mut data = vec![0u8; self.end.saturating_sub(self.start) as usize];
+        bin.seek(SeekFrom::Start(self.start))?;
+        bin.read_exact(&mut data)?;
+        let stored = self.stored_range();
+        let data: Vec<u8> = (self.start..)
+            .zip(data)
+            .filter(|(address, _)| !stored.contains(address))
+            .map(|(_, byte)| byte)
+            .collect();
+        Ok(self.algorithm.compute(&data))
+    }
+
+    /// Checksum stored in the binary.
+    pub fn stored<R: Read + Seek>(&self, bin: &mut R) -> std::io::Result<u32> {
+        let mut bytes = vec![0u8; self.algorithm.size()];
+        bin.seek(SeekFrom::Start(self.address))?;
+        bin.read_exact(&mut bytes)?;
+        if !self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes.iter().fold(0, |value, &b| value << 8 | b as u32))
+    }
+
+    /// Work out the checksum again and store it.
+    pub fn update<B: Read + Write + Seek>(&self, bin: &mut B) -> std::io::Result<()> {
+        let checksum = self.compute(bin)?;
+        let size = self.algorithm.size();
+        let mut bytes = checksum.to_be_bytes()[4 - size..].to_vec();
+        if !self.big_endian {
+            bytes.reverse();
+        }
+        bin.seek(SeekFrom::Start(self.address))?;
+        bin.write_all(&bytes)
+    }
+
+    /// One line saying whether the stored checksum matches the region.
+    pub fn verify<R: Read + Seek>(&self, bin: &mut R) -> std::io::Result<String> {
+        let width = self.algorithm.size() * 2;
+        let computed = self.compute(bin)?;
+        let stored = self.stored(bin)?;
+        Ok(if computed == stored {
+            format!("{}: pass, 0x{computed:0width$X}", self.name)
+        } else {
+            format!(
+                "{}: FAIL, stored 0x{stored:0width$X}, computed 0x{computed:0width$X}",
+                self.name
+            )
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Checksums {
+    /// Store every region's checksum again after each write
+    #[serde(default)]
+    pub update_on_write: bool,
+    #[serde(default)]
+    pub regions: Vec<Region>,
+}
+
+impl Checksums {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Store the checksum of every region again when enabled, returning the bytes written.
+    pub fn update_after_write<B: Read + Write + Seek>(
+        &self,
+        bin: &mut B,
+    ) -> std::io::Result<Vec<Range<u64>>> {
+        if !self.update_on_write {
+            return Ok(Vec::new());
+        }
+        for region in &self.regions {
+            region.update(bin)?;
+        }
+        Ok(self.regions.iter().map(Region::stored_range).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn check_values() {
+        assert_eq!(Algorithm::Crc32.compute(CHECK), 0xCBF4_3926);
+        assert_eq!(Algorithm::Crc16Ccitt.compute(CHECK), 0x29B1);
+        assert_eq!(Algorithm::Sum8.compute(CHECK), 0xDD);
+        assert_eq!(Algorithm::Sum16.compute(CHECK), 0x01DD);
+        assert_eq!(Algorithm::Sum32.compute(CHECK), 0x01DD);
+        assert_eq!(Algorithm::Crc32.compute(&[]), 0);
+        assert_eq!(Algorithm::Crc16Ccitt.compute(&[]), 0xFFFF);
+    }
+
+    #[test]
+    fn sums_wrap() {
+        let data = vec![0xFF; 0x102];
+        assert_eq!(Algorithm::Sum8.compute(&data), 0xFE);
+        assert_eq!(Algorithm::Sum16.compute(&data), 0x00FE);
+        assert_eq!(Algorithm::Sum32.compute(&data), 0x0001_00FE);
+    }
+
+    fn region(algorithm: Algorithm, big_endian: bool) -> Region {
+        Region {
+            name: "Main".to_string(),
+            start: 0,
+            end: 13,
+            address: 9,
+            algorithm,
+            big_endian,
+        }
+    }
+
+    #[test]
+    fn stored_checksum_skipped() {
+        let mut bin = Cursor::new([CHECK, &[0xAA; 4]].concat());
+        let region = region(Algorithm::Crc32, false);
+        assert_eq!(region.compute(&mut bin).unwrap(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn update_then_verify() {
+        for big_endian in [false, true] {
+            let mut bin = Cursor::new([CHECK, &[0; 4]].concat());
+            let region = region(Algorithm::Crc32, big_endian);
+            assert!(region.verify(&mut bin).unwrap().contains("FAIL"));
+            region.update(&mut bin).unwrap();
+            let stored = &bin.get_ref()[9..];
+            if big_endian {
+                assert_eq!(stored, [0xCB, 0xF4, 0x39, 0x26]);
+            } else {
+                assert_eq!(stored, [0x26, 0x39, 0xF4, 0xCB]);
+            }
+            assert_eq!(region.stored(&mut bin).unwrap(), 0xCBF4_3926);
+            assert_eq!(region.verify(&mut bin).unwrap(), "Main: pass, 0xCBF43926");
+        }
+    }
+
+    #[test]
+    fn parse_addresses() {
+        let checksums: Checksums = serde_json::from_str(
+            r#"{ "regions": [
+                { "name": "A", "start": 16, "end": "0x20", "address": "0X20", "algorithm": "crc16-ccitt" }
+            ] }"#,
+        )
+        .unwrap();
+        assert!(!checksums.update_on_write);
+        let region = &checksums.regions[0];
+        assert_eq!((region.start, region.end, region.address), (16, 32, 32));
+        assert_eq!(region.algorithm, Algorithm::Crc16Ccitt);
+        assert_eq!(region.stored_range(), 32..34);
+        assert!(serde_json::from_str::<Checksums>(
+            r#"{ "regions": [{ "name": "A", "start": "x", "end": 1, "address": 1, "algorithm": "sum8" }] }"#
+        )
+        .is_err());
+    }
+}
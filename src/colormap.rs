@@ -0,0 +1,135 @@
+//! Colour scales used to show values on the 3D chart and the cell heatmap.
+
+use std::fmt::Display;
+
+use plotters::style::RGBColor;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Colormap {
+    #[default]
+    Viridis,
+    Plasma,
+    Turbo,
+    /// Green for low values through to red for high values
+    RedGreen,
+}
+
+const VIRIDIS: [(u8, u8, u8); 5] = [
+    (0x44, 0x01, 0x54),
+    (0x3b, 0x52, 0x8b),
+    (0x21, 0x91, 0x8c),
+    (0x5e, 0xc9, 0x62),
+    (0xfd, 0xe7, 0x25),
+];
+const PLASMA: [(u8, u8, u8); 5] = [
+    (0x0d, 0x08, 0x87),
+    (0x7e, 0x03, 0xa8),
+    (0xcc, 0x47, 0x78),
+    (0xf8, 0x95, 0x40),
+    (0xf0, 0xf9, 0x21),
+];
+const TURBO: [(u8, u8, u8); 7] = [
+    (0x30, 0x12, 0x3b),
+    (0x46, 0x86, 0xfb),
+    (0x1a, 0xe4, 0xb6),
+    (0xa2, 0xfc, 0x3c),
+    (0xfa, 0xba, 0x39),
+    (0xe4, 0x46, 0x0a),
+    (0x7a, 0x04, 0x03),
+];
+const RED_GREEN: [(u8, u8, u8); 2] = [(0x00, 0xff, 0x00), (0xff, 0x00, 0x00)];
+
+impl Colormap {
+    pub const ALL: [Colormap; 4] = [Self::Viridis, Self::Plasma, Self::Turbo, Self::RedGreen];
+
+    fn points(self) -> &'static [(u8, u8, u8)] {
+        match self {
+            Self::Viridis => &VIRIDIS,
+            Self::Plasma => &PLASMA,
+            Self::Turbo => &TURBO,
+            Self::RedGreen => &RED_GREEN,
+        }
+    }
+
+    /// Colour for `t` from 0 to 1, linearly interpolated between the control points.
+    pub fn map(&self, t: f64) -> RGBColor {
+        let points = self.points();
+        let scaled = t.clamp(0.0, 1.0) * (points.len() - 1) as f64;
+        let i = (scaled.floor() as usize).min(points.len() - 2);
+        let f = scaled - i as f64;
+        let (a, b) = (points[i], points[i + 1]);
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+        RGBColor(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+    }
+
+    /// Same as [`Colormap::map`] as an iced colour with the given opacity.
+    pub fn iced_color(&self, t: f64, alpha: f32) -> iced::Color {
+        let RGBColor(r, g, b) = self.map(t);
+        iced::Color::from_rgba8(r, g, b, alpha)
+    }
+
+    /// Parse the name used on the command line, such as `viridis` or `red-green`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.to_string().eq_ignore_ascii_case(name))
+    }
+}
+
+impl Display for Colormap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Viridis => "Viridis",
+            Self::Plasma => "Plasma",
+            Self::Turbo => "Turbo",
+            Self::RedGreen => "Red-Green",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb((r, g, b): (u8, u8, u8)) -> RGBColor {
+        RGBColor(r, g, b)
+    }
+
+    #[test]
+    fn endpoints_are_first_and_last_points() {
+        for colormap in Colormap::ALL {
+            let points = colormap.points();
+            assert_eq!(colormap.map(0.0), rgb(points[0]), "{colormap}");
+            assert_eq!(
+                colormap.map(1.0),
+                rgb(points[points.len() - 1]),
+                "{colormap}"
+            );
+            // Values outside 0 to 1 are clamped
+            assert_eq!(colormap.map(-2.0), colormap.map(0.0), "{colormap}");
+            assert_eq!(colormap.map(3.0), colormap.map(1.0), "{colormap}");
+        }
+    }
+
+    #[test]
+    fn midpoints() {
+        assert_eq!(Colormap::Viridis.map(0.5), rgb(VIRIDIS[2]));
+        assert_eq!(Colormap::Plasma.map(0.5), rgb(PLASMA[2]));
+        assert_eq!(Colormap::Turbo.map(0.5), rgb(TURBO[3]));
+        // Halfway between the two points, rounded
+        assert_eq!(Colormap::RedGreen.map(0.5), RGBColor(0x80, 0x80, 0x00));
+    }
+
+    #[test]
+    fn between_points() {
+        // A quarter of the way from the first point to the second
+        assert_eq!(Colormap::Viridis.map(0.0625), RGBColor(0x42, 0x15, 0x62));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Colormap::from_name("red-green"), Some(Colormap::RedGreen));
+        assert_eq!(Colormap::from_name("TURBO"), Some(Colormap::Turbo));
+        assert_eq!(Colormap::from_name("jet"), None);
+    }
+}
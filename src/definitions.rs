@@ -15,13 +15,20 @@ use core::f64;
 use std::{
     collections::HashMap,
     io::{Read, Seek, Write},
+    ops::{Range, RangeInclusive},
 };
 
 use xdftuneparser::data_types::*;
 
-use crate::eval::{eval, eval_reverse};
+use crate::eval::{self, eval, eval_reverse};
 
-fn bytes_to_u32(bytes: &[u8]) -> u32 {
+/// Whether two byte ranges share at least one byte.
+pub fn ranges_overlap(a: &Range<u64>, b: &Range<u64>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Little endian integer from up to 4 bytes.
+pub fn bytes_to_u32(bytes: &[u8]) -> u32 {
     let mut final_bytes = [0; 4];
     if bytes.len() > 4 {
         panic!("too big");
@@ -33,6 +40,219 @@ fn bytes_to_u32(bytes: &[u8]) -> u32 {
     u32::from_be_bytes(final_bytes)
 }
 
+/// Whether bit `n` of `raw` is set.
+pub fn bit(raw: u32, n: u8) -> bool {
+    raw >> n & 1 == 1
+}
+
+/// `raw` with only bit `n` changed.
+pub fn with_bit(raw: u32, n: u8, on: bool) -> u32 {
+    if on {
+        raw | 1 << n
+    } else {
+        raw & !(1 << n)
+    }
+}
+
+/// Bits documented in a description as `bit 0: launch control, bit 3: hardcut`, one per line or
+/// separated by `,` or `;`. `None` when there are none, bits past `size` bytes are left out.
+fn bit_labels(description: &str, size: usize) -> Option<Vec<(u8, String)>> {
+    let labels: Vec<(u8, String)> = description
+        .split(['\n', ',', ';'])
+        .filter_map(|part| {
+            let part = part.trim();
+            if !part.get(..4)?.eq_ignore_ascii_case("bit ") {
+                return None;
+            }
+            let (n, label) = part[4..].split_once([':', '='])?;
+            let n: u8 = n.trim().parse().ok()?;
+            let label = label.trim();
+            ((n as usize) < size.min(4) * 8 && !label.is_empty()).then(|| (n, label.to_string()))
+        })
+        .collect();
+    (!labels.is_empty()).then_some(labels)
+}
+
+/// `mmedtypeflags` bit of values stored as two's complement
+const TYPE_SIGNED: u32 = 0x01;
+/// `mmedtypeflags` bit of values stored least significant byte first
+const TYPE_LSB_FIRST: u32 = 0x02;
+/// `mmedtypeflags` bit of floating point values
+const TYPE_FLOAT: u32 = 0x10000;
+
+/// How a stored value is decoded next to what the XDF type flags ask for, for finding out why
+/// a value reads the way it does.
+fn storage_report(
+    size: usize,
+    big_endian: bool,
+    type_flags: Option<u32>,
+    signed: bool,
+    flag: bool,
+) -> String {
+    let asked = |bit: u32| type_flags.map(|flags| flags & bit != 0);
+    let note = |asked: Option<bool>, yes: &str, no: &str| match asked {
+        Some(true) => format!(", the XDF asks for {yes}"),
+        Some(false) => format!(", the XDF asks for {no}"),
+        None => String::new(),
+    };
+    let flags = type_flags.map_or("not given".to_string(), |f| format!("0x{f:X}"));
+    let mask = if flag {
+        "0x01, 1 bit elements".to_string()
+    } else {
+        "none, masks are not read from the XDF".to_string()
+    };
+    [
+        format!("Element size: {size} bytes"),
+        format!(
+            "Byte order: {}{}",
+            if big_endian {
+                "big endian"
+            } else {
+                "little endian"
+            },
+            note(
+                asked(TYPE_LSB_FIRST),
+                "LSB first",
+                "MSB first, which is not supported"
+            )
+        ),
+        format!(
+            "Signed: {signed}{}",
+            note(asked(TYPE_SIGNED), "signed", "unsigned")
+        ),
+        format!(
+            "Type: integer{}",
+            note(
+                asked(TYPE_FLOAT),
+                "float, which is not supported",
+                "integer"
+            )
+        ),
+        format!("Mask: {mask}"),
+        format!("mmedtypeflags: {flags}"),
+    ]
+    .join("\n")
+}
+
+/// Inverse of [`bytes_to_u32`], only keeps the lowest `size` bytes.
+fn u32_to_bytes(value: u32, size: usize) -> Vec<u8> {
+    if size > 4 {
+        panic!("too big");
+    }
+    value.to_le_bytes()[..size].to_vec()
+}
+
+/// Largest raw value that fits in `size` bytes (1 to 4, 3 byte values are allowed).
+fn raw_max(size: usize) -> u32 {
+    if size >= 4 {
+        u32::MAX
+    } else {
+        (1 << (size * 8)) - 1
+    }
+}
+
+/// Stored integer as a number, read as two's complement when `signed`.
+fn decode_raw(raw: u32, size: usize, signed: bool) -> f64 {
+    let bits = size.min(4) as u32 * 8;
+    if signed && bits > 0 {
+        ((raw << (32 - bits)) as i32 >> (32 - bits)) as f64
+    } else {
+        raw as f64
+    }
+}
+
+/// Smallest and largest stored integer that fit in `size` bytes.
+fn raw_limits(size: usize, signed: bool) -> (f64, f64) {
+    let max = raw_max(size) as f64;
+    if signed {
+        let half = (max + 1.0) / 2.0;
+        (-half, half - 1.0)
+    } else {
+        (0.0, max)
+    }
+}
+
+/// Convert a human readable value to its raw integer form, clamped to what fits in `size` bytes.
+/// Fails when the expression cannot be solved for its variable.
+fn value_to_raw(expression: &str, val: f64, size: usize, signed: bool) -> Result<u32, String> {
+    let (min, max) = raw_limits(size, signed);
+    let raw = eval_reverse(expression, val)
+        .map_err(|e| format!("\"{expression}\" cannot be converted back, {e}"))?
+        .round()
+        .clamp(min, max) as i64;
+    Ok(raw as u32 & raw_max(size))
+}
+
+fn invalid_input(error: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+}
+
+/// Whether every value is a whole number, allowing for floating point error from the expression.
+fn all_integers(values: &[f64]) -> bool {
+    values
+        .iter()
+        .all(|v| v.is_finite() && (v - v.round()).abs() < 1e-6)
+}
+
+/// Raw value as zero padded hex with a `0x` prefix, two digits per byte.
+pub fn format_raw_hex(raw: u32, size: usize) -> String {
+    format!("0x{:0width$X}", raw, width = size * 2)
+}
+
+/// Parse a raw value, `0x` prefixed values are hex and anything else is decimal.
+/// Values that do not fit in `size` bytes are rejected.
+pub fn parse_raw(value: &str, size: usize) -> Option<u32> {
+    let value = value.trim();
+    let raw = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => value.parse().ok()?,
+    };
+    (raw <= raw_max(size)).then_some(raw)
+}
+
+/// Whether typed text is a `0x` prefixed stored integer rather than a converted value.
+pub fn is_hex_entry(value: &str) -> bool {
+    let value = value.trim();
+    value.starts_with("0x") || value.starts_with("0X")
+}
+
+/// Insert `,` between groups of three digits in the integer part of a number,
+/// anything that is not a plain decimal number is returned unchanged.
+pub fn group_thousands(value: &str) -> String {
+    let (sign, unsigned) = match value.strip_prefix('-') {
+        Some(v) => ("-", v),
+        None => ("", value),
+    };
+    let (int, fraction) = match unsigned.split_once('.') {
+        Some((int, fraction)) => (int, Some(fraction)),
+        None => (unsigned, None),
+    };
+    if int.is_empty() || !int.bytes().all(|b| b.is_ascii_digit()) {
+        return value.to_string();
+    }
+
+    let mut grouped = String::from(sign);
+    for (i, digit) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+/// Undo [`group_thousands`] so typed values can be parsed.
+pub fn strip_thousands(value: &str) -> String {
+    value.replace(',', "")
+}
+
 /// Binary definition metadata
 #[derive(Debug, Clone)]
 pub struct DefinitionInfo {
@@ -70,10 +290,23 @@ pub struct Scalar {
     pub size: usize,
     /// Equation to convert between integer representation and human readable value
     pub expression: String,
+    pub units: String,
+    /// Read the stored integer as two's complement, set by the user when the XDF leaves it out
+    pub signed: bool,
     pub categories: Vec<u32>,
+    /// Decimals shown, every one of them when `None`
+    pub decimals: Option<usize>,
+    /// `mmedtypeflags` as given in the XDF, only shown to explain how the value is decoded
+    pub type_flags: Option<u32>,
+    /// Names of the bits of a bitfield, taken from `bit N: name` lines of the description
+    pub bit_labels: Option<Vec<(u8, String)>>,
 }
 
 impl Scalar {
+    /// Bytes of the binary holding the value.
+    pub fn byte_range(&self) -> Range<u64> {
+        self.address..self.address + self.size as u64
+    }
     pub fn from_xdf(xdf: XDFConstant) -> Self {
         let edata = xdf.embedded_data.unwrap();
         let math = xdf.math.unwrap();
@@ -82,8 +315,12 @@ impl Scalar {
         let name = xdf.title.unwrap_or_default();
         let description = xdf.description.unwrap_or_default();
         let expression = math.expression.unwrap_or_default();
+        let units = xdf.unit.unwrap_or_default();
+        let type_flags = edata.mmedtypeflags;
         let categories = xdf.catmem.into_iter().filter_map(|c| c.category).collect();
 
+        let bit_labels = bit_labels(&description, size);
+
         Self {
             categories,
             name,
@@ -91,24 +328,106 @@ impl Scalar {
             address,
             size,
             expression,
+            units,
+            signed: false,
+            decimals: None,
+            type_flags,
+            bit_labels,
         }
     }
 
-    pub fn read<R: Read + Seek>(&self, bin: &mut R) -> Result<f64, std::io::Error> {
+    /// Element size, byte order, signedness and type the value is decoded with.
+    pub fn storage(&self) -> String {
+        storage_report(self.size, false, self.type_flags, self.signed, false)
+    }
+
+    /// Format a value with the chosen decimals.
+    pub fn format(&self, value: f64) -> String {
+        match self.decimals {
+            Some(decimals) => format!("{value:.decimals$}"),
+            None => value.to_string(),
+        }
+    }
+
+    /// Values the stored integer can convert to and the change from one raw step to the next.
+    /// `None` for 4 byte values, whose range is too wide to pick from, or when the expression
+    /// does not give finite values at the ends of the range.
+    pub fn slider_range(&self) -> Option<(RangeInclusive<f64>, f64)> {
+        if self.size >= 4 {
+            return None;
+        }
+        let (low, high) = raw_limits(self.size, self.signed);
+        let (first, last) = (eval(&self.expression, low), eval(&self.expression, high));
+        let step = (eval(&self.expression, low + 1.0) - first).abs();
+        if !(first.is_finite() && last.is_finite() && step.is_finite()) || step == 0.0 {
+            return None;
+        }
+        Some((first.min(last)..=first.max(last), step))
+    }
+
+    /// Whether `value` converts to a stored integer that fits without being clamped.
+    pub fn fits(&self, value: f64) -> bool {
+        let (low, high) = raw_limits(self.size, self.signed);
+        eval_reverse(&self.expression, value).is_ok_and(|raw| (low..=high).contains(&raw.round()))
+    }
+
+    /// Smallest and largest value that can be stored.
+    pub fn value_limits(&self) -> (f64, f64) {
+        let (low, high) = raw_limits(self.size, self.signed);
+        let (first, last) = (eval(&self.expression, low), eval(&self.expression, high));
+        (first.min(last), first.max(last))
+    }
+
+    /// Closest value to `value` that can be stored.
+    pub fn snap(&self, value: f64) -> f64 {
+        self.to_raw(value).map_or(value, |raw| self.from_raw(raw))
+    }
+
+    /// Change in value from the stored integer `raw` to the next one up. Worked out at `raw`
+    /// so non-linear expressions are followed, 0 at the largest integer that fits.
+    pub fn step_at(&self, raw: u64) -> f64 {
+        let raw = u32::try_from(raw).unwrap_or(u32::MAX) & raw_max(self.size);
+        self.from_raw(self.offset_raw(raw, 1)) - self.from_raw(raw)
+    }
+
+    /// Stored integer `steps` away from `raw`, clamped to what fits in the scalar's size.
+    pub fn offset_raw(&self, raw: u32, steps: i64) -> u32 {
+        let (min, max) = raw_limits(self.size, self.signed);
+        let stepped = (decode_raw(raw, self.size, self.signed) + steps as f64).clamp(min, max);
+        stepped as i64 as u32 & raw_max(self.size)
+    }
+
+    /// Raw stored form of a value, fails when the expression cannot be converted back.
+    pub fn to_raw(&self, value: f64) -> Result<u32, String> {
+        value_to_raw(&self.expression, value, self.size, self.signed)
+    }
+
+    /// Human readable form of a raw value.
+    pub fn from_raw(&self, raw: u32) -> f64 {
+        eval(&self.expression, decode_raw(raw, self.size, self.signed))
+    }
+
+    pub fn read_raw<R: Read + Seek>(&self, bin: &mut R) -> Result<u32, std::io::Error> {
         bin.seek(std::io::SeekFrom::Start(self.address))?;
         let mut buf = vec![0u8; self.size];
         bin.read_exact(&mut buf)?;
-        Ok(eval(&self.expression, bytes_to_u32(&buf)))
+        Ok(bytes_to_u32(&buf))
+    }
+
+    pub fn read<R: Read + Seek>(&self, bin: &mut R) -> Result<f64, std::io::Error> {
+        Ok(self.from_raw(self.read_raw(bin)?))
+    }
+
+    /// Write a raw value directly, bypassing the expression.
+    pub fn write_raw<W: Write + Seek>(&self, bin: &mut W, raw: u32) -> Result<(), std::io::Error> {
+        bin.seek(std::io::SeekFrom::Start(self.address))?;
+        bin.write_all(&u32_to_bytes(raw.min(raw_max(self.size)), self.size))
     }
 
     pub fn write<W: Write + Seek>(&self, bin: &mut W, val: f64) -> Result<(), std::io::Error> {
+        let raw = self.to_raw(val).map_err(invalid_input)?;
         bin.seek(std::io::SeekFrom::Start(self.address))?;
-        let bytes = (eval_reverse(&self.expression, val).round() as u32).to_be_bytes();
-        let mut buf = vec![];
-        for i in 0..self.size {
-            buf.push(bytes[bytes.len() - i - 1]);
-        }
-        bin.write_all(&mut buf)
+        bin.write_all(&u32_to_bytes(raw, self.size))
     }
 }
 
@@ -120,20 +439,177 @@ pub enum AxisData {
     /// Axis data defined in binary
     Binary {
         address: u64,
-        /// Size in bytes of one element (max 8 with current implementation)
+        /// Size in bytes of one element (max 4 with current implementation)
         element_size: usize,
+        /// Elements are stored most significant byte first, XDF axes are always little endian
+        big_endian: bool,
         /// Total number of elements, should equal product of rows and columns
         count: usize,
         /// Equation to convert betwen integer representation and human readable value
         expression: String,
+        /// Layout of padded rows, `None` when elements are packed
+        row_stride: Option<RowStride>,
+        /// Read elements as two's complement, set by the user when the XDF leaves it out
+        signed: bool,
+        /// Applied on top of the expression for display, never stored in the XDF
+        display: DisplayScale,
+        /// Elements are on/off flags in the lowest bit, set for 1 bit elements which are
+        /// read from a whole byte
+        flag: bool,
+        /// Decimals shown, picked by the user over the ones worked out from the expression
+        decimals: Option<usize>,
+        /// `mmedtypeflags` as given in the XDF, only shown to explain how values are decoded
+        type_flags: Option<u32>,
     },
 }
 
+/// Scale and offset applied to converted values, to show a stored fraction as a percentage
+/// without changing the definition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayScale {
+    pub scale: f64,
+    pub offset: f64,
+}
+
+impl Default for DisplayScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+impl DisplayScale {
+    /// Displayed value of a converted one.
+    pub fn apply(self, value: f64) -> f64 {
+        value * self.scale + self.offset
+    }
+    /// Converted value of a displayed one.
+    pub fn reverse(self, value: f64) -> f64 {
+        (value - self.offset) / self.scale
+    }
+}
+
+/// Rows that are not stored back to back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowStride {
+    /// Number of elements in one row
+    pub columns: usize,
+    /// Bytes from the start of one row to the start of the next
+    pub bytes: usize,
+}
+
+impl AxisData {
+    /// File offset of the element at `index`, `None` for user defined axes or past the end.
+    pub fn element_address(&self, index: usize) -> Option<u64> {
+        match self {
+            AxisData::User(_) => None,
+            AxisData::Binary {
+                address,
+                element_size,
+                count,
+                row_stride,
+                ..
+            } => (index < *count).then(|| {
+                let offset = match row_stride {
+                    Some(stride) => {
+                        (index / stride.columns) * stride.bytes
+                            + (index % stride.columns) * element_size
+                    }
+                    None => index * element_size,
+                };
+                address + offset as u64
+            }),
+        }
+    }
+
+    /// Indices where a new contiguous run of elements starts.
+    fn is_run_start(&self, index: usize) -> bool {
+        match self {
+            AxisData::Binary {
+                row_stride: Some(stride),
+                ..
+            } => index.is_multiple_of(stride.columns),
+            _ => index == 0,
+        }
+    }
+}
+
+/// Conversion expression of an axis with its storage variable renamed to `X`.
+/// Extra variables referencing other data are not supported yet and are replaced by zero.
+/// Problems that make values read or write wrong are described in `warnings`.
+fn axis_expression(math: Math, warnings: &mut Vec<String>) -> String {
+    let mut expression = math.expression.unwrap();
+    for var in math.vars.iter().skip(1) {
+        warnings.push(format!(
+            "variable {var} in \"{expression}\" refers to other data which is not supported, it is read as 0 so values are wrong"
+        ));
+        expression = expression.replace(var.as_str(), "(0)");
+    }
+    // Because we only allow one variable normalize it to 'X'
+    let expression = match math.vars.first() {
+        Some(var) => expression.replace(var.as_str(), "X"),
+        None => expression,
+    };
+    if let Some(warning) = constant_warning(&expression) {
+        warnings.push(warning);
+    }
+    expression
+}
+
+/// Warning for an expression that does not use its variable. A constant mislabeled as stored
+/// data reads the same value for every element and cannot be converted back when writing.
+fn constant_warning(expression: &str) -> Option<String> {
+    eval::parse(expression)
+        .is_ok_and(|e| !e.uses_var())
+        .then(|| format!("\"{expression}\" does not use its variable, every value reads the same and cannot be written"))
+}
+
+/// Values of a user defined axis and the names of its labels. When `strict`, labels that are
+/// not numbers are described in `errors` and read as NaN. Otherwise axes with a text label are
+/// categorical, every label is then named and placed at its index, and empty labels read as 0.
+fn user_axis(
+    labels: &[Option<&str>],
+    strict: bool,
+    errors: &mut Vec<String>,
+) -> (Vec<f64>, Vec<String>) {
+    let text = |l: &Option<&str>| l.unwrap_or_default().trim().to_string();
+    let categorical = labels
+        .iter()
+        .map(text)
+        .any(|t| !t.is_empty() && t.parse::<f64>().is_err());
+    if categorical && !strict {
+        let values = (0..labels.len()).map(|i| i as f64).collect();
+        return (values, labels.iter().map(text).collect());
+    }
+    let values = labels
+        .iter()
+        .enumerate()
+        .map(|(i, l)| match l.map(|v| v.trim().parse()) {
+            None => 0.0,
+            Some(Ok(value)) => value,
+            Some(Err(_)) if strict => {
+                errors.push(match text(l) {
+                    t if t.is_empty() => format!("label {i} is empty"),
+                    t => format!("label {i} \"{t}\" is not a number"),
+                });
+                f64::NAN
+            }
+            Some(Err(_)) => 0.0,
+        })
+        .collect();
+    (values, Vec::new())
+}
+
 /// Axis of a table
 #[derive(Debug, Clone)]
 pub struct Axis {
     pub units: String,
     pub data: AxisData,
+    /// Label names of a categorical user defined axis, whose values are the label indices.
+    /// Empty for numeric axes.
+    pub names: Vec<String>,
 }
 
 impl Axis {
@@ -143,46 +619,205 @@ impl Axis {
             AxisData::Binary { count, .. } => *count,
         }
     }
+    /// Bytes from the first element to the end of the last one, including any row padding.
+    /// `None` for user defined axes and axes without elements.
+    pub fn byte_range(&self) -> Option<Range<u64>> {
+        let first = self.data.element_address(0)?;
+        let last = self.data.element_address(self.len() - 1)?;
+        Some(first..last + self.element_size()? as u64)
+    }
+    /// Size of one stored element, `None` for user defined axes.
+    pub fn element_size(&self) -> Option<usize> {
+        match &self.data {
+            AxisData::User(_) => None,
+            AxisData::Binary { element_size, .. } => Some(*element_size),
+        }
+    }
+    /// Raw stored form of a value, `None` for user defined axes and expressions that cannot be
+    /// converted back.
+    pub fn to_raw(&self, value: f64) -> Option<u32> {
+        match &self.data {
+            AxisData::User(_) => None,
+            AxisData::Binary {
+                element_size,
+                expression,
+                signed,
+                display,
+                ..
+            } => value_to_raw(expression, display.reverse(value), *element_size, *signed).ok(),
+        }
+    }
+    /// Human readable form of a raw value, `None` for user defined axes.
+    pub fn from_raw(&self, raw: u32) -> Option<f64> {
+        match &self.data {
+            AxisData::User(_) => None,
+            AxisData::Binary {
+                element_size,
+                expression,
+                signed,
+                display,
+                ..
+            } => Some(display.apply(eval(expression, decode_raw(raw, *element_size, *signed)))),
+        }
+    }
+    /// Format a value with the axis' display precision.
+    pub fn format(&self, value: f64) -> String {
+        match self.precision() {
+            Some(p) => format!("{:.p$}", value),
+            None => value.to_string(),
+        }
+    }
+    pub fn signed(&self) -> bool {
+        matches!(self.data, AxisData::Binary { signed: true, .. })
+    }
+    pub fn display(&self) -> DisplayScale {
+        match &self.data {
+            AxisData::User(_) => DisplayScale::default(),
+            AxisData::Binary { display, .. } => *display,
+        }
+    }
+    /// Decimals picked by the user, `None` when worked out from the expression
+    pub fn decimals(&self) -> Option<usize> {
+        match &self.data {
+            AxisData::User(_) => None,
+            AxisData::Binary { decimals, .. } => *decimals,
+        }
+    }
+    /// Change how converted values are displayed, does nothing for user defined axes.
+    pub fn set_display(&mut self, value: DisplayScale) {
+        if let AxisData::Binary { display, .. } = &mut self.data {
+            *display = value;
+        }
+    }
+    /// Show values with a fixed number of decimals, or the ones worked out from the expression
+    /// with `None`. Does nothing for user defined axes.
+    pub fn set_decimals(&mut self, value: Option<usize>) {
+        if let AxisData::Binary { decimals, .. } = &mut self.data {
+            *decimals = value;
+        }
+    }
+    /// Change whether elements are read as two's complement, does nothing for user defined axes.
+    pub fn set_signed(&mut self, value: bool) {
+        if let AxisData::Binary { signed, .. } = &mut self.data {
+            *signed = value;
+        }
+    }
     pub fn writeable(&self) -> bool {
         match self.data {
             AxisData::User(_) => false,
             AxisData::Binary { .. } => true,
         }
     }
+    /// Smallest and largest stored integer, `None` for user defined axes.
+    pub fn raw_range(&self) -> Option<(f64, f64)> {
+        match &self.data {
+            AxisData::User(_) => None,
+            AxisData::Binary { flag: true, .. } => Some((0.0, 1.0)),
+            AxisData::Binary {
+                element_size,
+                signed,
+                ..
+            } => Some(raw_limits(*element_size, *signed)),
+        }
+    }
+    /// Whether elements are on/off flags, stored as 0 and 1 and converted to 0 and 1. Decided
+    /// by the definition and never the stored values, a numeric map that happens to only
+    /// hold 0 and 1 is still edited as numbers.
+    pub fn is_boolean(&self) -> bool {
+        self.raw_range() == Some((0.0, 1.0))
+            && self.from_raw(0) == Some(0.0)
+            && self.from_raw(1) == Some(1.0)
+    }
+    /// Conversion from the stored integer, `None` for user defined axes.
+    pub fn expression(&self) -> Option<&str> {
+        match &self.data {
+            AxisData::User(_) => None,
+            AxisData::Binary { expression, .. } => Some(expression),
+        }
+    }
     pub fn range(&self) -> Option<(f64, f64)> {
-        if let AxisData::Binary {
-            element_size,
-            expression,
-            ..
-        } = &self.data
+        if let (
+            AxisData::Binary {
+                expression,
+                display,
+                ..
+            },
+            Some((min, max)),
+        ) = (&self.data, self.raw_range())
         {
-            let mut bytes = [0u8; 4];
-            for i in 0..*element_size {
-                bytes[i] = 0xFF;
-            }
-
-            let num = u32::from_be_bytes(bytes);
-
-            Some((eval(&expression, 0), eval(&expression, num)))
+            Some((
+                display.apply(eval(&expression, min)),
+                display.apply(eval(&expression, max)),
+            ))
         } else {
             None
         }
     }
     pub fn precision(&self) -> Option<usize> {
-        if let AxisData::Binary { expression, .. } = &self.data {
+        if let AxisData::Binary {
+            decimals: Some(decimals),
+            ..
+        } = &self.data
+        {
+            Some(*decimals)
+        } else if let AxisData::Binary {
+            expression,
+            display,
+            ..
+        } = &self.data
+        {
             let avg = (0..20)
-                .map(|n| eval(&expression, n))
+                .map(|n| display.apply(eval(&expression, n)))
                 .map_windows(|[a, b]| (a - b).abs())
                 .reduce(|a, e| a + e)
                 .unwrap()
                 / 20.0;
 
+            // Constant expressions have no step to derive a precision from
+            if avg == 0.0 || !avg.is_finite() {
+                return None;
+            }
             Some(avg.recip().log10().round() as usize + 1)
         } else {
             None
         }
     }
-    pub fn from_xdf(xdf: XDFAxis, linked: Option<&HashMap<u32, (EmbeddedData, Math)>>) -> Self {
+    /// Element size, byte order, signedness and type the values are decoded with, `None` for
+    /// user defined axes.
+    pub fn storage(&self) -> Option<String> {
+        match &self.data {
+            AxisData::User(_) => None,
+            AxisData::Binary {
+                element_size,
+                big_endian,
+                signed,
+                flag,
+                type_flags,
+                ..
+            } => Some(storage_report(
+                *element_size,
+                *big_endian,
+                *type_flags,
+                *signed,
+                *flag,
+            )),
+        }
+    }
+    /// Name shown for the value at `index` of a categorical axis
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names.get(index).map(String::as_str)
+    }
+    /// Labels of user defined axes that are not numbers are described in `errors` and read as
+    /// NaN when `strict`, see [`user_axis`]. Unsupported expressions are described in
+    /// `warnings`.
+    pub fn from_xdf(
+        xdf: XDFAxis,
+        linked: Option<&HashMap<u32, (EmbeddedData, Math)>>,
+        strict: bool,
+        errors: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) -> Self {
+        let mut names = Vec::new();
         // If there are no labels this must be an internally defined axis
         let data = if xdf.labels.is_empty() {
             let mut edata = xdf.embeddeddata.unwrap();
@@ -203,8 +838,6 @@ impl Axis {
                 panic!("Found no valid embed data for data axis.");
             };
 
-            assert_eq!(math.vars.len(), 1);
-
             let address = edata.mmedaddress.unwrap() as u64;
 
             let count = if let Some(c) = xdf.count {
@@ -218,66 +851,138 @@ impl Axis {
             } as usize;
 
             // Element size must be defined or we might was well display random numbers.
-            let element_size = edata.mmedelementsizebits.unwrap() as usize / 8;
+            let element_bits = edata.mmedelementsizebits.unwrap() as usize;
+            let element_size = element_bits.div_ceil(8);
 
-            // Because we only allow one variable normalize it to 'X'
-            let expression = math.expression.unwrap().replace(math.vars[0].as_str(), "X");
+            // Rows padded to a larger stride, only meaningful with more than one row
+            let row_stride = match (edata.mmedmajorstridebits, edata.mmedcolcount) {
+                (Some(bits), Some(columns))
+                    if bits > 0 && columns > 0 && count > columns as usize =>
+                {
+                    let stride = RowStride {
+                        columns: columns as usize,
+                        bytes: bits as usize / 8,
+                    };
+                    (stride.bytes != stride.columns * element_size).then_some(stride)
+                }
+                _ => None,
+            };
+
+            let expression = axis_expression(math, warnings);
 
             AxisData::Binary {
                 address,
                 element_size,
                 count,
                 expression,
+                row_stride,
+                big_endian: false,
+                signed: false,
+                display: DisplayScale::default(),
+                flag: element_bits == 1,
+                decimals: None,
+                type_flags: edata.mmedtypeflags,
             }
         } else {
-            AxisData::User(
-                xdf.labels
-                    .iter()
-                    .map(|l| {
-                        l.value
-                            .as_ref()
-                            .map_or(0.0, |s| s.parse().unwrap_or_default())
-                    })
-                    .collect(),
-            )
+            let values;
+            let labels: Vec<Option<&str>> = xdf.labels.iter().map(|l| l.value.as_deref()).collect();
+            (values, names) = user_axis(&labels, strict, errors);
+            AxisData::User(values)
         };
 
         Self {
             units: xdf.unit.unwrap_or_default(),
             data,
+            names,
         }
     }
-    pub fn read<R: Read + Seek>(&self, bin: &mut R) -> Result<Vec<f64>, std::io::Error> {
+    /// Read the stored integers without applying the expression, fails for user defined axes.
+    pub fn read_raw<R: Read + Seek>(&self, bin: &mut R) -> Result<Vec<u32>, std::io::Error> {
+        self.read_raw_range(bin, 0..self.len())
+    }
+    /// Raw values of the elements in `elements`, which must all exist.
+    pub fn read_raw_range<R: Read + Seek>(
+        &self,
+        bin: &mut R,
+        elements: Range<usize>,
+    ) -> Result<Vec<u32>, std::io::Error> {
         match &self.data {
-            AxisData::User(items) => Ok(items.clone()),
+            AxisData::User(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "User defined axes have no raw values",
+            )),
             AxisData::Binary {
-                address,
                 element_size,
-                count,
-                expression,
+                big_endian,
+                flag,
+                ..
             } => {
-                bin.seek(std::io::SeekFrom::Start(*address))?;
-                let mut buf = vec![0u8; *element_size];
-
-                let mut result = Vec::with_capacity(*count);
-
-                for _ in 0..*count {
-                    bin.read_exact(&mut buf)?;
-                    result.push(eval(&expression, bytes_to_u32(&buf)));
+                let mask = if *flag { 1 } else { u32::MAX };
+                if elements.is_empty() {
+                    return Ok(Vec::new());
                 }
 
-                Ok(result)
+                // One read of the whole region, row padding included, so large maps are not
+                // read a run at a time
+                let start = self.data.element_address(elements.start).unwrap();
+                let end =
+                    self.data.element_address(elements.end - 1).unwrap() + *element_size as u64;
+                let region = start..end;
+                let mut buf = vec![0u8; (region.end - region.start) as usize];
+                bin.seek(std::io::SeekFrom::Start(region.start))?;
+                bin.read_exact(&mut buf)?;
+
+                Ok(elements
+                    .map(|i| {
+                        let start = (self.data.element_address(i).unwrap() - region.start) as usize;
+                        let mut bytes = buf[start..start + element_size].to_vec();
+                        if *big_endian {
+                            bytes.reverse();
+                        }
+                        bytes_to_u32(&bytes) & mask
+                    })
+                    .collect())
             }
         }
     }
+    pub fn read<R: Read + Seek>(&self, bin: &mut R) -> Result<Vec<f64>, std::io::Error> {
+        match &self.data {
+            AxisData::User(items) => Ok(items.clone()),
+            AxisData::Binary {
+                element_size,
+                expression,
+                signed,
+                display,
+                ..
+            } => Ok(self
+                .read_raw(bin)?
+                .into_iter()
+                .map(|raw| display.apply(eval(expression, decode_raw(raw, *element_size, *signed))))
+                .collect()),
+        }
+    }
+    /// Read and format the values. Axes whose values are all whole numbers, such as RPM
+    /// breakpoints, are shown without decimals unless the user picked a number of decimals.
     pub fn read_strings<R: Read + Seek>(&self, bin: &mut R) -> Result<Vec<String>, std::io::Error> {
-        let floats = self.read(bin)?;
-
-        Ok(if let Some(p) = self.precision() {
-            floats.iter().map(|v| format!("{:.p$}", v)).collect()
-        } else {
-            floats.iter().map(f64::to_string).collect()
-        })
+        let values = self.read(bin)?;
+        if self.decimals().is_none() && all_integers(&values) {
+            return Ok(values.into_iter().map(|v| format!("{v:.0}")).collect());
+        }
+        Ok(values.into_iter().map(|v| self.format(v)).collect())
+    }
+    /// Read and format the values of the elements in `elements`, a part of the data of a
+    /// binary axis. Whole numbers are formatted like any other value as the rest of the axis
+    /// is not known.
+    pub fn read_strings_range<R: Read + Seek>(
+        &self,
+        bin: &mut R,
+        elements: Range<usize>,
+    ) -> Result<Vec<String>, std::io::Error> {
+        Ok(self
+            .read_raw_range(bin, elements)?
+            .into_iter()
+            .filter_map(|raw| self.from_raw(raw).map(|v| self.format(v)))
+            .collect())
     }
     pub fn write<W: Write + Seek>(
         &self,
@@ -287,26 +992,152 @@ impl Axis {
         match &self.data {
             AxisData::User(_) => panic!("Cannot write user defined constant values to binary"),
             AxisData::Binary {
-                address,
                 element_size,
                 count,
                 expression,
+                big_endian,
+                signed,
+                display,
+                ..
             } => {
                 assert_eq!(count, &vals.len());
-                bin.seek(std::io::SeekFrom::Start(*address))?;
+                // Converted up front so nothing is written when the expression cannot be reversed
+                let raws: Vec<u32> = vals
+                    .into_iter()
+                    .map(|val| {
+                        value_to_raw(expression, display.reverse(val), *element_size, *signed)
+                    })
+                    .collect::<Result<_, _>>()
+                    .map_err(invalid_input)?;
                 let mut buf = vec![];
-                for val in vals {
-                    let bytes = (eval_reverse(&expression, val).round() as u32).to_be_bytes();
-                    for i in 0..*element_size {
-                        buf.push(bytes[bytes.len() - i - 1]);
+                for (i, raw) in raws.into_iter().enumerate() {
+                    if self.data.is_run_start(i) && !buf.is_empty() {
+                        bin.write_all(&buf)?;
+                        buf.clear();
+                    }
+                    if buf.is_empty() {
+                        bin.seek(std::io::SeekFrom::Start(
+                            self.data.element_address(i).unwrap(),
+                        ))?;
                     }
+                    let mut bytes = u32_to_bytes(raw, *element_size);
+                    if *big_endian {
+                        bytes.reverse();
+                    }
+                    buf.append(&mut bytes);
                 }
-                bin.write_all(&mut buf)
+                bin.write_all(&buf)
             }
         }
     }
 }
 
+/// How numbers are written when exporting to CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvPrecision {
+    /// Shortest representation that reads back as exactly the same value
+    #[default]
+    Full,
+    Decimals(usize),
+}
+
+impl CsvPrecision {
+    pub const ALL: [CsvPrecision; 8] = [
+        Self::Full,
+        Self::Decimals(0),
+        Self::Decimals(1),
+        Self::Decimals(2),
+        Self::Decimals(3),
+        Self::Decimals(4),
+        Self::Decimals(5),
+        Self::Decimals(6),
+    ];
+
+    pub fn format(self, value: f64) -> String {
+        match self {
+            Self::Full => value.to_string(),
+            Self::Decimals(p) => format!("{:.p$}", value),
+        }
+    }
+}
+
+impl std::fmt::Display for CsvPrecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => f.write_str("Full precision"),
+            Self::Decimals(p) => write!(f, "{p} decimals"),
+        }
+    }
+}
+
+/// Values of all three axes of a table, data is stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableValues {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub data: Vec<f64>,
+}
+
+/// Result of looking up a point in a table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lookup {
+    pub value: f64,
+    /// (row, column) of the cells the value was interpolated from
+    pub cells: [(usize, usize); 4],
+}
+
+/// Breakpoints on either side of `v` and how far it is from the first to the second.
+/// Points outside the axis are clamped to its ends, descending axes are supported.
+pub fn bracket(axis: &[f64], v: f64) -> Option<(usize, usize, f64)> {
+    if axis.is_empty() || !v.is_finite() || axis.iter().any(|a| !a.is_finite()) {
+        return None;
+    }
+    if axis.len() == 1 {
+        return Some((0, 0, 0.0));
+    }
+    let i = axis
+        .windows(2)
+        .position(|w| (w[0].min(w[1])..=w[0].max(w[1])).contains(&v));
+    let i = match i {
+        Some(i) => i,
+        // Outside the axis, clamp to whichever end is closer
+        None if (v - axis[0]).abs() <= (v - axis[axis.len() - 1]).abs() => {
+            return Some((0, 0, 0.0))
+        }
+        None => {
+            let last = axis.len() - 1;
+            return Some((last, last, 0.0));
+        }
+    };
+    let span = axis[i + 1] - axis[i];
+    let t = if span == 0.0 {
+        0.0
+    } else {
+        (v - axis[i]) / span
+    };
+    Some((i, i + 1, t))
+}
+
+impl TableValues {
+    /// Bilinear interpolation at `(x, y)` as an ECU would look it up, clamped to the axes.
+    /// `None` if any of the values involved are not numbers.
+    pub fn interpolate(&self, x: f64, y: f64) -> Option<Lookup> {
+        let (x0, x1, tx) = bracket(&self.x, x)?;
+        let (y0, y1, ty) = bracket(&self.y, y)?;
+        let cells = [(y0, x0), (y0, x1), (y1, x0), (y1, x1)];
+        let [a, b, c, d] = cells.map(|(r, col)| {
+            self.data
+                .get(r * self.x.len() + col)
+                .copied()
+                .unwrap_or(f64::NAN)
+        });
+        let top = a + (b - a) * tx;
+        let bottom = c + (d - c) * tx;
+        let value = top + (bottom - top) * ty;
+        value.is_finite().then_some(Lookup { value, cells })
+    }
+}
+
 /// Multivalue map data definitions
 #[derive(Debug, Clone)]
 pub struct Table {
@@ -322,9 +1153,14 @@ pub struct Table {
 }
 
 impl Table {
+    /// Axis labels that are not numbers are added to `errors` and unsupported expressions to
+    /// `warnings`, see [`Axis::from_xdf`].
     pub fn from_xdf(
         mut xdf: XDFTable,
         linked: Option<&HashMap<u32, (EmbeddedData, Math)>>,
+        strict: bool,
+        errors: &mut Vec<String>,
+        warnings: &mut Vec<String>,
     ) -> Self {
         let name = xdf.title.unwrap_or_default();
         let description = xdf.description.unwrap_or_default();
@@ -341,9 +1177,23 @@ impl Table {
         xdf.axis.sort_by_key(|a| a.id.clone());
 
         // Because they are now sorted, we can just pop them and the following should work.
-        let z = Axis::from_xdf(xdf.axis.pop().unwrap(), linked);
-        let y = Axis::from_xdf(xdf.axis.pop().unwrap(), linked);
-        let x = Axis::from_xdf(xdf.axis.pop().unwrap(), linked);
+        let mut axis = |axis_name: &str| {
+            let (mut axis_errors, mut axis_warnings) = (Vec::new(), Vec::new());
+            let axis = Axis::from_xdf(
+                xdf.axis.pop().unwrap(),
+                linked,
+                strict,
+                &mut axis_errors,
+                &mut axis_warnings,
+            );
+            let named = |e| format!("{name} ({axis_name} axis): {e}");
+            errors.extend(axis_errors.into_iter().map(named));
+            warnings.extend(axis_warnings.into_iter().map(named));
+            axis
+        };
+        let z = axis("z");
+        let y = axis("y");
+        let x = axis("x");
 
         Self {
             categories,
@@ -354,6 +1204,107 @@ impl Table {
             z,
         }
     }
+    /// Whether any stored axis of the table has bytes in `range`.
+    pub fn overlaps(&self, range: &Range<u64>) -> bool {
+        [&self.x, &self.y, &self.z]
+            .into_iter()
+            .filter_map(Axis::byte_range)
+            .any(|axis| ranges_overlap(&axis, range))
+    }
+    /// Whether the table is a grid of on/off flags, see [`Axis::is_boolean`].
+    pub fn is_boolean(&self) -> bool {
+        self.z.is_boolean()
+    }
+    /// Fails for tables that cannot be shown, such as ones with an axis without values.
+    pub fn check_axes(&self) -> Result<(), String> {
+        let empty: Vec<&str> = [("x", &self.x), ("y", &self.y), ("z", &self.z)]
+            .into_iter()
+            .filter(|(_, axis)| axis.len() == 0)
+            .map(|(name, _)| name)
+            .collect();
+        if empty.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Cannot open {}, its {} axis has no values",
+                self.name,
+                empty.join(" and ")
+            ))
+        }
+    }
+    /// Values as comma separated values, the first row is the x axis after an empty cell and
+    /// every other row starts with its y axis value.
+    pub fn to_csv(values: &TableValues, precision: CsvPrecision) -> String {
+        let join = |values: &[f64]| {
+            values
+                .iter()
+                .map(|v| precision.format(*v))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let mut csv = format!(",{}\n", join(&values.x));
+        for (y, row) in values.y.iter().zip(values.data.chunks(values.x.len())) {
+            csv.push_str(&format!("{},{}\n", precision.format(*y), join(row)));
+        }
+        csv
+    }
+
+    /// Parse a grid laid out like [`Table::to_csv`] into x axis, y axis and data values.
+    /// Values may also be separated by `;` or tabs, in which case `,` decimals are accepted.
+    /// Fails if the shape does not match this table.
+    pub fn grid_from_csv(&self, csv: &str) -> Result<TableValues, std::io::Error> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+        let delimiter = if csv.contains(';') {
+            ';'
+        } else if csv.contains('\t') {
+            '\t'
+        } else {
+            ','
+        };
+        let parse = |value: &str| {
+            let value = value.trim();
+            let number = if delimiter == ',' {
+                value.to_string()
+            } else {
+                value.replace(',', ".")
+            };
+            number
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("\"{value}\" is not a number")))
+        };
+
+        let rows: Vec<Vec<&str>> = csv
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.split(delimiter).collect())
+            .collect();
+
+        let (width, height) = (self.x.len() + 1, self.y.len() + 1);
+        let found_width = rows
+            .iter()
+            .map(Vec::len)
+            .find(|&w| w != width)
+            .unwrap_or(width);
+        if rows.len() != height || found_width != width {
+            return Err(invalid(format!(
+                "Expected {height} rows of {width} values, found {} rows of {found_width}",
+                rows.len()
+            )));
+        }
+
+        let x = rows[0][1..].iter().map(|v| parse(v)).try_collect()?;
+        let mut y = Vec::new();
+        let mut data = Vec::new();
+        for row in &rows[1..] {
+            y.push(parse(row[0])?);
+            for value in &row[1..] {
+                data.push(parse(value)?);
+            }
+        }
+        Ok(TableValues { x, y, data })
+    }
+
     pub fn build_array(&self, bin: &mut std::fs::File) -> std::io::Result<Vec<Vec<String>>> {
         // add one to length for row/column headers
         let xl = self.x.len();
@@ -395,16 +1346,36 @@ impl Table {
     }
 }
 
+/// Bytes `start..end` used by a named definition
+#[derive(Debug, Clone)]
+struct Region {
+    name: String,
+    start: u64,
+    end: u64,
+    axis: bool,
+}
+
 /// Definitions for a binary, metadata
 #[derive(Debug, Clone)]
 pub struct BinaryDefinition {
     pub info: DefinitionInfo,
     pub scalars: Vec<Scalar>,
     pub tables: Vec<Table>,
+    /// Subtracted from all addresses in the definition to make them relative to the start
+    /// of the file, for definitions that use memory mapped addresses.
+    pub base_offset: i64,
+    /// Axis labels that are not numbers, reported when the definition is loaded
+    pub label_errors: Vec<String>,
+    /// Conversion expressions that are not fully supported, reported when the definition is
+    /// loaded
+    pub expression_warnings: Vec<String>,
 }
 
 impl BinaryDefinition {
-    pub fn from_xdf(xdf: XDFFormat) -> Self {
+    /// With `strict_labels` axis labels that are not numbers are collected in `label_errors`
+    /// and read as NaN, otherwise they read as 0. Fails when the base offset of the XDF moves
+    /// an address before the start of the file.
+    pub fn from_xdf(xdf: XDFFormat, strict_labels: bool) -> Result<Self, String> {
         // This allows me to support linked objects, where the axis is defined in a different table.
         let mut table_zs = HashMap::new();
         for table in xdf.tables.iter() {
@@ -419,14 +1390,739 @@ impl BinaryDefinition {
                 }
             }
         }
-        Self {
-            info: DefinitionInfo::from_xdf(xdf.header.unwrap()),
-            scalars: xdf.constants.into_iter().map(Scalar::from_xdf).collect(),
-            tables: xdf
-                .tables
-                .into_iter()
-                .map(|t| Table::from_xdf(t, Some(&table_zs)))
-                .collect(),
+        let header = xdf.header.unwrap();
+
+        // TunerPro adds the base offset to addresses unless the subtract flag is set
+        let base_offset = header.baseoffset.map_or(0, |b| {
+            let offset = b.offset.unwrap_or_default() as i64;
+            if b.subtract.unwrap_or_default() {
+                offset
+            } else {
+                -offset
+            }
+        });
+
+        let (mut label_errors, mut expression_warnings) = (Vec::new(), Vec::new());
+        let tables = xdf
+            .tables
+            .into_iter()
+            .map(|t| {
+                Table::from_xdf(
+                    t,
+                    Some(&table_zs),
+                    strict_labels,
+                    &mut label_errors,
+                    &mut expression_warnings,
+                )
+            })
+            .collect();
+        let scalars: Vec<Scalar> = xdf.constants.into_iter().map(Scalar::from_xdf).collect();
+        for scalar in &scalars {
+            if let Some(warning) = constant_warning(&scalar.expression) {
+                expression_warnings.push(format!("{}: {warning}", scalar.name));
+            }
+        }
+        let mut definition = Self {
+            info: DefinitionInfo::from_xdf(header),
+            scalars,
+            tables,
+            base_offset: 0,
+            label_errors,
+            expression_warnings,
+        };
+
+        definition
+            .set_base_offset(base_offset)
+            .map_err(|e| format!("BASEOFFSET of the XDF does not fit: {e}"))?;
+
+        Ok(definition)
+    }
+
+    fn regions(&self) -> Vec<Region> {
+        let mut regions: Vec<Region> = self
+            .scalars
+            .iter()
+            .map(|s| Region {
+                name: s.name.clone(),
+                start: s.address,
+                end: s.address + s.size as u64,
+                axis: false,
+            })
+            .collect();
+
+        for table in self.tables.iter() {
+            for (axis_name, axis) in [("x", &table.x), ("y", &table.y), ("z", &table.z)] {
+                if let AxisData::Binary {
+                    element_size,
+                    count,
+                    ..
+                } = axis.data
+                {
+                    // One region per contiguous run so row padding is left free
+                    let starts: Vec<usize> =
+                        (0..count).filter(|i| axis.data.is_run_start(*i)).collect();
+                    for (n, first) in starts.iter().enumerate() {
+                        let len = starts.get(n + 1).unwrap_or(&count) - first;
+                        let start = axis.data.element_address(*first).unwrap();
+                        regions.push(Region {
+                            name: format!("{} ({axis_name} axis)", table.name),
+                            start,
+                            end: start + (element_size * len) as u64,
+                            axis: true,
+                        });
+                    }
+                }
+            }
+        }
+        regions
+    }
+
+    /// Describe every pair of definitions that share bytes, editing one would corrupt the other.
+    /// Axes stored at exactly the same bytes are linked axes and are not reported.
+    pub fn overlaps(&self) -> Vec<String> {
+        let mut regions: Vec<Region> = self
+            .regions()
+            .into_iter()
+            .filter(|r| r.end > r.start)
+            .collect();
+        regions.sort_by_key(|r| (r.start, r.end));
+
+        let mut overlaps = Vec::new();
+        for (i, a) in regions.iter().enumerate() {
+            for b in regions[i + 1..].iter().take_while(|b| b.start < a.end) {
+                if a.axis && b.axis && a.start == b.start && a.end == b.end {
+                    continue;
+                }
+                overlaps.push(format!(
+                    "{} (0x{:X}-0x{:X}) overlaps {} (0x{:X}-0x{:X})",
+                    a.name,
+                    a.start,
+                    a.end - 1,
+                    b.name,
+                    b.start,
+                    b.end - 1
+                ));
+            }
+        }
+        overlaps
+    }
+
+    /// Change the base offset, moving every address by the difference to the current one.
+    /// Fails without changing anything if an address would end up before the start of the file.
+    pub fn set_base_offset(&mut self, base_offset: i64) -> Result<(), String> {
+        let shift = self.base_offset.checked_sub(base_offset).ok_or(format!(
+            "base offset {} is too far from the current {}",
+            signed_hex(base_offset),
+            signed_hex(self.base_offset)
+        ))?;
+        let moved = |address: u64, name: &str| {
+            address.checked_add_signed(shift).ok_or(format!(
+                "{name} at 0x{address:X} is before the start of the file with base offset {}",
+                signed_hex(base_offset)
+            ))
+        };
+
+        let mut scalars = self.scalars.clone();
+        for scalar in scalars.iter_mut() {
+            scalar.address = moved(scalar.address, &scalar.name)?;
+        }
+
+        let mut tables = self.tables.clone();
+        for table in tables.iter_mut() {
+            for axis in [&mut table.x, &mut table.y, &mut table.z] {
+                if let AxisData::Binary { address, .. } = &mut axis.data {
+                    *address = moved(*address, &table.name)?;
+                }
+            }
+        }
+
+        self.scalars = scalars;
+        self.tables = tables;
+        self.base_offset = base_offset;
+        Ok(())
+    }
+}
+
+/// Hex with the sign in front, `{:X}` of a negative number would show its two's complement.
+fn signed_hex(value: i64) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    format!("{sign}0x{:X}", value.unsigned_abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn scalar(size: usize, expression: &str) -> Scalar {
+        Scalar {
+            name: "Test".to_string(),
+            description: String::new(),
+            address: 1,
+            size,
+            expression: expression.to_string(),
+            units: String::new(),
+            signed: false,
+            categories: Vec::new(),
+            decimals: None,
+            type_flags: None,
+            bit_labels: None,
         }
     }
+
+    fn axis(element_size: usize, count: usize, expression: &str) -> Axis {
+        Axis {
+            units: String::new(),
+            data: AxisData::Binary {
+                address: 2,
+                element_size,
+                count,
+                expression: expression.to_string(),
+                row_stride: None,
+                big_endian: false,
+                signed: false,
+                display: DisplayScale::default(),
+                flag: false,
+                decimals: None,
+                type_flags: None,
+            },
+            names: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn bytes_24_bit() {
+        assert_eq!(bytes_to_u32(&[0x01, 0x02, 0x03]), 0x030201);
+        assert_eq!(u32_to_bytes(0x030201, 3), [0x01, 0x02, 0x03]);
+        assert_eq!(u32_to_bytes(0xFF030201, 3), [0x01, 0x02, 0x03]);
+        for raw in [0, 1, 0x7FFFFF, 0x800000, 0xFFFFFF] {
+            assert_eq!(bytes_to_u32(&u32_to_bytes(raw, 3)), raw);
+        }
+        assert_eq!(raw_max(3), 0xFFFFFF);
+    }
+
+    #[test]
+    fn scalar_24_bit_round_trip() {
+        let scalar = scalar(3, "X*0.5");
+        let mut bin = Cursor::new(vec![0xAA; 5]);
+        scalar.write(&mut bin, 1000000.0).unwrap();
+        assert_eq!(bin.get_ref(), &[0xAA, 0x80, 0x84, 0x1E, 0xAA]);
+        assert_eq!(scalar.read_raw(&mut bin).unwrap(), 2000000);
+        assert_eq!(scalar.read(&mut bin).unwrap(), 1000000.0);
+
+        // Too large for 3 bytes is clamped instead of spilling into the next byte
+        scalar.write(&mut bin, 1e9).unwrap();
+        assert_eq!(bin.get_ref(), &[0xAA, 0xFF, 0xFF, 0xFF, 0xAA]);
+    }
+
+    #[test]
+    fn scalar_24_bit_signed() {
+        let mut scalar = scalar(3, "X");
+        scalar.signed = true;
+        let mut bin = Cursor::new(vec![0; 5]);
+        scalar.write(&mut bin, -1.0).unwrap();
+        assert_eq!(&bin.get_ref()[1..4], &[0xFF, 0xFF, 0xFF]);
+        assert_eq!(scalar.read(&mut bin).unwrap(), -1.0);
+
+        scalar.write(&mut bin, -8388608.0).unwrap();
+        assert_eq!(&bin.get_ref()[1..4], &[0x00, 0x00, 0x80]);
+        assert_eq!(scalar.read(&mut bin).unwrap(), -8388608.0);
+
+        // Clamped to the smallest value that fits
+        scalar.write(&mut bin, -9e6).unwrap();
+        assert_eq!(scalar.read(&mut bin).unwrap(), -8388608.0);
+        assert_eq!(decode_raw(0x7FFFFF, 3, true), 8388607.0);
+        assert_eq!(decode_raw(0x800000, 3, false), 8388608.0);
+    }
+
+    #[test]
+    fn axis_24_bit_round_trip() {
+        let values = vec![0.0, 1.0, 65536.0, 16777215.0];
+        for big_endian in [false, true] {
+            let mut axis = axis(3, 4, "X");
+            if let AxisData::Binary { big_endian: b, .. } = &mut axis.data {
+                *b = big_endian;
+            }
+            let mut bin = Cursor::new(vec![0; 16]);
+            axis.write(&mut bin, values.clone()).unwrap();
+            assert_eq!(axis.read(&mut bin).unwrap(), values);
+            let third = &bin.get_ref()[8..11];
+            if big_endian {
+                assert_eq!(third, &[0x01, 0x00, 0x00]);
+            } else {
+                assert_eq!(third, &[0x00, 0x00, 0x01]);
+            }
+        }
+    }
+
+    #[test]
+    fn axis_24_bit_signed() {
+        let mut axis = axis(3, 2, "X*2");
+        if let AxisData::Binary { signed, .. } = &mut axis.data {
+            *signed = true;
+        }
+        let mut bin = Cursor::new(vec![0; 8]);
+        axis.write(&mut bin, vec![-2.0, 10.0]).unwrap();
+        assert_eq!(&bin.get_ref()[2..5], &[0xFF, 0xFF, 0xFF]);
+        assert_eq!(axis.read(&mut bin).unwrap(), vec![-2.0, 10.0]);
+    }
+
+    /// 3 rows of 2 two byte elements, each row padded to 6 bytes
+    fn padded_axis() -> Axis {
+        let mut axis = axis(2, 6, "X");
+        if let AxisData::Binary { row_stride, .. } = &mut axis.data {
+            *row_stride = Some(RowStride {
+                columns: 2,
+                bytes: 6,
+            });
+        }
+        axis
+    }
+
+    #[test]
+    fn padded_rows_read() {
+        let axis = padded_axis();
+        #[rustfmt::skip]
+        let mut bin = Cursor::new(vec![
+            0xEE, 0xEE,
+            1, 0, 2, 0, 0xEE, 0xEE,
+            3, 0, 4, 0, 0xEE, 0xEE,
+            5, 0, 6, 0, 0xEE, 0xEE,
+        ]);
+        assert_eq!(
+            axis.read(&mut bin).unwrap(),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+        assert_eq!(axis.byte_range(), Some(2..18));
+        assert_eq!(axis.data.element_address(2), Some(8));
+        assert_eq!(axis.data.element_address(6), None);
+    }
+
+    #[test]
+    fn padded_rows_write_leaves_padding() {
+        let axis = padded_axis();
+        let mut bin = Cursor::new(vec![0xEE; 20]);
+        axis.write(&mut bin, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .unwrap();
+        #[rustfmt::skip]
+        assert_eq!(bin.get_ref(), &[
+            0xEE, 0xEE,
+            1, 0, 2, 0, 0xEE, 0xEE,
+            3, 0, 4, 0, 0xEE, 0xEE,
+            5, 0, 6, 0, 0xEE, 0xEE,
+        ]);
+    }
+
+    /// 3x2 table matching the shape of [`lookup_table`]
+    fn csv_table() -> Table {
+        Table {
+            name: "Boost target".to_string(),
+            description: String::new(),
+            x: axis(1, 3, "X"),
+            y: axis(1, 2, "X"),
+            z: axis(1, 6, "X"),
+            categories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn csv_round_trip_full_precision() {
+        let values = TableValues {
+            x: vec![0.0, 0.1 + 0.2, -48.0],
+            y: vec![1e-7, 2500.0],
+            data: vec![1.0, -2.25, 1.0 / 3.0, 0.0, 6.5, 1e12],
+        };
+        let csv = Table::to_csv(&values, CsvPrecision::Full);
+        assert_eq!(csv_table().grid_from_csv(&csv).unwrap(), values);
+    }
+
+    #[test]
+    fn csv_with_decimals() {
+        let csv = Table::to_csv(&lookup_table(), CsvPrecision::Decimals(1));
+        assert_eq!(
+            csv,
+            ",0.0,10.0,20.0\n100.0,1.0,2.0,3.0\n200.0,5.0,6.0,7.0\n"
+        );
+        assert_eq!(csv_table().grid_from_csv(&csv).unwrap(), lookup_table());
+    }
+
+    #[test]
+    fn csv_with_decimal_commas() {
+        let csv = ";0;10;20\n100;1,0;2;3\n\n200;5;6;7,0\n";
+        assert_eq!(csv_table().grid_from_csv(csv).unwrap(), lookup_table());
+        let csv = "\t0\t10\t20\n100\t1\t2\t3\n200\t5\t6\t7\n";
+        assert_eq!(csv_table().grid_from_csv(csv).unwrap(), lookup_table());
+    }
+
+    #[test]
+    fn csv_of_wrong_shape() {
+        let error = csv_table()
+            .grid_from_csv(",0,10\n100,1,2\n200,5,6\n")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected 3 rows of 4 values, found 3 rows of 3"
+        );
+        let error = csv_table()
+            .grid_from_csv(",0,10,20\n100,1,x,3\n200,5,6,7\n")
+            .unwrap_err();
+        assert_eq!(error.to_string(), "\"x\" is not a number");
+    }
+
+    fn lookup_table() -> TableValues {
+        TableValues {
+            x: vec![0.0, 10.0, 20.0],
+            y: vec![100.0, 200.0],
+            data: vec![1.0, 2.0, 3.0, 5.0, 6.0, 7.0],
+        }
+    }
+
+    #[test]
+    fn lookup_exact_breakpoints() {
+        let table = lookup_table();
+        let lookup = table.interpolate(10.0, 200.0).unwrap();
+        assert_eq!(lookup.value, 6.0);
+        assert!(lookup.cells.contains(&(1, 1)));
+        assert_eq!(table.interpolate(0.0, 100.0).unwrap().value, 1.0);
+        assert_eq!(table.interpolate(20.0, 200.0).unwrap().value, 7.0);
+    }
+
+    #[test]
+    fn lookup_between_cells() {
+        let table = lookup_table();
+        let lookup = table.interpolate(5.0, 150.0).unwrap();
+        assert_eq!(lookup.value, 3.5);
+        assert_eq!(lookup.cells, [(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(table.interpolate(15.0, 100.0).unwrap().value, 2.5);
+    }
+
+    #[test]
+    fn lookup_clamped_to_axes() {
+        let table = lookup_table();
+        assert_eq!(table.interpolate(-50.0, 0.0).unwrap().value, 1.0);
+        assert_eq!(table.interpolate(50.0, 1000.0).unwrap().value, 7.0);
+        assert_eq!(table.interpolate(5.0, 1000.0).unwrap().value, 5.5);
+    }
+
+    #[test]
+    fn lookup_descending_axis() {
+        let table = TableValues {
+            x: vec![20.0, 10.0, 0.0],
+            ..lookup_table()
+        };
+        assert_eq!(table.interpolate(15.0, 100.0).unwrap().value, 1.5);
+        assert_eq!(table.interpolate(-5.0, 100.0).unwrap().value, 3.0);
+        assert_eq!(table.interpolate(25.0, 100.0).unwrap().value, 1.0);
+    }
+
+    #[test]
+    fn lookup_needs_numbers() {
+        let mut table = lookup_table();
+        assert_eq!(table.interpolate(f64::NAN, 100.0), None);
+        table.data[5] = f64::NAN;
+        assert_eq!(table.interpolate(15.0, 200.0), None);
+        assert_eq!(table.interpolate(5.0, 150.0).unwrap().value, 3.5);
+    }
+
+    fn flag_axis(expression: &str) -> Axis {
+        let mut axis = axis(1, 4, expression);
+        if let AxisData::Binary { flag, .. } = &mut axis.data {
+            *flag = true;
+        }
+        axis
+    }
+
+    #[test]
+    fn one_bit_axis_is_boolean() {
+        let axis = flag_axis("X");
+        assert!(axis.is_boolean());
+        let mut bin = Cursor::new(vec![0xEE, 0xEE, 0x00, 0x01, 0xFE, 0x03]);
+        assert_eq!(axis.read(&mut bin).unwrap(), vec![0.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn scaled_flags_are_not_boolean() {
+        assert!(!flag_axis("X*10").is_boolean());
+        assert!(!flag_axis("1-X").is_boolean());
+    }
+
+    #[test]
+    fn byte_map_of_zeros_and_ones_is_not_boolean() {
+        let axis = axis(1, 4, "X");
+        let mut bin = Cursor::new(vec![0xEE, 0xEE, 0, 1, 1, 0]);
+        assert_eq!(axis.read(&mut bin).unwrap(), vec![0.0, 1.0, 1.0, 0.0]);
+        assert!(!axis.is_boolean());
+    }
+
+    #[test]
+    fn raw_hex_padded_to_size() {
+        assert_eq!(format_raw_hex(0xA, 1), "0x0A");
+        assert_eq!(format_raw_hex(0xBEEF, 2), "0xBEEF");
+        assert_eq!(format_raw_hex(0x1, 3), "0x000001");
+        assert_eq!(format_raw_hex(0xDEADBEEF, 4), "0xDEADBEEF");
+    }
+
+    #[test]
+    fn parse_raw_hex_and_decimal() {
+        assert_eq!(parse_raw("0xff", 1), Some(255));
+        assert_eq!(parse_raw(" 0XFf ", 1), Some(255));
+        assert_eq!(parse_raw("255", 1), Some(255));
+        assert_eq!(parse_raw("0x0A", 2), Some(10));
+        for size in 1..=4 {
+            let max = raw_max(size);
+            assert_eq!(parse_raw(&format_raw_hex(max, size), size), Some(max));
+        }
+    }
+
+    #[test]
+    fn parse_raw_rejects_invalid() {
+        assert_eq!(parse_raw("0x100", 1), None);
+        assert_eq!(parse_raw("256", 1), None);
+        assert_eq!(parse_raw("0x1000000", 3), None);
+        assert_eq!(parse_raw("0x100000000", 4), None);
+        assert_eq!(parse_raw("-1", 2), None);
+        assert_eq!(parse_raw("1.5", 2), None);
+        assert_eq!(parse_raw("0x", 2), None);
+        assert_eq!(parse_raw("0xG1", 2), None);
+    }
+
+    #[test]
+    fn step_linear() {
+        let scalar = scalar(1, "X*0.75-48");
+        assert_eq!(scalar.step_at(0), 0.75);
+        assert_eq!(scalar.step_at(100), 0.75);
+    }
+
+    #[test]
+    fn step_follows_quadratic() {
+        let scalar = scalar(2, "X*X");
+        assert_eq!(scalar.step_at(0), 1.0);
+        assert_eq!(scalar.step_at(3), 7.0);
+        assert_eq!(scalar.step_at(100), 201.0);
+    }
+
+    #[test]
+    fn step_zero_at_max() {
+        assert_eq!(scalar(1, "X").step_at(0xFF), 0.0);
+        assert_eq!(scalar(2, "X*2").step_at(0xFFFF), 0.0);
+        assert_eq!(scalar(1, "X").step_at(u64::MAX), 0.0);
+        let mut signed = scalar(1, "X");
+        signed.signed = true;
+        assert_eq!(signed.step_at(0x7F), 0.0);
+        assert_eq!(signed.step_at(0xFF), 1.0);
+    }
+
+    #[test]
+    fn constant_axis_has_no_precision() {
+        let constant = axis(1, 4, "5.0");
+        assert_eq!(constant.precision(), None);
+        assert_eq!(constant.format(5.0), "5");
+        assert_eq!(axis(1, 4, "X*0.75").precision(), Some(1));
+    }
+
+    #[test]
+    fn base_offset_moves_addresses() {
+        let mut definition = crate::demo::definition();
+        let scalar = definition.scalars[0].address;
+        let table = definition.tables[0].z.byte_range().unwrap().start;
+        definition.set_base_offset(-0x8000).unwrap();
+        assert_eq!(definition.scalars[0].address, scalar + 0x8000);
+        // Moved by the difference to the current base offset
+        definition.set_base_offset(-0x7FF0).unwrap();
+        assert_eq!(definition.scalars[0].address, scalar + 0x7FF0);
+        assert_eq!(
+            definition.tables[0].z.byte_range().unwrap().start,
+            table + 0x7FF0
+        );
+        assert_eq!(definition.base_offset, -0x7FF0);
+    }
+
+    #[test]
+    fn base_offset_past_start_changes_nothing() {
+        let mut definition = crate::demo::definition();
+        let scalar = definition.scalars[0].address;
+        let error = definition.set_base_offset(0x10000).unwrap_err();
+        assert!(
+            error.ends_with("is before the start of the file with base offset 0x10000"),
+            "{error}"
+        );
+        assert_eq!(definition.scalars[0].address, scalar);
+        assert_eq!(definition.base_offset, 0);
+    }
+
+    #[test]
+    fn base_offset_error_shows_sign() {
+        let mut definition = crate::demo::definition();
+        definition.set_base_offset(-0x10).unwrap();
+        assert_eq!(
+            definition.set_base_offset(i64::MAX).unwrap_err(),
+            "base offset 0x7FFFFFFFFFFFFFFF is too far from the current -0x10"
+        );
+        assert_eq!(definition.base_offset, -0x10);
+        assert_eq!(signed_hex(-0x1F), "-0x1F");
+        assert_eq!(signed_hex(0x1F), "0x1F");
+    }
+
+    /// Definition of a one byte constant at 0x20 with the given BASEOFFSET
+    fn offset_definition(offset: u32, subtract: bool) -> Result<BinaryDefinition, String> {
+        let xdf = format!(
+            r#"<XDFFORMAT version="1.60">
+  <XDFHEADER>
+    <deftitle>Base offset</deftitle>
+    <BASEOFFSET offset="{offset}" subtract="{}" />
+  </XDFHEADER>
+  <XDFCONSTANT uniqueid="0x1">
+    <title>Constant</title>
+    <EMBEDDEDDATA mmedaddress="0x20" mmedelementsizebits="8" />
+    <MATH equation="X">
+      <VAR id="X" />
+    </MATH>
+  </XDFCONSTANT>
+</XDFFORMAT>"#,
+            u8::from(subtract)
+        );
+        let Some(xdftuneparser::data_types::XDFElement::XDFFormat(xdf)) =
+            xdftuneparser::parse_buffer(xdf.as_bytes()).unwrap()
+        else {
+            panic!("Expected full XDF file.");
+        };
+        BinaryDefinition::from_xdf(xdf, true)
+    }
+
+    #[test]
+    fn xdf_base_offset_added() {
+        let definition = offset_definition(16, false).unwrap();
+        assert_eq!(definition.base_offset, -0x10);
+        let scalar = &definition.scalars[0];
+        assert_eq!(scalar.address, 0x30);
+        let binary: Vec<u8> = (0..0x40).collect();
+        assert_eq!(scalar.read(&mut Cursor::new(binary)).unwrap(), 48.0);
+    }
+
+    #[test]
+    fn xdf_base_offset_subtracted() {
+        let definition = offset_definition(16, true).unwrap();
+        assert_eq!(definition.base_offset, 0x10);
+        let scalar = &definition.scalars[0];
+        assert_eq!(scalar.address, 0x10);
+        let binary: Vec<u8> = (0..0x40).collect();
+        assert_eq!(scalar.read(&mut Cursor::new(binary)).unwrap(), 16.0);
+        let error = offset_definition(48, true).unwrap_err();
+        assert_eq!(
+            error,
+            "BASEOFFSET of the XDF does not fit: Constant at 0x20 is before the start of the file with base offset 0x30"
+        );
+    }
+
+    #[test]
+    fn offset_raw_clamped() {
+        let scalar = scalar(1, "X");
+        assert_eq!(scalar.offset_raw(10, 5), 15);
+        assert_eq!(scalar.offset_raw(10, -20), 0);
+        assert_eq!(scalar.offset_raw(250, 20), 0xFF);
+    }
+
+    #[test]
+    fn bits() {
+        assert!(bit(0b1010, 1));
+        assert!(!bit(0b1010, 2));
+        assert!(bit(0x8000_0000, 31));
+        assert_eq!(with_bit(0b1010, 0, true), 0b1011);
+        assert_eq!(with_bit(0b1010, 1, false), 0b1000);
+        assert_eq!(with_bit(0b1010, 3, true), 0b1010);
+        assert_eq!(with_bit(0, 31, true), 0x8000_0000);
+        for n in 0..32 {
+            assert!(bit(with_bit(0, n, true), n));
+            assert_eq!(
+                with_bit(with_bit(0x1234, n, true), n, false),
+                0x1234 & !(1 << n)
+            );
+        }
+    }
+
+    #[test]
+    fn bit_labels_from_description() {
+        assert_eq!(
+            bit_labels("Flags\nbit 0: launch control, BIT 3 = hardcut; bit 7:  ", 1),
+            Some(vec![
+                (0, "launch control".to_string()),
+                (3, "hardcut".to_string()),
+            ])
+        );
+        assert_eq!(
+            bit_labels("bit 7: last\nbit 8: too far", 1),
+            Some(vec![(7, "last".to_string())])
+        );
+        assert_eq!(bit_labels("Idle target", 2), None);
+        assert_eq!(bit_labels("bit x: nothing, bit", 2), None);
+    }
+
+    #[test]
+    fn hex_entries() {
+        assert!(is_hex_entry("0xFF"));
+        assert!(is_hex_entry(" 0Xff"));
+        assert!(is_hex_entry("0x"));
+        assert!(!is_hex_entry("255"));
+        assert!(!is_hex_entry("-0x1"));
+        assert!(!is_hex_entry("x0"));
+    }
+
+    #[test]
+    fn strict_labels_must_be_numbers() {
+        let mut errors = Vec::new();
+        let (values, names) = user_axis(
+            &[Some("800"), Some(" 1200 "), Some("WOT"), Some(""), None],
+            true,
+            &mut errors,
+        );
+        assert_eq!(values[..2], [800.0, 1200.0]);
+        assert!(values[2].is_nan() && values[3].is_nan());
+        assert_eq!(values[4], 0.0);
+        assert!(names.is_empty());
+        assert_eq!(
+            errors,
+            ["label 2 \"WOT\" is not a number", "label 3 is empty"]
+        );
+    }
+
+    #[test]
+    fn lenient_text_labels_are_categorical() {
+        let mut errors = Vec::new();
+        let (values, names) =
+            user_axis(&[Some("Idle"), Some("50"), Some("WOT")], false, &mut errors);
+        assert_eq!(values, [0.0, 1.0, 2.0]);
+        assert_eq!(names, ["Idle", "50", "WOT"]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn lenient_empty_labels_read_as_zero() {
+        let mut errors = Vec::new();
+        let (values, names) = user_axis(&[Some("1.5"), Some(" "), None], false, &mut errors);
+        assert_eq!(values, [1.5, 0.0, 0.0]);
+        assert!(names.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn read_part_of_padded_rows() {
+        let axis = padded_axis();
+        #[rustfmt::skip]
+        let mut bin = Cursor::new(vec![
+            0xEE, 0xEE,
+            1, 0, 2, 0, 0xEE, 0xEE,
+            3, 0, 4, 0, 0xEE, 0xEE,
+            5, 0, 6, 0,
+        ]);
+        assert_eq!(axis.read_raw_range(&mut bin, 1..4).unwrap(), vec![2, 3, 4]);
+        assert_eq!(
+            axis.read_strings_range(&mut bin, 4..6).unwrap(),
+            [axis.format(5.0), axis.format(6.0)]
+        );
+        assert!(axis.read_raw_range(&mut bin, 3..3).unwrap().is_empty());
+        bin.get_mut().truncate(16);
+        assert!(axis.read_raw_range(&mut bin, 0..2).is_ok());
+        assert!(axis.read_raw_range(&mut bin, 4..6).is_err());
+    }
 }
@@ -0,0 +1,154 @@
+//! Small made up definition and binary, used when no files are provided so the UI can
+//! still be explored.
+
+use std::collections::HashMap;
+
+use crate::definitions::{
+    Axis, AxisData, BinaryDefinition, DefinitionInfo, DisplayScale, Scalar, Table,
+};
+
+const RPM_ADDRESS: u64 = 0x10;
+const LOAD_ADDRESS: u64 = 0x18;
+const TIMING_ADDRESS: u64 = 0x20;
+const VOLTAGE_ADDRESS: u64 = 0x60;
+const DEAD_TIME_ADDRESS: u64 = 0x68;
+
+fn binary_axis(address: u64, element_size: usize, count: usize, expression: &str) -> AxisData {
+    AxisData::Binary {
+        address,
+        element_size,
+        count,
+        expression: expression.to_string(),
+        row_stride: None,
+        big_endian: false,
+        signed: false,
+        display: DisplayScale::default(),
+        flag: false,
+        decimals: None,
+        type_flags: None,
+    }
+}
+
+pub fn definition() -> BinaryDefinition {
+    let categories = HashMap::from([
+        (0, "Limits".to_string()),
+        (1, "Fuel".to_string()),
+        (2, "Ignition".to_string()),
+    ]);
+
+    let scalars = vec![
+        Scalar {
+            name: "Rev limiter".to_string(),
+            description: "Engine speed at which fuel is cut".to_string(),
+            address: 0x00,
+            size: 2,
+            expression: "X*0.25".to_string(),
+            units: "rpm".to_string(),
+            signed: false,
+            categories: vec![0],
+            decimals: None,
+            type_flags: None,
+            bit_labels: None,
+        },
+        Scalar {
+            name: "Maximum boost".to_string(),
+            description: "Boost pressure limit in bar".to_string(),
+            address: 0x02,
+            size: 1,
+            expression: "X*0.01".to_string(),
+            units: "bar".to_string(),
+            signed: false,
+            categories: vec![0],
+            decimals: None,
+            type_flags: None,
+            bit_labels: None,
+        },
+    ];
+
+    let tables = vec![
+        Table {
+            name: "Ignition timing".to_string(),
+            description: "Base ignition advance".to_string(),
+            x: Axis {
+                units: "RPM".to_string(),
+                data: binary_axis(RPM_ADDRESS, 1, 8, "X*40"),
+                names: Vec::new(),
+            },
+            y: Axis {
+                units: "%".to_string(),
+                data: binary_axis(LOAD_ADDRESS, 1, 8, "X*0.75"),
+                names: Vec::new(),
+            },
+            z: Axis {
+                units: "°".to_string(),
+                data: binary_axis(TIMING_ADDRESS, 1, 64, "X*0.75-48"),
+                names: Vec::new(),
+            },
+            categories: vec![2],
+        },
+        Table {
+            name: "Injector dead time".to_string(),
+            description: "Injector opening delay by battery voltage".to_string(),
+            x: Axis {
+                units: "V".to_string(),
+                data: binary_axis(VOLTAGE_ADDRESS, 1, 8, "X*0.1"),
+                names: Vec::new(),
+            },
+            y: Axis {
+                units: String::new(),
+                data: AxisData::User(vec![0.0]),
+                names: Vec::new(),
+            },
+            z: Axis {
+                units: "ms".to_string(),
+                data: binary_axis(DEAD_TIME_ADDRESS, 2, 8, "X*0.01"),
+                names: Vec::new(),
+            },
+            categories: vec![1],
+        },
+    ];
+
+    BinaryDefinition {
+        info: DefinitionInfo {
+            name: "Demo".to_string(),
+            description: "Example definition, not for a real ECU".to_string(),
+            categories,
+        },
+        scalars,
+        tables,
+        base_offset: 0,
+        label_errors: Vec::new(),
+        expression_warnings: Vec::new(),
+    }
+}
+
+/// Binary matching [`definition`], all values little endian.
+pub fn binary() -> Vec<u8> {
+    let mut bin = vec![0u8; 0x100];
+
+    // 7000 RPM and 1.5 bar
+    bin[0x00..0x02].copy_from_slice(&28000u16.to_le_bytes());
+    bin[0x02] = 150;
+
+    for i in 0..8 {
+        // 800 to 6400 RPM
+        bin[RPM_ADDRESS as usize + i] = 20 + 20 * i as u8;
+        // 15 to 120 % load
+        bin[LOAD_ADDRESS as usize + i] = 20 + 20 * i as u8;
+        // 9 to 16 V
+        bin[VOLTAGE_ADDRESS as usize + i] = 90 + 10 * i as u8;
+        // Dead time falls as voltage rises
+        let dead_time = 150 - 12 * i as u16;
+        let offset = DEAD_TIME_ADDRESS as usize + i * 2;
+        bin[offset..offset + 2].copy_from_slice(&dead_time.to_le_bytes());
+    }
+
+    // Timing rises with RPM and falls with load
+    for load in 0..8 {
+        for rpm in 0..8 {
+            bin[TIMING_ADDRESS as usize + load * 8 + rpm] = (70 + rpm * 4 - load * 3) as u8;
+        }
+    }
+
+    bin
+}
@@ -1,9 +1,7 @@
-// TODO: error handling, remove panics and unwraps
-
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum Ops {
+enum Ops {
     Add,
     Subtract,
     Multiply,
@@ -58,17 +56,22 @@ macro_rules! do_op {
 }
 
 macro_rules! rev {
-    ($a:ident, $b:ident, $action:ident, $ops:ident) => {{
-        let av = $a.has_var();
-        let bv = $b.has_var();
-        assert_ne!(av, bv);
-
-        if av {
-            $ops.append(&mut $a.rev());
-            $ops.push(Action::$action($b.to_f64()));
+    ($a:ident, $b:ident, $action:ident, $ops:ident) => {
+        rev!($a, $b, $action, $action, $ops)
+    };
+    // `$swapped` is used when the variable is on the right hand side
+    ($a:ident, $b:ident, $action:ident, $swapped:ident, $ops:ident) => {{
+        if $a.has_var() && $b.has_var() {
+            return Err("The variable appears more than once, it cannot be solved for".to_string());
+        }
+
+        // The constant side may still be a compound expression, fold it to a number first
+        if $a.has_var() {
+            $ops.append(&mut $a.rev()?);
+            $ops.push(Action::$action($b.eval().to_f64()?));
         } else {
-            $ops.append(&mut $b.rev());
-            $ops.push(Action::$action($a.to_f64()));
+            $ops.append(&mut $b.rev()?);
+            $ops.push(Action::$swapped($a.eval().to_f64()?));
         }
     }};
 }
@@ -79,25 +82,30 @@ macro_rules! set {
     };
 }
 
-// if there is more than one variable this doesnt work
 impl Atom {
-    fn rev(self) -> Vec<Action> {
-        assert!(self.has_var());
+    /// Steps undoing the expression, fails unless the variable appears exactly once.
+    fn rev(self) -> Result<Vec<Action>, String> {
+        if !self.has_var() {
+            return Err(
+                "The expression does not use its variable, it cannot be solved for".to_string(),
+            );
+        }
         let mut ops = Vec::new();
         match self {
-            Atom::Var(_) => return ops,
+            Atom::Var(_) => (),
             Atom::Num(n) => ops.push(Action::Ret(n)),
             Atom::Add(a, b) => rev!(a, b, Sub, ops),
-            Atom::Sub(a, b) => rev!(a, b, Add, ops),
-            Atom::Div(a, b) => rev!(a, b, Mul, ops),
+            Atom::Sub(a, b) => rev!(a, b, Add, SubFrom, ops),
+            Atom::Div(a, b) => rev!(a, b, Mul, DivInto, ops),
             Atom::Mul(a, b) => rev!(a, b, Div, ops),
         }
-        ops
+        Ok(ops)
     }
-    fn to_f64(self) -> f64 {
+    /// Value of an atom folded with `eval`, fails while it still has a variable.
+    fn to_f64(self) -> Result<f64, String> {
         match self {
-            Self::Num(n) => n,
-            _ => panic!("not a raw number"),
+            Self::Num(n) => Ok(n),
+            _ => Err("Not a number".to_string()),
         }
     }
     fn has_var(&self) -> bool {
@@ -131,7 +139,7 @@ impl Atom {
 }
 
 #[derive(Debug, Clone, Copy)]
-pub(crate) enum Tokens {
+enum Tokens {
     Number(f64),
     Op(Ops),
     Var(char, bool),
@@ -139,28 +147,47 @@ pub(crate) enum Tokens {
     CloseBracket,
 }
 
-fn tokenize(str: &str) -> Vec<Tokens> {
+/// Push the number collected in `buf`, negated if a unary minus came before it.
+fn push_number(tokens: &mut Vec<Tokens>, buf: &mut String, neg: &mut bool) -> Result<(), String> {
+    if buf.is_empty() {
+        return Ok(());
+    }
+    let num: f64 = buf
+        .parse()
+        .map_err(|_| format!("Invalid number \"{buf}\""))?;
+    tokens.push(Tokens::Number(if *neg { -num } else { num }));
+    *neg = false;
+    buf.clear();
+    Ok(())
+}
+
+fn tokenize(str: &str) -> Result<Vec<Tokens>, String> {
     let mut tokens: Vec<Tokens> = Vec::new();
     let mut buf = String::new();
     let mut neg = false;
     for c in str.chars() {
+        if let '0'..='9' | '.' = c {
+            buf.push(c);
+            continue;
+        }
+        push_number(&mut tokens, &mut buf, &mut neg)?;
+
         match c {
-            '0'..='9' | '.' => {
-                buf.push(c);
-                continue;
-            }
             '/' => tokens.push(Tokens::Op(Ops::Divide)),
             '*' => tokens.push(Tokens::Op(Ops::Multiply)),
             '+' => tokens.push(Tokens::Op(Ops::Add)),
-            '-' => {
-                if let Some(last) = tokens.last() {
-                    match last {
-                        Tokens::OpenBracket | Tokens::Op(_) if buf.is_empty() => neg = !neg,
-                        _ => tokens.push(Tokens::Op(Ops::Subtract)),
-                    }
-                } else {
-                    neg = !neg;
-                }
+            '-' => match tokens.last() {
+                None | Some(Tokens::OpenBracket | Tokens::Op(_)) => neg = !neg,
+                _ => tokens.push(Tokens::Op(Ops::Subtract)),
+            },
+            // A unary minus before a bracket negates the whole bracket, not its first value
+            '(' | '[' if neg => {
+                tokens.extend([
+                    Tokens::Number(-1.0),
+                    Tokens::Op(Ops::Multiply),
+                    Tokens::OpenBracket,
+                ]);
+                neg = false;
             }
             'a'..='z' | 'A'..='Z' => {
                 tokens.push(Tokens::Var(c, neg));
@@ -170,48 +197,40 @@ fn tokenize(str: &str) -> Vec<Tokens> {
             ')' | ']' => tokens.push(Tokens::CloseBracket),
             _ => continue,
         }
-        if !buf.is_empty() {
-            let prev = tokens.pop().unwrap();
-            let mut num = buf.parse().unwrap();
-            if neg {
-                num *= -1.0;
-                neg = false;
-            }
-            tokens.push(Tokens::Number(num));
-            tokens.push(prev);
-            buf.clear();
-        }
-    }
-    if !buf.is_empty() {
-        tokens.push(Tokens::Number(buf.parse().unwrap()));
-        buf.clear();
     }
+    push_number(&mut tokens, &mut buf, &mut neg)?;
 
-    tokens
+    Ok(tokens)
 }
 
-fn ast_shunting_yard(tokens: Vec<Tokens>) -> Atom {
+fn ast_shunting_yard(tokens: Vec<Tokens>) -> Result<Atom, String> {
     let mut output = Vec::new();
     let mut ops = Vec::new();
 
-    fn do_op(stack: &mut Vec<Atom>, op: Ops) {
-        let b = Box::new(stack.pop().unwrap());
-        let a = Box::new(stack.pop().unwrap());
+    fn do_op(stack: &mut Vec<Atom>, op: Ops) -> Result<(), String> {
+        if op == Ops::OpenBracket {
+            return Err("Unmatched opening bracket".to_string());
+        }
+        let missing = || format!("Missing operand for {op:?}");
+        let b = Box::new(stack.pop().ok_or_else(missing)?);
+        let a = Box::new(stack.pop().ok_or_else(missing)?);
         stack.push(match op {
             Ops::Add => Atom::Add(a, b),
             Ops::Subtract => Atom::Sub(a, b),
             Ops::Multiply => Atom::Mul(a, b),
             Ops::Divide => Atom::Div(a, b),
-            _ => panic!("Unexpected parentheses."),
+            Ops::OpenBracket => unreachable!(),
         });
+        Ok(())
     }
 
     for token in tokens {
         match token {
             Tokens::Number(n) => output.push(Atom::Num(n)),
             Tokens::Op(op) => {
-                while ops.last().is_some_and(|o| o >= &op) {
-                    do_op(&mut output, ops.pop().unwrap());
+                while let Some(&last) = ops.last().filter(|o| *o >= &op) {
+                    ops.pop();
+                    do_op(&mut output, last)?;
                 }
                 ops.push(op)
             }
@@ -225,7 +244,7 @@ fn ast_shunting_yard(tokens: Vec<Tokens>) -> Atom {
                 while let Some(op) = ops.pop() {
                     match op {
                         Ops::OpenBracket => break,
-                        o => do_op(&mut output, o),
+                        o => do_op(&mut output, o)?,
                     }
                 }
             }
@@ -233,11 +252,14 @@ fn ast_shunting_yard(tokens: Vec<Tokens>) -> Atom {
     }
 
     while let Some(op) = ops.pop() {
-        do_op(&mut output, op);
+        do_op(&mut output, op)?;
     }
 
-    assert!(output.len() == 1);
-    output.pop().unwrap()
+    match (output.pop(), output.is_empty()) {
+        (Some(ast), true) => Ok(ast),
+        (None, _) => Err("Empty expression".to_string()),
+        (Some(_), false) => Err("Missing operator between values".to_string()),
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -246,6 +268,10 @@ enum Action {
     Sub(f64),
     Mul(f64),
     Div(f64),
+    /// Subtract the number from this value
+    SubFrom(f64),
+    /// Divide this value by the number
+    DivInto(f64),
     Ret(f64),
 }
 
@@ -257,6 +283,8 @@ fn exec_actions(mut actions: Vec<Action>, mut num: f64) -> f64 {
             Action::Sub(n) => num -= n,
             Action::Mul(n) => num *= n,
             Action::Div(n) => num /= n,
+            Action::SubFrom(n) => num = n - num,
+            Action::DivInto(n) => num = n / num,
             Action::Ret(n) => return n,
         }
     }
@@ -264,18 +292,128 @@ fn exec_actions(mut actions: Vec<Action>, mut num: f64) -> f64 {
     num
 }
 
-pub fn eval_reverse(expr: &str, num: f64) -> f64 {
-    exec_actions(ast_shunting_yard(tokenize(expr)).eval().rev(), num)
+/// Conversion expression with a single variable, parsed once to be evaluated many times.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    ast: Atom,
+}
+
+/// Parse an expression such as `X*0.1+5`, fails on malformed input.
+pub fn parse(expr: &str) -> Result<Expression, String> {
+    Ok(Expression {
+        ast: ast_shunting_yard(tokenize(expr)?)?,
+    })
 }
 
-pub fn eval(expr: &str, var: u32) -> f64 {
-    let mut vars = HashMap::new();
-    vars.insert('X', var.into());
-    vars.insert('x', var.into());
-    let ast = ast_shunting_yard(tokenize(expr));
-    if let Atom::Num(f) = ast.clone().set_vars(&vars).eval() {
-        f
-    } else {
-        panic!("fail")
+impl Expression {
+    pub fn eval(&self, var: impl Into<f64>) -> f64 {
+        let var = var.into();
+        let mut vars = HashMap::new();
+        vars.insert('X', var);
+        vars.insert('x', var);
+        match self.ast.clone().set_vars(&vars).eval() {
+            Atom::Num(f) => f,
+            _ => unreachable!("all variables are set"),
+        }
+    }
+
+    /// Whether the expression depends on its variable at all.
+    pub fn uses_var(&self) -> bool {
+        self.ast.has_var()
+    }
+
+    /// Solve for the variable, fails unless it appears exactly once in the expression.
+    pub fn eval_reverse(&self, num: f64) -> Result<f64, String> {
+        Ok(exec_actions(self.ast.clone().rev()?, num))
+    }
+}
+
+pub fn eval_reverse(expr: &str, num: f64) -> Result<f64, String> {
+    parse(expr)?.eval_reverse(num)
+}
+
+/// Evaluate an expression once, NaN when it does not parse.
+pub fn eval(expr: &str, var: impl Into<f64>) -> f64 {
+    parse(expr).map_or(f64::NAN, |expression| expression.eval(var))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("2+3*4", 0), 14.0);
+        assert_eq!(eval("2*3+4", 0), 10.0);
+        assert_eq!(eval("10-4-3", 0), 3.0);
+        assert_eq!(eval("16/4/2", 0), 2.0);
+    }
+
+    #[test]
+    fn brackets() {
+        assert_eq!(eval("(2+3)*4", 0), 20.0);
+        assert_eq!(eval("2*(3+4)", 0), 14.0);
+        assert_eq!(eval("[(1+1)*(X+1)]", 2), 6.0);
+        assert!(parse("(X+1").is_err());
+    }
+
+    #[test]
+    fn negatives() {
+        assert_eq!(eval("-2+X", 5), 3.0);
+        assert_eq!(eval("X*-2", 3), -6.0);
+        assert_eq!(eval("-X", 4), -4.0);
+        assert_eq!(eval("5--X", 2), 7.0);
+        assert_eq!(eval("-(2+X)", 1), -3.0);
+        assert_eq!(eval("-(2+X)*3", 1), -9.0);
+        assert_eq!(eval("4-(2+X)", 1), 1.0);
+    }
+
+    #[test]
+    fn reverse_round_trips() {
+        for expr in [
+            "X*0.1+5",
+            "X",
+            "(X-40)*0.75",
+            "100/X",
+            "5-X",
+            "-(2+X)",
+            "X*-2",
+        ] {
+            let expression = parse(expr).unwrap();
+            for raw in [1.0, 12.0, 255.0] {
+                let value = expression.eval(raw);
+                let back = expression.eval_reverse(value).unwrap();
+                assert!(close(back, raw), "{expr}: {raw} -> {value} -> {back}");
+            }
+        }
+        assert!(close(eval_reverse("X*0.1+5", 15.0).unwrap(), 100.0));
+    }
+
+    #[test]
+    fn reverse_folds_constant_subexpressions() {
+        assert!(close(eval_reverse("X*(2+3)", 10.0).unwrap(), 2.0));
+        assert!(close(eval_reverse("(2+3)*X", 10.0).unwrap(), 2.0));
+        assert!(close(eval_reverse("10/(1+1)-X", 10.0).unwrap(), -5.0));
+        assert!(close(eval_reverse("(X+1)/(4-2)", 3.0).unwrap(), 5.0));
+        assert!(close(eval_reverse("(6/3)/X", 0.5).unwrap(), 4.0));
+    }
+
+    #[test]
+    fn reverse_needs_one_variable() {
+        assert!(eval_reverse("1", 5.0).is_err());
+        assert!(eval_reverse("2*3", 5.0).is_err());
+        assert!(eval_reverse("X*X", 4.0).is_err());
+        assert!(eval_reverse("X+", 4.0).is_err());
+    }
+
+    #[test]
+    fn invalid_expression() {
+        assert!(eval("X+", 1).is_nan());
+        assert!(parse("").is_err());
+        assert!(parse("1 2").is_err());
     }
 }
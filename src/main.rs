@@ -1,25 +1,49 @@
 #![feature(iterator_try_collect)]
 #![feature(iter_map_windows)]
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs::File;
+use std::io::Cursor;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::anyhow;
 use anyhow::bail;
+use checksum::Checksums;
 use definitions::{Scalar, Table};
 
-use iced::widget::pane_grid;
-use iced::{Element, Task};
-use rfd::FileDialog;
-use views::map_nav::MapNav;
+use iced::keyboard::{self, key::Named, Key};
+use iced::widget::{pane_grid, scrollable};
+use iced::{Element, Subscription, Task};
+use ops::{BulkOp, Interpolation, PasteMode, Target};
+use project::{ChartLayout, Preset, Project};
+use rfd::{FileDialog, MessageButtons, MessageDialog, MessageDialogResult};
+use settings::Settings;
+use tune::Tune;
+use views::compare::DeltaMode;
+use views::expr_tester::TesterField;
+use views::map_nav::{MapNav, NavAction, NavItem};
 use views::panes::{PaneAction, PaneContent};
-use views::table::EditSource;
+use views::probe::{Endian, ProbeField};
+use views::region::RegionField;
+use views::table::{
+    Decimals, Density, DisplayField, EditSource, Extreme, ImageSize, MoveDirection, Overlay,
+    SelectAction, TableAxis,
+};
 use xdftuneparser::data_types::XDFElement;
 use xdftuneparser::parse_buffer;
 
+pub mod checksum;
+pub mod colormap;
 pub mod definitions;
+pub mod demo;
 pub mod eval;
+pub mod ops;
+pub mod project;
+pub mod settings;
+pub mod tune;
 
 mod views;
 
@@ -36,12 +60,61 @@ impl<RW> Clone for RWGuarded<RW> {
     }
 }
 
-pub type FileGuard = RWGuarded<File>;
+/// Where the binary being edited lives
+#[derive(Debug)]
+pub enum BinaryStorage {
+    File(File),
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl std::io::Read for BinaryStorage {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            BinaryStorage::File(f) => f.read(buf),
+            BinaryStorage::Memory(m) => m.read(buf),
+        }
+    }
+}
 
-impl From<File> for RWGuarded<File> {
+impl std::io::Seek for BinaryStorage {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        match self {
+            BinaryStorage::File(f) => f.seek(pos),
+            BinaryStorage::Memory(m) => m.seek(pos),
+        }
+    }
+}
+
+impl std::io::Write for BinaryStorage {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            BinaryStorage::File(f) => f.write(buf),
+            BinaryStorage::Memory(m) => m.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            BinaryStorage::File(f) => f.flush(),
+            BinaryStorage::Memory(m) => m.flush(),
+        }
+    }
+}
+
+pub type FileGuard = RWGuarded<BinaryStorage>;
+
+impl From<File> for RWGuarded<BinaryStorage> {
     fn from(value: File) -> Self {
         Self {
-            inner: Arc::new(Mutex::new(value)),
+            inner: Arc::new(Mutex::new(BinaryStorage::File(value))),
+        }
+    }
+}
+
+impl From<Vec<u8>> for RWGuarded<BinaryStorage> {
+    fn from(value: Vec<u8>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(BinaryStorage::Memory(Cursor::new(value)))),
         }
     }
 }
@@ -75,10 +148,33 @@ impl<RW: std::io::Write> std::io::Write for RWGuarded<RW> {
 pub struct App {
     /// Binaries, mapped to their names and corresponding definition
     binary: FileGuard,
+    /// `None` when the binary only exists in memory
+    bin_path: Option<PathBuf>,
+    /// Second binary to compare tables against, kept in memory and never written
+    reference: Option<FileGuard>,
+    settings: Settings,
+    /// Set once the original binary has been backed up this session
+    backed_up: bool,
+    nav: MapNav,
+    /// Nav sidebar is collapsed to give the panes more room
+    nav_collapsed: bool,
     panes: pane_grid::State<views::panes::Pane>,
     panes_created: usize,
     pane_id_map: HashMap<usize, pane_grid::Pane>,
     focus: Option<pane_grid::Pane>,
+    /// Keyboard modifiers currently held, used for multi-select clicks
+    modifiers: keyboard::Modifiers,
+    last_autosave: Option<SystemTime>,
+    /// Autosave failed and stays off until a pane is written, so the failure is shown once
+    autosave_paused: bool,
+    project: Project,
+    /// `None` when the project is only kept in memory
+    project_path: Option<PathBuf>,
+    /// Names of the tables and scalars written this session, exported as a tune
+    committed_tables: BTreeSet<String>,
+    committed_scalars: BTreeSet<String>,
+    /// Regions from `--checksums`, empty without it
+    checksums: Checksums,
 }
 
 macro_rules! get_pane_content {
@@ -109,78 +205,1003 @@ macro_rules! write_table_axis {
 }
 
 impl App {
-    fn new(bin: File, def: definitions::BinaryDefinition) -> Self {
-        let mut nav = MapNav::default();
-        nav.tables = def.tables.clone();
-        nav.scalars = def.scalars.clone();
-        let (panes, nav_pane) = pane_grid::State::new(views::panes::Pane::nav(def.clone()));
-        let mut pane_id_map = HashMap::new();
-        pane_id_map.insert(0, nav_pane.clone());
-        Self {
-            binary: FileGuard::from(bin),
+    fn new(
+        binary: FileGuard,
+        bin_path: Option<PathBuf>,
+        def: definitions::BinaryDefinition,
+        settings: Settings,
+        errors: Vec<String>,
+    ) -> Self {
+        let overlaps = def.overlaps();
+        let label_errors = def.label_errors.clone();
+        let expression_warnings = def.expression_warnings.clone();
+        let project_path = settings
+            .project
+            .clone()
+            .or_else(|| bin_path.as_deref().map(Project::default_path));
+        // An unreadable project is not saved over so its notes are not lost
+        let (project, project_path, project_error) =
+            match project_path.as_deref().map(Project::load) {
+                Some(Ok(project)) => (project, project_path, None),
+                Some(Err(e)) => (
+                    Project::default(),
+                    None,
+                    Some(format!(
+                        "Could not read project file {}, notes will not be saved: {e}",
+                        project_path.unwrap().display()
+                    )),
+                ),
+                None => (Project::default(), None, None),
+            };
+        let (checksums, checksums_error) = match settings.checksums.as_deref().map(Checksums::load)
+        {
+            Some(Ok(checksums)) => (checksums, None),
+            Some(Err(e)) => (
+                Checksums::default(),
+                Some(format!(
+                    "Could not read checksum file {}, checksums will not be updated: {e}",
+                    settings.checksums.as_ref().unwrap().display()
+                )),
+            ),
+            None => (Checksums::default(), None),
+        };
+        let (panes, empty_pane) = pane_grid::State::new(views::panes::Pane::empty());
+        let mut app = Self {
+            binary,
+            bin_path,
+            reference: None,
+            settings,
+            backed_up: false,
+            nav: MapNav::new(def),
+            nav_collapsed: false,
             panes,
-            panes_created: 1,
-            pane_id_map,
-            focus: Some(nav_pane),
+            panes_created: 0,
+            pane_id_map: HashMap::new(),
+            focus: Some(empty_pane),
+            modifiers: keyboard::Modifiers::default(),
+            last_autosave: None,
+            autosave_paused: false,
+            project,
+            project_path,
+            committed_tables: BTreeSet::new(),
+            committed_scalars: BTreeSet::new(),
+            checksums,
+        };
+
+        for error in errors {
+            let binary = app.binary.clone();
+            views::panes::open(&mut app, Open::Error(error), binary);
+        }
+
+        for error in [project_error, checksums_error].into_iter().flatten() {
+            let binary = app.binary.clone();
+            views::panes::open(&mut app, Open::Warning(error), binary);
+        }
+
+        if !overlaps.is_empty() {
+            let warning = format!(
+                "Definitions share bytes, editing one will change the other:\n{}",
+                overlaps.join("\n")
+            );
+            let binary = app.binary.clone();
+            views::panes::open(&mut app, Open::Warning(warning), binary);
         }
+
+        if !label_errors.is_empty() {
+            let warning = format!(
+                "Axis labels are not numbers and are shown as NaN, start with --lenient-labels to read text labels as names and empty ones as 0:\n{}",
+                label_errors.join("\n")
+            );
+            let binary = app.binary.clone();
+            views::panes::open(&mut app, Open::Warning(warning), binary);
+        }
+
+        if !expression_warnings.is_empty() {
+            let warning = format!(
+                "Conversion expressions are not fully supported:\n{}",
+                expression_warnings.join("\n")
+            );
+            let binary = app.binary.clone();
+            views::panes::open(&mut app, Open::Warning(warning), binary);
+        }
+        app
     }
     fn view(&self) -> Element<Message> {
-        views::panes::view_grid(self)
+        iced::widget::column![iced::widget::row![
+            self.nav.sidebar(
+                self.nav_collapsed,
+                self.settings.thousands_separators,
+                self.settings.placement
+            ),
+            views::panes::view_grid(self)
+        ]
+        .height(iced::Length::Fill)]
+        .push_maybe(self.status_bar())
+        .into()
     }
-    fn update(&mut self, message: Message) {
-        if let Err(e) = self.try_update(message) {
-            let pane = views::panes::open(self, Open::Error(e.to_string()), self.binary.clone())
-                .expect("Failed to display error message!");
-            self.panes.maximize(pane);
+    /// Autosave interval and when it last ran, only shown with autosave enabled.
+    fn status_bar(&self) -> Option<Element<Message>> {
+        let interval = self.settings.autosave?;
+        let last = match self
+            .last_autosave
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        {
+            Some(time) => {
+                let seconds = time.as_secs() % 86400;
+                format!(
+                    "last at {:02}:{:02}:{:02} UTC",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                )
+            }
+            None => "not run yet".to_string(),
+        };
+        let last = if self.autosave_paused {
+            format!("{last}, paused after a failed write until a pane is written")
+        } else {
+            last
+        };
+        Some(
+            iced::widget::container(
+                iced::widget::text(format!("Autosave every {}s, {last}", interval.as_secs()))
+                    .size(14),
+            )
+            .padding([2, 10])
+            .into(),
+        )
+    }
+    fn subscription(&self) -> Subscription<Message> {
+        let shortcuts = iced::event::listen_with(shortcuts);
+        match self.settings.autosave.filter(|_| !self.autosave_paused) {
+            Some(interval) => Subscription::batch([
+                shortcuts,
+                iced::time::every(interval).map(|_| Message::Autosave),
+            ]),
+            None => shortcuts,
         }
     }
-    fn try_update(&mut self, message: Message) -> anyhow::Result<()> {
+    fn update(&mut self, message: Message) -> Task<Message> {
+        let write = matches!(
+            message,
+            Message::WriteTable { .. }
+                | Message::WriteScalar { .. }
+                | Message::WriteScalarGroup { .. }
+                | Message::WriteAll
+        );
+        match self.try_update(message) {
+            Ok(task) => {
+                if write {
+                    self.autosave_paused = false;
+                }
+                task
+            }
+            Err(e) => {
+                let pane =
+                    views::panes::open(self, Open::Error(e.to_string()), self.binary.clone())
+                        .expect("Failed to display error message!");
+                self.panes.maximize(pane);
+                Task::none()
+            }
+        }
+    }
+    /// Copy the binary to a timestamped `.bak` file, only done before the first write of a session.
+    fn backup_binary(&mut self) -> anyhow::Result<()> {
+        let (false, Some(bin_path)) = (self.backed_up, &self.bin_path) else {
+            return Ok(());
+        };
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let file_name = bin_path
+            .file_name()
+            .ok_or(anyhow!("Binary path has no file name"))?
+            .to_string_lossy();
+        let dir = match &self.settings.backup_dir {
+            Some(dir) => dir.clone(),
+            None => bin_path
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_default(),
+        };
+
+        std::fs::create_dir_all(&dir)?;
+        std::fs::copy(bin_path, dir.join(format!("{file_name}.{timestamp}.bak")))?;
+
+        self.backed_up = true;
+        Ok(())
+    }
+    /// File name of the binary, or "demo binary" when it only exists in memory
+    fn binary_name(&self) -> String {
+        self.bin_path
+            .as_deref()
+            .and_then(|path| path.file_name())
+            .map_or("demo binary".to_string(), |name| {
+                name.to_string_lossy().into_owned()
+            })
+    }
+
+    /// Window title naming the definition and binary, marked with `*` while there are edits
+    fn title(&self) -> String {
+        let dirty = self.panes.iter().any(|(_, p)| p.content.is_dirty());
+        let mut parts = vec!["HEXTuner".to_string()];
+        // Definitions do not have to name themselves
+        if !self.nav.title.is_empty() {
+            parts.push(self.nav.title.clone());
+        }
+        parts.push(self.binary_name());
+        let title = parts.join(" - ");
+        if dirty {
+            format!("{title} *")
+        } else {
+            title
+        }
+    }
+
+    /// Ask before an action that throws away unwritten edits, true if there are none.
+    fn confirm_discard(&self, title: &str) -> bool {
+        let dirty = self.panes.iter().any(|(_, p)| p.content.is_dirty());
+        !dirty || ask_discard(title)
+    }
+    /// Read again the panes other than `writer` that show bytes in `ranges`, so they do not
+    /// keep showing what was there before a write. Cells with their own edits keep them.
+    fn reload_affected(&mut self, ranges: &[Range<u64>], writer: usize) -> anyhow::Result<()> {
+        let writer = self.pane_id_map.get(&writer).copied();
+        for (id, pane) in self.panes.iter_mut() {
+            if Some(*id) != writer && ranges.iter().any(|range| pane.content.reads(range)) {
+                pane.content.refresh()?;
+            }
+        }
+        Ok(())
+    }
+    /// Store the configured checksums again after a write, returning the bytes they are in.
+    fn update_checksums(&mut self) -> anyhow::Result<Vec<Range<u64>>> {
+        Ok(self
+            .checksums
+            .update_after_write(&mut self.binary.clone())?)
+    }
+    /// Show the presets of a scalar again in every pane of it and save them.
+    fn update_presets(&mut self, scalar: &str) -> anyhow::Result<()> {
+        for (_, pane) in self.panes.iter_mut() {
+            if let PaneContent::Scalar(s) = &mut pane.content {
+                if s.scalar.name == scalar {
+                    s.presets = self.project.presets.get(scalar).to_vec();
+                }
+            }
+        }
+        if let Some(path) = &self.project_path {
+            self.project.save(path)?;
+        }
+        Ok(())
+    }
+    /// Keep the chart layout of a table for the next time it is opened.
+    fn save_chart_layout(&mut self, map: String, layout: ChartLayout) -> anyhow::Result<()> {
+        self.project.charts.insert(map, layout);
+        if let Some(path) = &self.project_path {
+            self.project.save(path)?;
+        }
+        Ok(())
+    }
+    fn try_update(&mut self, message: Message) -> anyhow::Result<Task<Message>> {
         match message {
-            Message::Open(kind) => {
-                views::panes::open(self, kind, self.binary.clone());
+            Message::NavClick(item) => {
+                if self.modifiers.command() || self.modifiers.shift() {
+                    self.nav.toggle_selected(item);
+                } else {
+                    let kind = self.nav.open_kind(item);
+                    views::panes::open(self, kind, self.binary.clone());
+                }
+            }
+            Message::NavMenu(item) => self.nav.menu = item,
+            Message::NavAction(item, action) => {
+                self.nav.menu = None;
+                match action {
+                    NavAction::Open => {
+                        let kind = self.nav.open_kind(item);
+                        views::panes::open(self, kind, self.binary.clone());
+                    }
+                    NavAction::OpenSplit => {
+                        let kind = self.nav.open_kind(item);
+                        views::panes::open_beside(self, kind, self.binary.clone());
+                    }
+                    NavAction::CopyAddress => {
+                        if let Some(address) = self.nav.address(item) {
+                            return Ok(iced::clipboard::write(format!("0x{address:X}")));
+                        }
+                    }
+                    NavAction::ShowDefinition => {
+                        let name = self.nav.name(item).to_string();
+                        let text = self.nav.definition(item);
+                        views::panes::open(
+                            self,
+                            Open::Definition { name, text },
+                            self.binary.clone(),
+                        );
+                    }
+                    NavAction::ShowStorage => {
+                        let title = format!("{} (storage)", self.nav.name(item));
+                        let text = self.nav.storage(item);
+                        views::panes::open(
+                            self,
+                            Open::Summary { title, text },
+                            self.binary.clone(),
+                        );
+                    }
+                    NavAction::Reset => {
+                        let name = self.nav.name(item).to_string();
+                        for (_, pane) in self.panes.iter_mut() {
+                            // Only the scalar's own row of a group is read again
+                            if let (PaneContent::ScalarGroup(g), NavItem::Scalar(_)) =
+                                (&mut pane.content, item)
+                            {
+                                for row in g.rows.iter_mut().filter(|row| row.scalar.name == name) {
+                                    row.reload()?;
+                                }
+                                continue;
+                            }
+                            let shows_item = match (&pane.content, item) {
+                                (PaneContent::Table(t), NavItem::Table(_)) => t.table.name == name,
+                                (PaneContent::Scalar(s), NavItem::Scalar(_)) => {
+                                    s.scalar.name == name
+                                }
+                                _ => false,
+                            };
+                            if shows_item {
+                                pane.content.reload()?;
+                            }
+                        }
+                    }
+                }
+            }
+            Message::OpenSelected => {
+                let kinds = self.nav.take_selected();
+                views::panes::open_tiled(self, kinds, self.binary.clone());
+            }
+            Message::ToggleNav => self.nav_collapsed = !self.nav_collapsed,
+            Message::Autosave => {
+                // Panes that could not be written are left for the user to fix
+                let writes: Vec<Message> = self
+                    .panes
+                    .iter()
+                    .filter_map(|(_, pane)| match &pane.content {
+                        PaneContent::Table(t) if t.is_dirty() && t.check_valid().is_ok() => {
+                            Some(Message::WriteTable { pane: t.pane_id() })
+                        }
+                        PaneContent::Scalar(s)
+                            if s.is_dirty() && s.error.is_none() && s.is_valid() =>
+                        {
+                            Some(Message::WriteScalar { pane: s.pane_id() })
+                        }
+                        PaneContent::ScalarGroup(g) if !g.writable().is_empty() => {
+                            Some(Message::WriteScalarGroup { pane: g.pane_id() })
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let tasks: anyhow::Result<Vec<Task<Message>>> = writes
+                    .into_iter()
+                    .map(|write| self.try_update(write))
+                    .try_collect();
+                let tasks = match tasks {
+                    Ok(tasks) => tasks,
+                    Err(e) => {
+                        // Reported once instead of on every tick
+                        self.autosave_paused = true;
+                        bail!("Autosave failed, it is paused until a pane is written: {e:#}");
+                    }
+                };
+                self.last_autosave = Some(SystemTime::now());
+                return Ok(Task::batch(tasks));
+            }
+            Message::ReloadBinary => {
+                if !self.confirm_discard("Reload binary") {
+                    return Ok(Task::none());
+                }
+                for (_, pane) in self.panes.iter_mut() {
+                    pane.content.reload()?;
+                }
+            }
+            Message::LoadReference => {
+                if let Some(path) = FileDialog::new().add_filter("BIN", &["bin"]).pick_file() {
+                    self.reference = Some(FileGuard::from(std::fs::read(path)?));
+                    // Comparisons already open switch to the new reference
+                    for (_, pane) in self.panes.iter_mut() {
+                        if let PaneContent::TableCompare(c) = &mut pane.content {
+                            let table = c.table.clone();
+                            let view = views::compare::TableCompareView::new(
+                                c.pane_id(),
+                                table,
+                                self.binary.clone(),
+                                self.reference.clone().unwrap(),
+                            )?;
+                            *c = view;
+                        }
+                    }
+                }
+            }
+            Message::CompareTable { pane } => {
+                let table = get_pane_content!(Table, self, pane).table.clone();
+                views::panes::open(self, Open::TableCompare(table), self.binary.clone());
+            }
+            Message::SetDeltaMode { pane, mode } => {
+                get_pane_content!(TableCompare, self, pane).mode = mode;
+            }
+            Message::CompareScrolled { pane, viewport } => {
+                get_pane_content!(TableCompare, self, pane).scrolled(viewport);
+            }
+            Message::RefreshCompare { pane } => {
+                get_pane_content!(TableCompare, self, pane).reload()?;
+            }
+            Message::ExportTune => {
+                let tables = self
+                    .nav
+                    .tables
+                    .iter()
+                    .filter(|t| self.committed_tables.contains(&t.name));
+                let scalars = self
+                    .nav
+                    .scalars
+                    .iter()
+                    .filter(|s| self.committed_scalars.contains(&s.name));
+                let tune = Tune::capture(&mut self.binary.clone(), tables, scalars)?;
+                if let Some(path) = FileDialog::new()
+                    .add_filter("Tune", &["json"])
+                    .set_file_name("tune.json")
+                    .save_file()
+                {
+                    std::fs::write(path, serde_json::to_string_pretty(&tune)?)?;
+                }
+            }
+            Message::ImportTune => {
+                let Some(path) = FileDialog::new().add_filter("Tune", &["json"]).pick_file() else {
+                    return Ok(Task::none());
+                };
+                let tune: Tune = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+                // Open panes are read again afterwards to show the imported values
+                if !self.confirm_discard("Import tune") {
+                    return Ok(Task::none());
+                }
+                self.backup_binary()?;
+                let skipped = tune.apply(
+                    &mut self.binary.clone(),
+                    &self.nav.tables,
+                    &self.nav.scalars,
+                )?;
+                self.committed_tables.extend(tune.tables.into_keys());
+                self.committed_scalars.extend(tune.scalars.into_keys());
+                self.update_checksums()?;
+                for (_, pane) in self.panes.iter_mut() {
+                    pane.content.reload()?;
+                }
+                if !skipped.is_empty() {
+                    let warning = format!(
+                        "Some tune values do not match the loaded definition and were not applied:\n{}",
+                        skipped.join("\n")
+                    );
+                    views::panes::open(self, Open::Warning(warning), self.binary.clone());
+                }
+            }
+            Message::VerifyChecksums => {
+                if self.checksums.regions.is_empty() {
+                    bail!("No checksum regions, start with --checksums <file> to describe them");
+                }
+                let mut binary = self.binary.clone();
+                let lines: Vec<String> = self
+                    .checksums
+                    .regions
+                    .iter()
+                    .map(|region| region.verify(&mut binary))
+                    .try_collect()?;
+                let text = format!(
+                    "Checked against the binary as written, unwritten edits are not included.\n\n{}",
+                    lines.join("\n")
+                );
+                views::panes::open(
+                    self,
+                    Open::Summary {
+                        title: "Checksums".to_string(),
+                        text,
+                    },
+                    self.binary.clone(),
+                );
+            }
+            Message::OpenExprTester => {
+                views::panes::open(self, Open::ExprTester, self.binary.clone());
+            }
+            Message::OpenProbe => {
+                views::panes::open(self, Open::Probe, self.binary.clone());
+            }
+            Message::EditProbe { pane, field, value } => {
+                get_pane_content!(Probe, self, pane).edit(field, value);
+            }
+            Message::SetProbeSize { pane, size } => {
+                get_pane_content!(Probe, self, pane).set_size(size);
+            }
+            Message::SetProbeEndian { pane, endian } => {
+                get_pane_content!(Probe, self, pane).set_endian(endian);
+            }
+            Message::OpenRegion => {
+                views::panes::open(self, Open::Region, self.binary.clone());
+            }
+            Message::EditRegion { pane, field, value } => {
+                get_pane_content!(Region, self, pane).edit(field, value);
+            }
+            Message::SetRegionSize { pane, size } => {
+                get_pane_content!(Region, self, pane).set_size(size);
+            }
+            Message::SetRegionEndian { pane, endian } => {
+                get_pane_content!(Region, self, pane).set_endian(endian);
             }
+            Message::OpenRegionTable { pane } => {
+                let table = get_pane_content!(Region, self, pane)
+                    .table()
+                    .map_err(|e| anyhow!(e))?;
+                views::panes::open(self, Open::Table(table), self.binary.clone());
+            }
+            Message::EditTester { pane, field, value } => {
+                get_pane_content!(ExprTester, self, pane).edit(field, value);
+            }
+            Message::ToggleSeparators => {
+                let separators = !self.settings.thousands_separators;
+                self.settings.thousands_separators = separators;
+                for (_, pane) in self.panes.iter_mut() {
+                    pane.content.set_separators(separators);
+                }
+            }
+            Message::SetPlacement(placement) => self.settings.placement = placement,
+            Message::ModifiersChanged(modifiers) => self.modifiers = modifiers,
+            Message::UndoEdit | Message::RedoEdit => {
+                let Some(pane) = self.focus.and_then(|p| self.panes.get_mut(p)) else {
+                    return Ok(Task::none());
+                };
+                if let PaneContent::Table(table_view) = &mut pane.content {
+                    if let Message::UndoEdit = message {
+                        table_view.undo()?;
+                    } else {
+                        table_view.redo()?;
+                    }
+                }
+            }
+            Message::CancelEdit => match self
+                .focus
+                .and_then(|p| self.panes.get_mut(p))
+                .map(|p| &mut p.content)
+            {
+                Some(PaneContent::Table(table_view)) => table_view.cancel_edit(),
+                Some(PaneContent::Scalar(scalar_view)) => scalar_view.cancel_edit(),
+                _ => {}
+            },
             Message::EditCell {
                 value,
                 pane,
                 source,
             } => {
+                get_pane_content!(Table, self, pane).edit(source, value)?;
+            }
+            Message::EditFormula { pane, value } => {
+                get_pane_content!(Table, self, pane).edit_formula(value)?;
+            }
+            Message::CommitFormula { pane } => {
+                get_pane_content!(Table, self, pane).commit_formula()?;
+            }
+            Message::WriteTable { pane } => {
                 let table_view = get_pane_content!(Table, self, pane);
-                match source {
-                    EditSource::YHead(n) => table_view.y_head[n] = value,
-                    EditSource::XHead(n) => table_view.x_head[n] = value,
-                    EditSource::Data(n) => table_view.data[n] = value,
+                table_view.check_valid()?;
+                // Axes without changes are left alone, locked axes are never written
+                let changed: Vec<TableAxis> = [TableAxis::X, TableAxis::Y, TableAxis::Z]
+                    .into_iter()
+                    .filter(|&axis| !(table_view.axes_locked && axis != TableAxis::Z))
+                    .filter(|&axis| table_view.axis_modified(axis))
+                    .collect();
+                if changed.is_empty() {
+                    return Ok(Task::none());
                 }
+                self.backup_binary()?;
+                let table_view = get_pane_content!(Table, self, pane);
+                for &axis in &changed {
+                    match axis {
+                        TableAxis::X => write_table_axis!(
+                            table_view.table.x,
+                            table_view.x_head.iter(),
+                            table_view.source
+                        ),
+                        TableAxis::Y => write_table_axis!(
+                            table_view.table.y,
+                            table_view.y_head.iter(),
+                            table_view.source
+                        ),
+                        TableAxis::Z => {
+                            // Rows that were never read are written back as they are stored
+                            let data = table_view.z_values()?.into_owned();
+                            write_table_axis!(table_view.table.z, data.iter(), table_view.source)
+                        }
+                    }
+                }
+                table_view.read_back(&changed)?;
+                table_view.refresh_chart();
+                let name = table_view.table.name.clone();
+                let table = &table_view.table;
+                let mut written: Vec<Range<u64>> = changed
+                    .iter()
+                    .filter_map(|axis| match axis {
+                        TableAxis::X => table.x.byte_range(),
+                        TableAxis::Y => table.y.byte_range(),
+                        TableAxis::Z => table.z.byte_range(),
+                    })
+                    .collect();
+                self.committed_tables.insert(name);
+                written.extend(self.update_checksums()?);
+                self.reload_affected(&written, pane)?;
             }
-            Message::WriteTable { pane } => {
+            Message::ReloadTable { pane } => {
                 let table_view = get_pane_content!(Table, self, pane);
-                write_table_axis!(
-                    table_view.table.x,
-                    table_view.x_head.iter(),
-                    table_view.source
-                );
-                write_table_axis!(
-                    table_view.table.y,
-                    table_view.y_head.iter(),
-                    table_view.source
-                );
-                write_table_axis!(
-                    table_view.table.z,
-                    table_view.data.iter(),
-                    table_view.source
-                );
-                table_view
+                if table_view.is_dirty() && !ask_discard("Reload table") {
+                    return Ok(Task::none());
+                }
+                return Ok(table_view.reload_in_place()?);
+            }
+            Message::RevertTable { pane } => {
+                get_pane_content!(Table, self, pane).revert();
+            }
+            Message::ExportTable { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                if let Some(path) = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name(format!("{}.csv", table_view.table.name))
+                    .save_file()
+                {
+                    std::fs::write(path, table_view.to_csv()?)?;
+                }
+            }
+            Message::ExportTableImage { pane } => {
+                let binary_name = self.binary_name();
+                let table_view = get_pane_content!(Table, self, pane);
+                let name = table_view.table.name.clone();
+                if let Some(path) = FileDialog::new()
+                    .add_filter("PNG", &["png"])
+                    .set_file_name(format!("{name}.png"))
+                    .save_file()
+                {
+                    let image = table_view.grid_image(format!("{name} ({binary_name})"))?;
+                    views::grid_image::render(&image, &path)?;
+                }
+            }
+            Message::ExportChart { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                let name = &table_view.table.name;
+                if let Some(path) = FileDialog::new()
+                    .add_filter("PNG", &["png"])
+                    .set_file_name(format!("{name} chart.png"))
+                    .save_file()
+                {
+                    // Values typed since the chart was last built are included
+                    table_view.refresh_chart();
+                    table_view
+                        .chart
+                        .render_png(&path, table_view.chart_image_size)?;
+                }
+            }
+            Message::SetChartImageSize { pane, size } => {
+                get_pane_content!(Table, self, pane).chart_image_size = size;
+            }
+            Message::SetCsvPrecision { pane, precision } => {
+                get_pane_content!(Table, self, pane).csv_precision = precision;
+            }
+            Message::ImportTable { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                if let Some(path) = FileDialog::new().add_filter("CSV", &["csv"]).pick_file() {
+                    table_view.import_csv(&std::fs::read_to_string(path)?)?;
+                }
+            }
+            Message::TableScrolled { pane, viewport } => {
+                get_pane_content!(Table, self, pane).scrolled(viewport)?;
+            }
+            Message::Select { pane, action } => {
+                get_pane_content!(Table, self, pane).select(action);
+            }
+            Message::SetInterpolation { pane, mode } => {
+                get_pane_content!(Table, self, pane).interpolation = mode;
+            }
+            Message::Interpolate { pane } => {
+                get_pane_content!(Table, self, pane).interpolate()?;
+            }
+            Message::SetSmoothStrength { pane, strength } => {
+                get_pane_content!(Table, self, pane).smooth_strength = strength;
+            }
+            Message::ApplyBulkOp { pane, op, target } => {
+                get_pane_content!(Table, self, pane).apply_bulk_op(op, target)?;
+            }
+            Message::HeaderMenu { pane, target } => {
+                get_pane_content!(Table, self, pane).header_menu = target;
+            }
+            Message::SetLookup { pane, x, y } => {
+                get_pane_content!(Table, self, pane).set_lookup(x, y)?;
+            }
+            Message::SetPasteMode { pane, mode } => {
+                get_pane_content!(Table, self, pane).paste_mode = mode;
+            }
+            Message::PasteClipboard { pane } => {
+                return Ok(iced::clipboard::read().map(move |text| Message::Paste { pane, text }));
+            }
+            Message::Paste { pane, text } => {
+                let Some(text) = text else {
+                    bail!("Clipboard is empty or does not contain text");
+                };
+                let table_view = get_pane_content!(Table, self, pane);
+                // One number pasted over several cells fills all of them
+                match ops::parse_single(&text) {
+                    Some(value) if table_view.selection.is_some_and(|s| !s.is_single()) => {
+                        return Ok(Task::done(Message::PasteValue { pane, value }));
+                    }
+                    _ => table_view.paste(&text)?,
+                }
+            }
+            Message::PasteValue { pane, value } => {
+                get_pane_content!(Table, self, pane).paste_value(value)?;
+            }
+            Message::SetOperand { pane, value } => {
+                get_pane_content!(Table, self, pane).operand = value;
+            }
+            Message::ToggleHeatmap { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.heatmap = !table_view.heatmap;
+            }
+            Message::ToggleSigned {
+                pane,
+                axis: Some(axis),
+            } => {
+                get_pane_content!(Table, self, pane).toggle_signed(axis)?;
+            }
+            Message::EditDisplayScale { pane, field, value } => {
+                get_pane_content!(Table, self, pane).edit_display(field, value);
+            }
+            Message::ApplyDisplayScale { pane } => {
+                get_pane_content!(Table, self, pane).apply_display_scale()?;
+            }
+            Message::ToggleSigned { pane, axis: None } => {
+                get_pane_content!(Scalar, self, pane).toggle_signed()?;
+            }
+            Message::OpenNote { pane, cell } => {
+                get_pane_content!(Table, self, pane).open_note(cell);
+            }
+            Message::StepScalar { pane, up } => {
+                let steps = if self.modifiers.shift() { 10 } else { 1 };
+                get_pane_content!(Scalar, self, pane).step(if up { steps } else { -steps });
+            }
+            Message::OpenScalarGroup(category) => {
+                views::panes::open(self, Open::ScalarGroup(category), self.binary.clone());
+            }
+            Message::EditGroupScalar { pane, row, value } => {
+                if let Some(view) = get_pane_content!(ScalarGroup, self, pane).rows.get_mut(row) {
+                    view.edit(value);
+                }
+            }
+            Message::StepGroupScalar { pane, row, up } => {
+                let steps = if self.modifiers.shift() { 10 } else { 1 };
+                if let Some(view) = get_pane_content!(ScalarGroup, self, pane).rows.get_mut(row) {
+                    view.step(if up { steps } else { -steps });
+                }
+            }
+            Message::WriteScalarGroup { pane } => {
+                // Invalid rows already show why under their input and are left for the user
+                let rows = get_pane_content!(ScalarGroup, self, pane).writable();
+                if rows.is_empty() {
+                    return Ok(Task::none());
+                }
+                self.backup_binary()?;
+                let (written, failed) = get_pane_content!(ScalarGroup, self, pane).write(&rows);
+                let (names, mut ranges): (Vec<String>, Vec<Range<u64>>) =
+                    written.into_iter().unzip();
+                self.committed_scalars.extend(names);
+                ranges.extend(self.update_checksums()?);
+                self.reload_affected(&ranges, pane)?;
+                if !failed.is_empty() {
+                    bail!("Could not write:\n{}", failed.join("\n"));
+                }
+            }
+            Message::RevertScalar { pane } => {
+                get_pane_content!(Scalar, self, pane).revert();
+            }
+            Message::ApplyScalarPreset { pane, index } => {
+                get_pane_content!(Scalar, self, pane).apply_preset(index);
+            }
+            Message::EditPresetName { pane, name } => {
+                get_pane_content!(Scalar, self, pane).preset_name = name;
+            }
+            Message::AddScalarPreset { pane } => {
+                let scalar_view = get_pane_content!(Scalar, self, pane);
+                let Some(value) = scalar_view.current_value() else {
+                    bail!("Only a valid value can be saved as a preset");
+                };
+                let name = scalar_view.preset_name.trim().to_string();
+                if name.is_empty() {
+                    return Ok(Task::none());
+                }
+                scalar_view.preset_name.clear();
+                let scalar = scalar_view.scalar.name.clone();
+                self.project.presets.add(&scalar, Preset { name, value });
+                self.update_presets(&scalar)?;
+            }
+            Message::RemoveScalarPreset { pane, index } => {
+                let scalar = get_pane_content!(Scalar, self, pane).scalar.name.clone();
+                self.project.presets.remove(&scalar, index);
+                self.update_presets(&scalar)?;
+            }
+            Message::ToggleScalarBit { pane, bit } => {
+                get_pane_content!(Scalar, self, pane).toggle_bit(bit);
+            }
+            Message::StepFocusedScalar { up } => {
+                let steps = if self.modifiers.shift() { 10 } else { 1 };
+                if let Some(PaneContent::Scalar(scalar_view)) = self
+                    .focus
+                    .and_then(|p| self.panes.get_mut(p))
+                    .map(|p| &mut p.content)
+                {
+                    scalar_view.step(if up { steps } else { -steps });
+                }
+            }
+            Message::NoteFocused => {
+                if let Some(PaneContent::Table(table_view)) = self
+                    .focus
+                    .and_then(|p| self.panes.get_mut(p))
+                    .map(|p| &mut p.content)
+                {
+                    table_view.open_current_note();
+                }
+            }
+            Message::EditNote { pane, text } => {
+                if let Some((_, draft)) = &mut get_pane_content!(Table, self, pane).note_editor {
+                    *draft = text;
+                }
+            }
+            Message::RemoveNote { pane } => {
+                if let Some((_, draft)) = &mut get_pane_content!(Table, self, pane).note_editor {
+                    draft.clear();
+                }
+                return self.try_update(Message::SaveNote { pane });
+            }
+            Message::SaveNote { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                let map = table_view.table.name.clone();
+                let Some(((row, col), text)) = table_view.save_note() else {
+                    return Ok(Task::none());
+                };
+                match text {
+                    Some(text) => self.project.notes.add(&map, row, col, text),
+                    None => {
+                        self.project.notes.remove(&map, row, col);
+                    }
+                }
+                // Other panes showing the same table
+                for (_, pane) in self.panes.iter_mut() {
+                    if let PaneContent::Table(t) = &mut pane.content {
+                        if t.table.name == map {
+                            t.notes = self.project.notes.for_map(&map);
+                        }
+                    }
+                }
+                if let Some(path) = &self.project_path {
+                    self.project.save(path)?;
+                }
+            }
+            Message::ToggleIndices { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.show_indices = !table_view.show_indices;
+            }
+            Message::FocusChartPoint { pane, cell } => {
+                return Ok(get_pane_content!(Table, self, pane).focus_chart_point(cell)?);
+            }
+            Message::JumpTo { pane, extreme } => {
+                return Ok(get_pane_content!(Table, self, pane).jump_to(extreme)?);
+            }
+            Message::ToggleRaw { pane } => {
+                get_pane_content!(Table, self, pane).toggle_raw();
+            }
+            Message::ToggleScalarRaw { pane } => {
+                get_pane_content!(Scalar, self, pane).toggle_raw()?;
+            }
+            Message::SetComparison { pane, overlay } => {
+                let reference = self.reference.clone();
+                let table_view = get_pane_content!(Table, self, pane);
+                let values = match overlay {
+                    Overlay::Off => None,
+                    Overlay::Snapshot => Some(table_view.z_values()?.into_owned()),
+                    Overlay::Reference => {
+                        let Some(mut reference) = reference else {
+                            bail!("Load a reference binary from the sidebar first");
+                        };
+                        Some(table_view.table.z.read_strings(&mut reference)?)
+                    }
+                };
+                table_view.set_comparison(overlay, values);
+            }
+            Message::ToggleAxesLocked { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.axes_locked = !table_view.axes_locked;
+            }
+            Message::ToggleTransposed { pane } => {
+                get_pane_content!(Table, self, pane).toggle_transposed()?;
+            }
+            Message::ToggleChange { pane } => {
+                get_pane_content!(Table, self, pane).toggle_change();
+            }
+            Message::SetChangeMode { pane, mode } => {
+                get_pane_content!(Table, self, pane).set_change_mode(mode);
+            }
+            Message::SetDensity { pane, density } => {
+                get_pane_content!(Table, self, pane).set_density(density);
+            }
+            Message::SetDecimals { pane, decimals } => {
+                let index = self
+                    .pane_id_map
+                    .get(&pane)
+                    .ok_or(anyhow!("Fatal: Pane ID not in map"))?;
+                let map = match &self.panes.get(*index).map(|p| &p.content) {
+                    Some(PaneContent::Table(t)) => t.table.name.clone(),
+                    Some(PaneContent::Scalar(s)) => s.scalar.name.clone(),
+                    _ => bail!("Fatal: Wrong pane"),
+                };
+                let decimals = Option::<usize>::from(decimals);
+                match decimals {
+                    Some(decimals) => self.project.decimals.insert(map.clone(), decimals),
+                    None => self.project.decimals.remove(&map),
+                };
+                // Every pane showing the same map
+                for (_, pane) in self.panes.iter_mut() {
+                    match &mut pane.content {
+                        PaneContent::Table(t) if t.table.name == map => t.set_decimals(decimals)?,
+                        PaneContent::Scalar(s) if s.scalar.name == map => {
+                            s.set_decimals(decimals)?
+                        }
+                        PaneContent::ScalarGroup(g) => {
+                            for row in g.rows.iter_mut().filter(|row| row.scalar.name == map) {
+                                row.set_decimals(decimals)?;
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                if let Some(path) = &self.project_path {
+                    self.project.save(path)?;
+                }
+            }
+            Message::SetColormap { pane, colormap } => {
+                get_pane_content!(Table, self, pane)
                     .chart
-                    .update(&table_view.x_head, &table_view.y_head, &table_view.data);
+                    .set_colormap(colormap);
+            }
+            Message::ToggleChart { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.toggle_chart()?;
+                let map = table_view.table.name.clone();
+                let layout = table_view.chart_layout();
+                self.save_chart_layout(map, layout)?;
+            }
+            Message::SetChartWidth { pane, fraction } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.chart_fraction = fraction;
+                let map = table_view.table.name.clone();
+                let layout = table_view.chart_layout();
+                self.save_chart_layout(map, layout)?;
+            }
+            Message::ToggleHistogram { pane } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.show_histogram = !table_view.show_histogram;
             }
             Message::EditScalar { value, pane } => {
-                let scalar_view = get_pane_content!(Scalar, self, pane);
-                scalar_view.value = value;
+                get_pane_content!(Scalar, self, pane).edit(value);
             }
             Message::WriteScalar { pane } => {
+                // Reached with an invalid value from "Write all", which lists the failure in its
+                // summary
+                get_pane_content!(Scalar, self, pane).check_writable()?;
+                self.backup_binary()?;
                 let scalar_view = get_pane_content!(Scalar, self, pane);
-                scalar_view
-                    .scalar
-                    .write(&mut scalar_view.source, scalar_view.value.parse()?)?;
+                scalar_view.write()?;
+                let name = scalar_view.scalar.name.clone();
+                let mut written = vec![scalar_view.scalar.byte_range()];
+                self.committed_scalars.insert(name);
+                written.extend(self.update_checksums()?);
+                self.reload_affected(&written, pane)?;
             }
             Message::PaneAction(action) => views::panes::update_panes(self, action),
             Message::GraphPitch(pane, ps) => {
@@ -191,22 +1212,144 @@ impl App {
                 let table_view = get_pane_content!(Table, self, pane);
                 table_view.chart.yaw(ys);
             }
+            Message::GraphRotate { pane, pitch, yaw } => {
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.chart.pitch(pitch);
+                table_view.chart.yaw(yaw);
+            }
+            Message::GraphScale(pane, scale) => {
+                get_pane_content!(Table, self, pane).chart.scale(scale);
+            }
+            Message::CommitCellAndMove { pane, source } => {
+                let direction = if self.modifiers.shift() {
+                    MoveDirection::Up
+                } else {
+                    MoveDirection::Down
+                };
+                let table_view = get_pane_content!(Table, self, pane);
+                table_view.commit_edit();
+                return Ok(table_view.move_focus(source, direction)?);
+            }
+            Message::FocusNext | Message::FocusPrevious => {
+                if let Some(pane) = self.focus.and_then(|p| self.panes.get_mut(p)) {
+                    if let PaneContent::Table(table_view) = &mut pane.content {
+                        table_view.commit_edit();
+                    }
+                }
+                return Ok(if let Message::FocusNext = message {
+                    iced::widget::focus_next()
+                } else {
+                    iced::widget::focus_previous()
+                });
+            }
+            Message::WriteAll => {
+                let writes: Vec<(String, Message)> = self
+                    .panes
+                    .iter()
+                    .filter_map(|(_, pane)| match &pane.content {
+                        PaneContent::Table(t) if t.is_dirty() => Some((
+                            t.table.name.clone(),
+                            Message::WriteTable { pane: t.pane_id() },
+                        )),
+                        PaneContent::Scalar(s) if s.is_dirty() => Some((
+                            s.scalar.name.clone(),
+                            Message::WriteScalar { pane: s.pane_id() },
+                        )),
+                        // Rows with invalid values stay edited and are not listed
+                        PaneContent::ScalarGroup(g) if !g.writable().is_empty() => Some((
+                            format!("{} (scalars)", g.name),
+                            Message::WriteScalarGroup { pane: g.pane_id() },
+                        )),
+                        _ => None,
+                    })
+                    .collect();
+                if writes.is_empty() {
+                    return Ok(Task::none());
+                }
+                // A pane that fails is reported and does not stop the others being written
+                let total = writes.len();
+                let mut tasks = Vec::new();
+                let mut written = Vec::new();
+                let mut failed = Vec::new();
+                for (name, write) in writes {
+                    match self.try_update(write) {
+                        Ok(task) => {
+                            tasks.push(task);
+                            written.push(name);
+                        }
+                        Err(e) => failed.push(format!("{name}: {e}")),
+                    }
+                }
+                let mut text = format!("Wrote {} of {total} edited panes", written.len());
+                if !written.is_empty() {
+                    text.push_str(&format!("\n\nWritten:\n{}", written.join("\n")));
+                }
+                if !failed.is_empty() {
+                    text.push_str(&format!("\n\nNot written:\n{}", failed.join("\n")));
+                }
+                let summary = Open::Summary {
+                    title: "Write all".to_string(),
+                    text,
+                };
+                views::panes::open(self, summary, self.binary.clone());
+                return Ok(Task::batch(tasks));
+            }
+            Message::WriteFocused => {
+                let Some(pane) = self.focus.and_then(|p| self.panes.get(p)) else {
+                    return Ok(Task::none());
+                };
+                let write = match &pane.content {
+                    PaneContent::Table(t) => Message::WriteTable { pane: t.pane_id() },
+                    // Why the value cannot be written is already shown under its input
+                    PaneContent::Scalar(s) if !s.is_valid() => return Ok(Task::none()),
+                    PaneContent::Scalar(s) => Message::WriteScalar { pane: s.pane_id() },
+                    PaneContent::ScalarGroup(g) => Message::WriteScalarGroup { pane: g.pane_id() },
+                    _ => return Ok(Task::none()),
+                };
+                return self.try_update(write);
+            }
         }
-        Ok(())
+        Ok(Task::none())
     }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum Open {
-    // Nav(BinaryDefinition),
     Table(Table),
     Scalar(Scalar),
     Error(String),
+    Warning(String),
+    ExprTester,
+    /// Every scalar of a category in one pane
+    ScalarGroup(u32),
+    /// Read a value at any address without a definition
+    Probe,
+    /// Pick bytes of the binary to open as a table without a definition
+    Region,
+    /// Table in the working binary next to the reference binary
+    TableCompare(Table),
+    /// Parsed definition of a table or scalar
+    Definition {
+        name: String,
+        text: String,
+    },
+    /// Result of an action that touched several panes
+    Summary {
+        title: String,
+        text: String,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum Message {
-    Open(Open),
+    /// Typed into the formula bar of a table, applies to its focused cell
+    EditFormula {
+        pane: usize,
+        value: String,
+    },
+    CommitFormula {
+        pane: usize,
+    },
     EditCell {
         value: String,
         pane: usize,
@@ -215,6 +1358,266 @@ pub(crate) enum Message {
     WriteTable {
         pane: usize,
     },
+    RevertTable {
+        pane: usize,
+    },
+    /// Read the table from the binary again, keeping the scroll position and focused cell
+    ReloadTable {
+        pane: usize,
+    },
+    /// Save the table's current values to a CSV file
+    ExportTable {
+        pane: usize,
+    },
+    /// Save the grid as it is shown, colours included, as a PNG
+    ExportTableImage {
+        pane: usize,
+    },
+    SetCsvPrecision {
+        pane: usize,
+        precision: definitions::CsvPrecision,
+    },
+    /// Save the chart as it is shown, rotation and colours included, as a PNG
+    ExportChart {
+        pane: usize,
+    },
+    SetChartImageSize {
+        pane: usize,
+        size: ImageSize,
+    },
+    /// Load values from a CSV file into the table without writing them
+    ImportTable {
+        pane: usize,
+    },
+    ToggleHeatmap {
+        pane: usize,
+    },
+    ToggleChart {
+        pane: usize,
+    },
+    SetChartWidth {
+        pane: usize,
+        fraction: f32,
+    },
+    ToggleHistogram {
+        pane: usize,
+    },
+    ToggleRaw {
+        pane: usize,
+    },
+    /// Move a scalar one stored integer up or down, ten while Shift is held
+    StepScalar {
+        pane: usize,
+        up: bool,
+    },
+    /// Open every scalar of a category in one pane
+    OpenScalarGroup(u32),
+    EditGroupScalar {
+        pane: usize,
+        row: usize,
+        value: String,
+    },
+    /// Move one row of a scalar group a stored integer up or down, ten while Shift is held
+    StepGroupScalar {
+        pane: usize,
+        row: usize,
+        up: bool,
+    },
+    /// Write the edited rows of a scalar group that are valid
+    WriteScalarGroup {
+        pane: usize,
+    },
+    /// Set a scalar to one of its presets without writing it
+    ApplyScalarPreset {
+        pane: usize,
+        index: usize,
+    },
+    EditPresetName {
+        pane: usize,
+        name: String,
+    },
+    /// Save the value of a scalar as a preset named as typed
+    AddScalarPreset {
+        pane: usize,
+    },
+    RemoveScalarPreset {
+        pane: usize,
+        index: usize,
+    },
+    /// Restore a scalar to the value last read or written
+    RevertScalar {
+        pane: usize,
+    },
+    /// Flip one bit of a bitfield scalar
+    ToggleScalarBit {
+        pane: usize,
+        bit: u8,
+    },
+    /// Step the focused scalar, sent by `+` and `-` outside text inputs
+    StepFocusedScalar {
+        up: bool,
+    },
+    /// Show and write the stored integer of a scalar instead of its converted value
+    ToggleScalarRaw {
+        pane: usize,
+    },
+    /// Show data cells as their change from the values last read or written
+    ToggleChange {
+        pane: usize,
+    },
+    SetChangeMode {
+        pane: usize,
+        mode: DeltaMode,
+    },
+    /// Draw other values of a table over its chart
+    SetComparison {
+        pane: usize,
+        overlay: Overlay,
+    },
+    /// Make the x and y axes of a table read only, or editable again
+    ToggleAxesLocked {
+        pane: usize,
+    },
+    /// Swap the displayed rows and columns of a table
+    ToggleTransposed {
+        pane: usize,
+    },
+    SetDensity {
+        pane: usize,
+        density: Density,
+    },
+    /// Show a table or scalar with a fixed number of decimals, kept in the project file
+    SetDecimals {
+        pane: usize,
+        decimals: Decimals,
+    },
+    ToggleIndices {
+        pane: usize,
+    },
+    /// Pick a second binary to compare tables against
+    LoadReference,
+    /// Open a comparison of a table with the reference binary
+    CompareTable {
+        pane: usize,
+    },
+    SetDeltaMode {
+        pane: usize,
+        mode: DeltaMode,
+    },
+    RefreshCompare {
+        pane: usize,
+    },
+    CompareScrolled {
+        pane: usize,
+        viewport: scrollable::Viewport,
+    },
+    /// Save the values written this session to a tune file
+    ExportTune,
+    /// Write the values of a tune file to the binary
+    ImportTune,
+    /// Edit the note of a table cell, or close the editor with `None`
+    OpenNote {
+        pane: usize,
+        cell: Option<(usize, usize)>,
+    },
+    /// Edit the note of the current cell of the focused table
+    NoteFocused,
+    EditNote {
+        pane: usize,
+        text: String,
+    },
+    /// Store the edited note in the project file
+    SaveNote {
+        pane: usize,
+    },
+    RemoveNote {
+        pane: usize,
+    },
+    /// Read a table axis, or a scalar with `None`, as two's complement or not
+    ToggleSigned {
+        pane: usize,
+        axis: Option<TableAxis>,
+    },
+    EditDisplayScale {
+        pane: usize,
+        field: DisplayField,
+        value: String,
+    },
+    /// Show the table data scaled from its converted values
+    ApplyDisplayScale {
+        pane: usize,
+    },
+    /// Focus the smallest or largest value of a table
+    JumpTo {
+        pane: usize,
+        extreme: Extreme,
+    },
+    /// Clicked a point of a table chart, `cell` is its (row, column) as the chart shows them
+    FocusChartPoint {
+        pane: usize,
+        cell: (usize, usize),
+    },
+    SetColormap {
+        pane: usize,
+        colormap: colormap::Colormap,
+    },
+    TableScrolled {
+        pane: usize,
+        viewport: scrollable::Viewport,
+    },
+    Select {
+        pane: usize,
+        action: SelectAction,
+    },
+    SetInterpolation {
+        pane: usize,
+        mode: Interpolation,
+    },
+    Interpolate {
+        pane: usize,
+    },
+    SetSmoothStrength {
+        pane: usize,
+        strength: f64,
+    },
+    ApplyBulkOp {
+        pane: usize,
+        op: BulkOp,
+        target: Target,
+    },
+    /// Open the operation menu of a row or column, or close it with `None`
+    HeaderMenu {
+        pane: usize,
+        target: Option<Target>,
+    },
+    SetOperand {
+        pane: usize,
+        value: String,
+    },
+    SetPasteMode {
+        pane: usize,
+        mode: PasteMode,
+    },
+    /// Read the clipboard to paste into a table
+    PasteClipboard {
+        pane: usize,
+    },
+    /// Clipboard contents read for a paste
+    Paste {
+        pane: usize,
+        text: Option<String>,
+    },
+    /// Combine one value with every selected cell
+    PasteValue {
+        pane: usize,
+        value: f64,
+    },
+    /// Operating point to interpolate the table at
+    SetLookup {
+        pane: usize,
+        x: String,
+        y: String,
+    },
     EditScalar {
         value: String,
         pane: usize,
@@ -224,40 +1627,291 @@ pub(crate) enum Message {
     },
     GraphPitch(usize, f64),
     GraphYaw(usize, f64),
+    /// Chart dragged with the mouse
+    GraphRotate {
+        pane: usize,
+        pitch: f64,
+        yaw: f64,
+    },
+    GraphScale(usize, f64),
     PaneAction(PaneAction),
+    NavClick(NavItem),
+    /// Show the right-click menu of a nav item, or hide it with `None`
+    NavMenu(Option<NavItem>),
+    NavAction(NavItem, NavAction),
+    /// Open everything selected in the nav
+    OpenSelected,
+    /// Collapse or expand the nav sidebar
+    ToggleNav,
+    SetPlacement(settings::PanePlacement),
+    /// Toggle thousands separators in every pane
+    ToggleSeparators,
+    ModifiersChanged(keyboard::Modifiers),
+    /// Undo the last cell edit in the focused table
+    UndoEdit,
+    RedoEdit,
+    /// Restore the field being typed in to its value from before typing started
+    CancelEdit,
+    /// Enter pressed in a cell, focus the cell below or above with shift held
+    CommitCellAndMove {
+        pane: usize,
+        source: EditSource,
+    },
+    FocusNext,
+    FocusPrevious,
+    /// Write the focused table or scalar
+    WriteFocused,
+    /// Write every edited table and scalar, then open a summary of what was written
+    WriteAll,
+    /// Write every edited pane without invalid values, sent on the autosave timer
+    Autosave,
+    /// Read every open pane from the binary again, after it was changed by another program
+    ReloadBinary,
+    /// Compare the stored checksums with the regions they cover
+    VerifyChecksums,
+    OpenExprTester,
+    OpenProbe,
+    EditProbe {
+        pane: usize,
+        field: ProbeField,
+        value: String,
+    },
+    SetProbeSize {
+        pane: usize,
+        size: usize,
+    },
+    SetProbeEndian {
+        pane: usize,
+        endian: Endian,
+    },
+    OpenRegion,
+    EditRegion {
+        pane: usize,
+        field: RegionField,
+        value: String,
+    },
+    SetRegionSize {
+        pane: usize,
+        size: usize,
+    },
+    SetRegionEndian {
+        pane: usize,
+        endian: Endian,
+    },
+    /// Open the region picked in a region pane as a table
+    OpenRegionTable {
+        pane: usize,
+    },
+    EditTester {
+        pane: usize,
+        field: TesterField,
+        value: String,
+    },
+}
+
+/// Ask whether edits that have not been written may be thrown away.
+fn ask_discard(title: &str) -> bool {
+    MessageDialog::new()
+        .set_title(title)
+        .set_description("Discard edits that have not been written?")
+        .set_buttons(MessageButtons::YesNo)
+        .show()
+        == MessageDialogResult::Yes
+}
+
+/// Global keyboard shortcuts, these also apply while a text input is focused.
+fn shortcuts(
+    event: iced::Event,
+    status: iced::event::Status,
+    _window: iced::window::Id,
+) -> Option<Message> {
+    let (key, modifiers) = match event {
+        iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+            (key, modifiers)
+        }
+        iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+            return Some(Message::ModifiersChanged(modifiers))
+        }
+        _ => return None,
+    };
+
+    match key.as_ref() {
+        Key::Named(Named::F11) => Some(PaneAction::ToggleMaximize.into()),
+        Key::Named(Named::Escape) => Some(Message::CancelEdit),
+        Key::Named(Named::F5) => Some(Message::ReloadBinary),
+        Key::Character("m") if modifiers.command() => Some(PaneAction::ToggleMaximize.into()),
+        Key::Character("z" | "Z") if modifiers.command() && modifiers.shift() => {
+            Some(Message::RedoEdit)
+        }
+        Key::Character("z" | "Z") if modifiers.command() => Some(Message::UndoEdit),
+        Key::Character("y") if modifiers.command() => Some(Message::RedoEdit),
+        Key::Character("s" | "S") if modifiers.command() && modifiers.shift() => {
+            Some(Message::WriteAll)
+        }
+        Key::Character("s") if modifiers.command() => Some(Message::WriteFocused),
+        Key::Character("n") if modifiers.command() => Some(Message::NoteFocused),
+        Key::Named(Named::Tab) if modifiers.command() => Some(
+            PaneAction::Cycle {
+                backward: modifiers.shift(),
+            }
+            .into(),
+        ),
+        Key::Character(digit) if modifiers.alt() => match digit.parse::<usize>() {
+            Ok(n @ 1..=9) => Some(PaneAction::FocusNth(n - 1).into()),
+            _ => None,
+        },
+        // Typed into a text input rather than meant as a step
+        Key::Character("+" | "=") if status == iced::event::Status::Ignored => {
+            Some(Message::StepFocusedScalar { up: true })
+        }
+        Key::Character("-" | "_") if status == iced::event::Status::Ignored => {
+            Some(Message::StepFocusedScalar { up: false })
+        }
+        Key::Named(Named::Tab) if modifiers.shift() => Some(Message::FocusPrevious),
+        Key::Named(Named::Tab) => Some(Message::FocusNext),
+        _ => None,
+    }
 }
 
 fn main() -> iced::Result {
-    let xdf_path = FileDialog::new()
+    let settings = Settings::from_args(std::env::args().skip(1));
+
+    let (binary, bin_path, mut def) = if settings.demo {
+        (FileGuard::from(demo::binary()), None, demo::definition())
+    } else if let Some(xdf_path) = FileDialog::new()
         .add_filter("XDF", &["xdf"])
         .set_directory("/")
         .pick_file()
-        .unwrap();
-    // let xdf_path = "./testfiles/xdf";
+    {
+        let xdf = File::open(xdf_path).unwrap();
 
-    let xdf = File::open(xdf_path).unwrap();
+        let xdf_parsed = parse_buffer(xdf).unwrap().unwrap();
 
-    let xdf_parsed = parse_buffer(xdf).unwrap().unwrap();
+        let Some(bin_path) = FileDialog::new()
+            .add_filter("BIN", &["bin"])
+            .set_directory("/")
+            .pick_file()
+        else {
+            return Ok(());
+        };
 
-    let bin_path = FileDialog::new()
-        .add_filter("BIN", &["bin"])
-        .set_directory("/")
-        .pick_file()
-        .unwrap();
-    // let bin_path = "./testfiles/bin";
+        let bin = File::options()
+            .write(true)
+            .read(true)
+            .open(&bin_path)
+            .unwrap();
 
-    let bin = File::options()
-        .write(true)
-        .read(true)
-        .open(bin_path)
-        .unwrap();
+        let def = if let XDFElement::XDFFormat(xdf) = xdf_parsed {
+            definitions::BinaryDefinition::from_xdf(xdf, !settings.lenient_labels)
+        } else {
+            panic!("Expected full XDF file.");
+        };
+        // Nothing is open yet to show the error in
+        let def = match def {
+            Ok(def) => def,
+            Err(e) => {
+                MessageDialog::new()
+                    .set_level(rfd::MessageLevel::Error)
+                    .set_title("Could not load definition")
+                    .set_description(e)
+                    .show();
+                return Ok(());
+            }
+        };
 
-    let def = if let XDFElement::XDFFormat(xdf) = xdf_parsed {
-        definitions::BinaryDefinition::from_xdf(xdf)
+        (FileGuard::from(bin), Some(bin_path), def)
     } else {
-        panic!("Expected full XDF file.");
+        // No definition picked, show the demo so there is something to look at
+        (FileGuard::from(demo::binary()), None, demo::definition())
     };
 
-    iced::application("HEXTuner", App::update, App::view)
-        .run_with(|| (App::new(bin, def), Task::none()))
+    // The definition is kept at its own base offset and the error shown once the app is open
+    let mut errors = Vec::new();
+    if let Some(base_offset) = settings.base_offset {
+        if let Err(e) = def.set_base_offset(base_offset) {
+            errors.push(format!(
+                "--base-offset does not fit the definition, using the offset of the XDF: {e}"
+            ));
+        }
+    }
+
+    iced::application(App::title, App::update, App::view)
+        .subscription(App::subscription)
+        .run_with(|| {
+            (
+                App::new(binary, bin_path, def, settings, errors),
+                Task::none(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Seek};
+
+    use super::*;
+
+    /// App editing the demo binary in memory with "Ignition timing" open, and the pane's id
+    fn demo_app() -> (App, usize) {
+        let mut app = App::new(
+            FileGuard::from(demo::binary()),
+            None,
+            demo::definition(),
+            Settings::default(),
+            Vec::new(),
+        );
+        let pane = app.panes_created;
+        let table = demo::definition().tables.remove(0);
+        let binary = app.binary.clone();
+        views::panes::open(&mut app, Open::Table(table), binary);
+        (app, pane)
+    }
+
+    /// Handle a message, the task it returns is only needed to drive the GUI
+    fn send(app: &mut App, message: Message) -> anyhow::Result<()> {
+        app.try_update(message).map(drop)
+    }
+
+    fn bytes(app: &App) -> Vec<u8> {
+        let mut binary = app.binary.clone();
+        let mut bytes = Vec::new();
+        binary.rewind().unwrap();
+        binary.read_to_end(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn unmodified_table_is_not_written() -> anyhow::Result<()> {
+        let (mut app, pane) = demo_app();
+        let before = bytes(&app);
+        send(&mut app, Message::WriteTable { pane })?;
+        // Same value at display precision
+        let value = format!("{}0", get_pane_content!(Table, app, pane).data[0]);
+        let source = EditSource::Data(0);
+        send(
+            &mut app,
+            Message::EditCell {
+                value,
+                pane,
+                source,
+            },
+        )?;
+        send(&mut app, Message::WriteTable { pane })?;
+        assert!(app.committed_tables.is_empty());
+        assert_eq!(bytes(&app), before);
+
+        let value = "10".to_string();
+        send(
+            &mut app,
+            Message::EditCell {
+                value,
+                pane,
+                source,
+            },
+        )?;
+        send(&mut app, Message::WriteTable { pane })?;
+        assert!(app.committed_tables.contains("Ignition timing"));
+        assert_ne!(bytes(&app), before);
+        Ok(())
+    }
 }
@@ -0,0 +1,408 @@
+//! Pure operations on blocks of table values, blocks are indexed `[row][column]`.
+
+use std::{fmt::Display, ops::RangeInclusive};
+
+/// Operation applied to every targeted cell of a table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BulkOp {
+    /// 3x3 smoothing, blended with the original values by a strength from 0 to 1
+    Smooth(f64),
+    Add(f64),
+    Multiply(f64),
+    Set(f64),
+}
+
+impl BulkOp {
+    /// New value of a single cell, `None` for operations that depend on neighbouring cells.
+    pub fn apply(self, value: f64) -> Option<f64> {
+        match self {
+            Self::Smooth(_) => None,
+            Self::Add(n) => Some(value + n),
+            Self::Multiply(n) => Some(value * n),
+            Self::Set(n) => Some(n),
+        }
+    }
+
+    /// Whether the current value of a cell is used
+    pub fn reads_cells(self) -> bool {
+        !matches!(self, Self::Set(_))
+    }
+}
+
+/// Cells a bulk operation is applied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The selected cells, or the whole table if nothing is selected
+    Selection,
+    Row(usize),
+    Column(usize),
+}
+
+/// Clamp a value to a storage range given in either order.
+pub fn clamp_to(value: f64, range: Option<(f64, f64)>) -> f64 {
+    match range {
+        Some((a, b)) => value.clamp(a.min(b), a.max(b)),
+        None => value,
+    }
+}
+
+/// How pasted values are combined with the cells they land on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PasteMode {
+    #[default]
+    Replace,
+    Add,
+    Multiply,
+    /// Multiply by a percentage, 100 leaves the cell unchanged
+    Percent,
+}
+
+impl PasteMode {
+    pub const ALL: [PasteMode; 4] = [Self::Replace, Self::Add, Self::Multiply, Self::Percent];
+
+    pub fn combine(self, current: f64, pasted: f64) -> f64 {
+        match self {
+            Self::Replace => pasted,
+            Self::Add => current + pasted,
+            Self::Multiply => current * pasted,
+            Self::Percent => current * pasted / 100.0,
+        }
+    }
+
+    /// Whether the current value of a cell is used
+    pub fn reads_cells(self) -> bool {
+        self != Self::Replace
+    }
+}
+
+impl Display for PasteMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Replace => "Replace",
+            Self::Add => "Add",
+            Self::Multiply => "Multiply",
+            Self::Percent => "Multiply %",
+        })
+    }
+}
+
+/// Parse tab separated rows as copied from a spreadsheet, cells that are not numbers are `None`.
+/// Trailing empty lines are ignored.
+pub fn parse_tsv(text: &str) -> Vec<Vec<Option<f64>>> {
+    let mut rows: Vec<Vec<Option<f64>>> = text
+        .lines()
+        .map(|line| line.split('\t').map(|v| v.trim().parse().ok()).collect())
+        .collect();
+    while rows
+        .last()
+        .is_some_and(|row| row.len() == 1 && row[0].is_none())
+    {
+        rows.pop();
+    }
+    rows
+}
+
+/// Swap the rows and columns of parsed clipboard text, missing cells of short rows become `None`.
+pub fn transpose_rows(rows: &[Vec<Option<f64>>]) -> Vec<Vec<Option<f64>>> {
+    let cols = rows.iter().map(Vec::len).max().unwrap_or(0);
+    (0..cols)
+        .map(|c| {
+            rows.iter()
+                .map(|row| row.get(c).copied().flatten())
+                .collect()
+        })
+        .collect()
+}
+
+/// The number in clipboard text holding exactly one cell.
+pub fn parse_single(text: &str) -> Option<f64> {
+    match parse_tsv(text).as_slice() {
+        [row] => match row.as_slice() {
+            [value] => *value,
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Interpolate each row between its first and last cell
+    Horizontal,
+    /// Interpolate each column between its first and last cell
+    Vertical,
+    /// Interpolate between the four corners
+    #[default]
+    Bilinear,
+}
+
+impl Interpolation {
+    pub const ALL: [Interpolation; 3] = [Self::Horizontal, Self::Vertical, Self::Bilinear];
+
+    /// Mode actually used for a block, blocks that are one cell wide fall back to the other direction.
+    pub fn resolve(self, rows: usize, cols: usize) -> Self {
+        match self {
+            _ if rows <= 1 => Self::Horizontal,
+            _ if cols <= 1 => Self::Vertical,
+            mode => mode,
+        }
+    }
+
+    /// Cells (row, column) that the interpolated values are computed from.
+    pub fn sources(self, rows: usize, cols: usize) -> Vec<(usize, usize)> {
+        if rows == 0 || cols == 0 {
+            return Vec::new();
+        }
+        let (last_row, last_col) = (rows - 1, cols - 1);
+        match self.resolve(rows, cols) {
+            Self::Horizontal => (0..rows).flat_map(|r| [(r, 0), (r, last_col)]).collect(),
+            Self::Vertical => (0..cols).flat_map(|c| [(0, c), (last_row, c)]).collect(),
+            Self::Bilinear => vec![(0, 0), (0, last_col), (last_row, 0), (last_row, last_col)],
+        }
+    }
+}
+
+impl Display for Interpolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Horizontal => "Horizontal",
+            Self::Vertical => "Vertical",
+            Self::Bilinear => "2D",
+        })
+    }
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Fraction of the way `i` is to `last`
+fn fraction(i: usize, last: usize) -> f64 {
+    if last == 0 {
+        0.0
+    } else {
+        i as f64 / last as f64
+    }
+}
+
+/// Fill a rectangular block by linear interpolation between its edge or corner cells,
+/// only the cells given by [`Interpolation::sources`] are read.
+pub fn interpolate(block: &mut [Vec<f64>], mode: Interpolation) {
+    let rows = block.len();
+    let cols = block.first().map_or(0, Vec::len);
+    if rows == 0 || cols == 0 {
+        return;
+    }
+    let (last_row, last_col) = (rows - 1, cols - 1);
+
+    match mode.resolve(rows, cols) {
+        Interpolation::Horizontal => {
+            for row in block.iter_mut() {
+                let (a, b) = (row[0], row[last_col]);
+                for (c, value) in row.iter_mut().enumerate() {
+                    *value = lerp(a, b, fraction(c, last_col));
+                }
+            }
+        }
+        Interpolation::Vertical => {
+            for c in 0..cols {
+                let (a, b) = (block[0][c], block[last_row][c]);
+                for (r, row) in block.iter_mut().enumerate() {
+                    row[c] = lerp(a, b, fraction(r, last_row));
+                }
+            }
+        }
+        Interpolation::Bilinear => {
+            let (top_left, top_right) = (block[0][0], block[0][last_col]);
+            let (bottom_left, bottom_right) = (block[last_row][0], block[last_row][last_col]);
+            for (r, row) in block.iter_mut().enumerate() {
+                for (c, value) in row.iter_mut().enumerate() {
+                    let top = lerp(top_left, top_right, fraction(c, last_col));
+                    let bottom = lerp(bottom_left, bottom_right, fraction(c, last_col));
+                    *value = lerp(top, bottom, fraction(r, last_row));
+                }
+            }
+        }
+    }
+}
+
+/// Center weighted 3x3 kernel, weights add up to 16
+const SMOOTH_KERNEL: [[f64; 3]; 3] = [[1.0, 2.0, 1.0], [2.0, 4.0, 2.0], [1.0, 2.0, 1.0]];
+
+/// Smooth the cells within `rows` and `cols` using their 3x3 neighbourhood, neighbours past
+/// the edge of the grid are clamped to the edge. Cells outside the region are read but never
+/// changed, `strength` (0 to 1) blends between the original and fully smoothed value.
+pub fn smooth(
+    grid: &mut [Vec<f64>],
+    rows: RangeInclusive<usize>,
+    cols: RangeInclusive<usize>,
+    strength: f64,
+) {
+    let source = grid.to_vec();
+    let last_row = source.len().saturating_sub(1);
+    let last_col = source.first().map_or(0, |r| r.len().saturating_sub(1));
+    let strength = strength.clamp(0.0, 1.0);
+
+    for r in rows {
+        for c in cols.clone() {
+            let mut sum = 0.0;
+            for (dr, weights) in SMOOTH_KERNEL.iter().enumerate() {
+                for (dc, weight) in weights.iter().enumerate() {
+                    let nr = (r + dr).saturating_sub(1).min(last_row);
+                    let nc = (c + dc).saturating_sub(1).min(last_col);
+                    sum += weight * source[nr][nc];
+                }
+            }
+            grid[r][c] = lerp(source[r][c], sum / 16.0, strength);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolate_single_row() {
+        let mut block = vec![vec![0.0, 99.0, 99.0, 99.0, 30.0]];
+        interpolate(&mut block, Interpolation::Bilinear);
+        assert_eq!(block, vec![vec![0.0, 7.5, 15.0, 22.5, 30.0]]);
+        // Vertical on a single row falls back to horizontal
+        let mut block = vec![vec![10.0, 0.0, 20.0]];
+        interpolate(&mut block, Interpolation::Vertical);
+        assert_eq!(block, vec![vec![10.0, 15.0, 20.0]]);
+    }
+
+    #[test]
+    fn interpolate_single_column() {
+        let mut block = vec![vec![4.0], vec![0.0], vec![0.0], vec![-2.0]];
+        interpolate(&mut block, Interpolation::Horizontal);
+        assert_eq!(block, vec![vec![4.0], vec![2.0], vec![0.0], vec![-2.0]]);
+    }
+
+    #[test]
+    fn interpolate_block() {
+        let corners = || {
+            vec![
+                vec![0.0, 5.0, 10.0],
+                vec![7.0, 7.0, 7.0],
+                vec![20.0, 5.0, 40.0],
+            ]
+        };
+
+        let mut block = corners();
+        interpolate(&mut block, Interpolation::Bilinear);
+        assert_eq!(
+            block,
+            vec![
+                vec![0.0, 5.0, 10.0],
+                vec![10.0, 17.5, 25.0],
+                vec![20.0, 30.0, 40.0],
+            ]
+        );
+
+        let mut block = corners();
+        interpolate(&mut block, Interpolation::Horizontal);
+        assert_eq!(block[0], vec![0.0, 5.0, 10.0]);
+        assert_eq!(block[1], vec![7.0, 7.0, 7.0]);
+        assert_eq!(block[2], vec![20.0, 30.0, 40.0]);
+
+        let mut block = corners();
+        interpolate(&mut block, Interpolation::Vertical);
+        assert_eq!(block[1], vec![10.0, 5.0, 25.0]);
+    }
+
+    #[test]
+    fn interpolate_empty_and_single_cell() {
+        let mut block: Vec<Vec<f64>> = Vec::new();
+        interpolate(&mut block, Interpolation::Bilinear);
+        assert!(block.is_empty());
+        let mut block = vec![vec![3.0]];
+        interpolate(&mut block, Interpolation::Bilinear);
+        assert_eq!(block, vec![vec![3.0]]);
+    }
+
+    #[test]
+    fn interpolation_sources() {
+        assert_eq!(
+            Interpolation::Bilinear.sources(3, 4),
+            vec![(0, 0), (0, 3), (2, 0), (2, 3)]
+        );
+        assert_eq!(Interpolation::Bilinear.sources(1, 3), vec![(0, 0), (0, 2)]);
+        assert_eq!(Interpolation::Bilinear.sources(3, 1), vec![(0, 0), (2, 0),]);
+        assert!(Interpolation::Vertical.sources(0, 3).is_empty());
+    }
+
+    #[test]
+    fn smooth_flat_grid_unchanged() {
+        let mut grid = vec![vec![5.0; 4]; 3];
+        smooth(&mut grid, 0..=2, 0..=3, 1.0);
+        assert_eq!(grid, vec![vec![5.0; 4]; 3]);
+    }
+
+    #[test]
+    fn smooth_spike() {
+        let mut grid = vec![vec![0.0; 3]; 3];
+        grid[1][1] = 16.0;
+        smooth(&mut grid, 1..=1, 1..=1, 1.0);
+        // Only the center is in the region, its weight is 4 of 16
+        assert_eq!(grid[1][1], 4.0);
+        assert_eq!(grid[0][1], 0.0);
+    }
+
+    #[test]
+    fn smooth_reads_but_keeps_outside_cells() {
+        let mut grid = vec![vec![0.0, 0.0, 16.0], vec![0.0, 0.0, 16.0]];
+        let before = grid.clone();
+        smooth(&mut grid, 0..=1, 1..=1, 1.0);
+        // Neighbours in the last column are read, with the edge rows clamped
+        assert_eq!(grid[0][1], 4.0);
+        assert_eq!(grid[1][1], 4.0);
+        for r in 0..2 {
+            assert_eq!(grid[r][0], before[r][0]);
+            assert_eq!(grid[r][2], before[r][2]);
+        }
+    }
+
+    #[test]
+    fn smooth_edges_clamped() {
+        let mut grid = vec![vec![16.0, 0.0]];
+        smooth(&mut grid, 0..=0, 0..=0, 1.0);
+        // Missing rows repeat the only row and the left column repeats itself
+        assert_eq!(grid[0][0], 12.0);
+    }
+
+    #[test]
+    fn smooth_strength() {
+        let spike = || {
+            let mut grid = vec![vec![0.0; 3]; 3];
+            grid[1][1] = 16.0;
+            grid
+        };
+        let mut grid = spike();
+        smooth(&mut grid, 1..=1, 1..=1, 0.0);
+        assert_eq!(grid[1][1], 16.0);
+        let mut grid = spike();
+        smooth(&mut grid, 1..=1, 1..=1, 0.5);
+        assert_eq!(grid[1][1], 10.0);
+        let mut grid = spike();
+        smooth(&mut grid, 1..=1, 1..=1, 2.0);
+        assert_eq!(grid[1][1], 4.0);
+    }
+
+    #[test]
+    fn paste_modes() {
+        let combined = PasteMode::ALL.map(|mode| mode.combine(8.0, 4.0));
+        assert_eq!(combined, [4.0, 12.0, 32.0, 0.32]);
+        assert!(!PasteMode::Replace.reads_cells());
+        assert!(PasteMode::Add.reads_cells());
+    }
+
+    #[test]
+    fn paste_percent() {
+        assert_eq!(PasteMode::Percent.combine(12.5, 100.0), 12.5);
+        assert_eq!(PasteMode::Percent.combine(12.5, 50.0), 6.25);
+        assert_eq!(PasteMode::Percent.combine(-8.0, 150.0), -12.0);
+        assert_eq!(PasteMode::Percent.combine(12.5, 0.0), 0.0);
+    }
+}
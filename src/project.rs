@@ -0,0 +1,264 @@
+//! Information about a binary kept in a file beside it, never written to the binary itself.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Project {
+    #[serde(default)]
+    pub notes: CellNotes,
+    /// Decimals picked for tables and scalars, keyed by name
+    #[serde(default)]
+    pub decimals: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub presets: ScalarPresets,
+    /// Chart visibility and width of tables, keyed by name
+    #[serde(default)]
+    pub charts: BTreeMap<String, ChartLayout>,
+}
+
+impl Project {
+    /// Project file used for a binary when none is given, `<binary>.hextuner.json`.
+    pub fn default_path(bin_path: &Path) -> PathBuf {
+        let mut name = bin_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".hextuner.json");
+        bin_path.with_file_name(name)
+    }
+
+    /// Read a project file, a file that does not exist yet is an empty project.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Text attached to a table cell
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellNote {
+    /// Name of the table
+    pub map: String,
+    pub row: usize,
+    pub col: usize,
+    pub text: String,
+}
+
+/// Notes of every table, at most one per cell
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CellNotes(Vec<CellNote>);
+
+impl CellNotes {
+    fn position(&self, map: &str, row: usize, col: usize) -> Option<usize> {
+        self.0
+            .iter()
+            .position(|n| n.map == map && n.row == row && n.col == col)
+    }
+
+    /// Set the note of a cell, replacing any it already had.
+    pub fn add(&mut self, map: &str, row: usize, col: usize, text: String) {
+        match self.position(map, row, col) {
+            Some(i) => self.0[i].text = text,
+            None => self.0.push(CellNote {
+                map: map.to_string(),
+                row,
+                col,
+                text,
+            }),
+        }
+    }
+
+    /// Remove the note of a cell, returning its text.
+    pub fn remove(&mut self, map: &str, row: usize, col: usize) -> Option<String> {
+        self.position(map, row, col).map(|i| self.0.remove(i).text)
+    }
+
+    pub fn get(&self, map: &str, row: usize, col: usize) -> Option<&str> {
+        self.position(map, row, col)
+            .map(|i| self.0[i].text.as_str())
+    }
+
+    /// Notes of one table keyed by (row, column).
+    pub fn for_map(&self, map: &str) -> HashMap<(usize, usize), String> {
+        self.0
+            .iter()
+            .filter(|n| n.map == map)
+            .map(|n| ((n.row, n.col), n.text.clone()))
+            .collect()
+    }
+}
+
+/// How the chart of a table pane is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChartLayout {
+    pub visible: bool,
+    /// Share of the pane width given to the chart
+    pub fraction: f32,
+}
+
+/// Named value of a scalar, kept as the converted value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub value: f64,
+}
+
+/// Presets of every scalar, keyed by scalar name
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScalarPresets(BTreeMap<String, Vec<Preset>>);
+
+impl ScalarPresets {
+    /// Add a preset to a scalar, replacing one it already had with the same name.
+    pub fn add(&mut self, scalar: &str, preset: Preset) {
+        let presets = self.0.entry(scalar.to_string()).or_default();
+        match presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => existing.value = preset.value,
+            None => presets.push(preset),
+        }
+    }
+
+    /// Remove a preset of a scalar by its position, returning it.
+    pub fn remove(&mut self, scalar: &str, index: usize) -> Option<Preset> {
+        let presets = self.0.get_mut(scalar)?;
+        let preset = (index < presets.len()).then(|| presets.remove(index));
+        if presets.is_empty() {
+            self.0.remove(scalar);
+        }
+        preset
+    }
+
+    pub fn get(&self, scalar: &str) -> &[Preset] {
+        self.0.get(scalar).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, value: f64) -> Preset {
+        Preset {
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn notes_add_replaces_cell() {
+        let mut notes = CellNotes::default();
+        notes.add("Ignition timing", 1, 2, "Knock here".to_string());
+        notes.add("Ignition timing", 1, 2, "Pulled 2 degrees".to_string());
+        notes.add("Boost target", 1, 2, "Stock".to_string());
+        assert_eq!(notes.get("Ignition timing", 1, 2), Some("Pulled 2 degrees"));
+        assert_eq!(notes.get("Boost target", 1, 2), Some("Stock"));
+        assert_eq!(notes.get("Ignition timing", 2, 1), None);
+        assert_eq!(notes.0.len(), 2);
+    }
+
+    #[test]
+    fn notes_remove() {
+        let mut notes = CellNotes::default();
+        notes.add("Ignition timing", 0, 0, "Idle".to_string());
+        assert_eq!(notes.remove("Ignition timing", 0, 1), None);
+        assert_eq!(notes.remove("Boost target", 0, 0), None);
+        assert_eq!(
+            notes.remove("Ignition timing", 0, 0),
+            Some("Idle".to_string())
+        );
+        assert_eq!(notes.get("Ignition timing", 0, 0), None);
+    }
+
+    #[test]
+    fn notes_for_map() {
+        let mut notes = CellNotes::default();
+        notes.add("Ignition timing", 0, 0, "Idle".to_string());
+        notes.add("Ignition timing", 7, 3, "Full load".to_string());
+        notes.add("Boost target", 0, 0, "Stock".to_string());
+        let timing = notes.for_map("Ignition timing");
+        assert_eq!(timing.len(), 2);
+        assert_eq!(timing[&(0, 0)], "Idle");
+        assert_eq!(timing[&(7, 3)], "Full load");
+        assert!(notes.for_map("Fuel").is_empty());
+    }
+
+    #[test]
+    fn presets_add_replaces_by_name() {
+        let mut presets = ScalarPresets::default();
+        presets.add("Rev limiter", preset("Street", 6500.0));
+        presets.add("Rev limiter", preset("Track", 7200.0));
+        presets.add("Rev limiter", preset("Street", 6800.0));
+        assert_eq!(
+            presets.get("Rev limiter"),
+            [preset("Street", 6800.0), preset("Track", 7200.0)]
+        );
+        assert!(presets.get("Maximum boost").is_empty());
+    }
+
+    #[test]
+    fn presets_remove() {
+        let mut presets = ScalarPresets::default();
+        presets.add("Rev limiter", preset("Street", 6500.0));
+        presets.add("Rev limiter", preset("Track", 7200.0));
+        assert_eq!(presets.remove("Rev limiter", 2), None);
+        assert_eq!(presets.remove("Maximum boost", 0), None);
+        assert_eq!(
+            presets.remove("Rev limiter", 0),
+            Some(preset("Street", 6500.0))
+        );
+        assert_eq!(
+            presets.remove("Rev limiter", 0),
+            Some(preset("Track", 7200.0))
+        );
+        assert!(presets.0.is_empty());
+    }
+
+    #[test]
+    fn presets_round_trip() {
+        let mut project = Project::default();
+        project.presets.add("Rev limiter", preset("Street", 6500.0));
+        project.presets.add("Maximum boost", preset("Low", 0.8));
+        let json = serde_json::to_string(&project).unwrap();
+        let loaded: Project = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            loaded.presets.get("Rev limiter"),
+            [preset("Street", 6500.0)]
+        );
+        assert_eq!(loaded.presets.get("Maximum boost"), [preset("Low", 0.8)]);
+    }
+
+    #[test]
+    fn presets_missing_from_older_projects() {
+        let project: Project = serde_json::from_str(r#"{ "notes": [] }"#).unwrap();
+        assert!(project.presets.0.is_empty());
+        let presets: ScalarPresets =
+            serde_json::from_str(r#"{ "Rev limiter": [{ "name": "Street", "value": 6500 }] }"#)
+                .unwrap();
+        assert_eq!(presets.get("Rev limiter"), [preset("Street", 6500.0)]);
+    }
+
+    #[test]
+    fn chart_layouts_round_trip() {
+        let mut project = Project::default();
+        let layout = ChartLayout {
+            visible: false,
+            fraction: 0.35,
+        };
+        project.charts.insert("Ignition timing".to_string(), layout);
+        let json = serde_json::to_string(&project).unwrap();
+        let loaded: Project = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.charts.get("Ignition timing"), Some(&layout));
+        let older: Project = serde_json::from_str(r#"{ "notes": [] }"#).unwrap();
+        assert!(older.charts.is_empty());
+    }
+}
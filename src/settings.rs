@@ -0,0 +1,121 @@
+//! User configurable behaviour, set from command line flags and some from the UI.
+
+use std::{fmt::Display, path::PathBuf, time::Duration};
+
+use crate::colormap::Colormap;
+
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    /// Directory to place the backup of the binary in, defaults to the binary's directory.
+    pub backup_dir: Option<PathBuf>,
+    /// Skip the file dialogs and load the built in demo definition and binary
+    pub demo: bool,
+    /// Overrides the base offset of the loaded definition
+    pub base_offset: Option<i64>,
+    /// Display values with `,` between groups of thousands
+    pub thousands_separators: bool,
+    /// Write edited panes to the binary this often, off when `None`
+    pub autosave: Option<Duration>,
+    /// Colour scale new tables start with
+    pub colormap: Colormap,
+    /// Project file with cell notes, defaults to `<binary>.hextuner.json`
+    pub project: Option<PathBuf>,
+    /// Where panes opened from the nav are placed
+    pub placement: PanePlacement,
+    /// Open tables with their axes locked against editing
+    pub lock_axes: bool,
+    /// Read text axis labels as names and empty ones as 0 instead of reporting them
+    pub lenient_labels: bool,
+    /// JSON file describing the checksum regions of the binary
+    pub checksums: Option<PathBuf>,
+}
+
+/// Where a newly opened pane goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanePlacement {
+    /// Split the focused pane, the new pane goes below it
+    #[default]
+    SplitFocus,
+    /// Split whichever pane has the most room along its longer side
+    Grid,
+}
+
+impl PanePlacement {
+    pub const ALL: [PanePlacement; 2] = [Self::SplitFocus, Self::Grid];
+
+    /// Parse the name used on the command line, `split` or `grid`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "split" => Some(Self::SplitFocus),
+            "grid" => Some(Self::Grid),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PanePlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::SplitFocus => "Split focused pane",
+            Self::Grid => "Tile in a grid",
+        })
+    }
+}
+
+/// Parse a possibly negative decimal or `0x` prefixed hex integer.
+fn parse_offset(value: &str) -> Option<i64> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, value),
+    };
+    let offset = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => value.parse().ok()?,
+    };
+    Some(if negative { -offset } else { offset })
+}
+
+impl Settings {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut settings = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--backup-dir" => settings.backup_dir = args.next().map(PathBuf::from),
+                "--project" => settings.project = args.next().map(PathBuf::from),
+                "--checksums" => settings.checksums = args.next().map(PathBuf::from),
+                "--demo" => settings.demo = true,
+                "--thousands-separators" => settings.thousands_separators = true,
+                "--lock-axes" => settings.lock_axes = true,
+                "--lenient-labels" => settings.lenient_labels = true,
+                "--autosave" => {
+                    settings.autosave = args
+                        .next()
+                        .and_then(|s| s.parse().ok())
+                        .filter(|&seconds| seconds > 0)
+                        .map(Duration::from_secs)
+                }
+                "--colormap" => {
+                    if let Some(colormap) = args.next().as_deref().and_then(Colormap::from_name) {
+                        settings.colormap = colormap;
+                    }
+                }
+                "--placement" => {
+                    if let Some(placement) =
+                        args.next().as_deref().and_then(PanePlacement::from_name)
+                    {
+                        settings.placement = placement;
+                    }
+                }
+                "--base-offset" => {
+                    settings.base_offset = args.next().as_deref().and_then(parse_offset)
+                }
+                _ => {}
+            }
+        }
+
+        settings
+    }
+}
@@ -0,0 +1,115 @@
+//! Edited values keyed by definition name, so they can be shared and applied to another binary
+//! whose definition uses the same names.
+
+use std::{
+    collections::BTreeMap,
+    io::{Read, Seek, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::definitions::{Axis, Scalar, Table};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Tune {
+    #[serde(default)]
+    pub scalars: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub tables: BTreeMap<String, TableTune>,
+}
+
+/// Values of the stored axes of a table, user defined axes are left out
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TableTune {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z: Option<Vec<f64>>,
+}
+
+fn read_axis<R: Read + Seek>(axis: &Axis, bin: &mut R) -> std::io::Result<Option<Vec<f64>>> {
+    if axis.writeable() {
+        Ok(Some(axis.read(bin)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Write one axis of a tune, returning why it was skipped if it does not fit.
+fn write_axis<W: Write + Seek>(
+    axis: &Axis,
+    values: &Option<Vec<f64>>,
+    label: &str,
+    bin: &mut W,
+) -> std::io::Result<Option<String>> {
+    let Some(values) = values else {
+        return Ok(None);
+    };
+    if !axis.writeable() {
+        return Ok(Some(format!("{label}: not stored in the binary")));
+    }
+    if values.len() != axis.len() {
+        return Ok(Some(format!(
+            "{label}: {} values, definition has {}",
+            values.len(),
+            axis.len()
+        )));
+    }
+    axis.write(bin, values.clone())?;
+    Ok(None)
+}
+
+impl Tune {
+    /// Read the current values of the given definitions from the binary.
+    pub fn capture<'a, R: Read + Seek>(
+        bin: &mut R,
+        tables: impl IntoIterator<Item = &'a Table>,
+        scalars: impl IntoIterator<Item = &'a Scalar>,
+    ) -> std::io::Result<Self> {
+        let mut tune = Self::default();
+        for scalar in scalars {
+            tune.scalars.insert(scalar.name.clone(), scalar.read(bin)?);
+        }
+        for table in tables {
+            let values = TableTune {
+                x: read_axis(&table.x, bin)?,
+                y: read_axis(&table.y, bin)?,
+                z: read_axis(&table.z, bin)?,
+            };
+            tune.tables.insert(table.name.clone(), values);
+        }
+        Ok(tune)
+    }
+
+    /// Write every value that matches a definition by name and size. Returns what was skipped.
+    pub fn apply<B: Write + Seek>(
+        &self,
+        bin: &mut B,
+        tables: &[Table],
+        scalars: &[Scalar],
+    ) -> std::io::Result<Vec<String>> {
+        let mut skipped = Vec::new();
+        for (name, value) in &self.scalars {
+            match scalars.iter().find(|s| &s.name == name) {
+                Some(scalar) => scalar.write(bin, *value)?,
+                None => skipped.push(format!("{name}: no scalar with this name")),
+            }
+        }
+        for (name, values) in &self.tables {
+            let Some(table) = tables.iter().find(|t| &t.name == name) else {
+                skipped.push(format!("{name}: no table with this name"));
+                continue;
+            };
+            for (axis, values, label) in [
+                (&table.x, &values.x, "x"),
+                (&table.y, &values.y, "y"),
+                (&table.z, &values.z, "z"),
+            ] {
+                skipped.extend(write_axis(axis, values, &format!("{name} {label}"), bin)?);
+            }
+        }
+        Ok(skipped)
+    }
+}
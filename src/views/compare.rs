@@ -0,0 +1,330 @@
+use std::{fmt::Display, ops::Range};
+
+use iced::{
+    widget::{button, column, container, pick_list, row, scrollable, text, tooltip, Space},
+    Color, Element, Length, Padding,
+};
+
+use super::{
+    panes::style,
+    table::{grid_body, visible_rows, CELL_WIDTH, DEFAULT_VIEWPORT_HEIGHT, OVERSCAN, ROW_HEIGHT},
+};
+use crate::{
+    definitions::{group_thousands, Axis, Table},
+    FileGuard, Message,
+};
+
+/// How the difference from the reference is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeltaMode {
+    #[default]
+    Absolute,
+    Percent,
+}
+
+impl DeltaMode {
+    pub const ALL: [DeltaMode; 2] = [Self::Absolute, Self::Percent];
+}
+
+impl Display for DeltaMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Absolute => "Difference",
+            Self::Percent => "Difference %",
+        })
+    }
+}
+
+/// Change from `reference` to `working`. A percentage of a zero reference is `None` unless
+/// the value is still zero.
+pub fn delta(working: f64, reference: f64, mode: DeltaMode) -> Option<f64> {
+    match mode {
+        DeltaMode::Absolute => Some(working - reference),
+        DeltaMode::Percent if reference == 0.0 => (working == 0.0).then_some(0.0),
+        DeltaMode::Percent => Some((working - reference) / reference.abs() * 100.0),
+    }
+}
+
+/// Value whose change from `reference` is `delta`, the inverse of [`delta`].
+pub fn apply_delta(delta: f64, reference: f64, mode: DeltaMode) -> f64 {
+    match mode {
+        DeltaMode::Absolute => reference + delta,
+        DeltaMode::Percent => reference + delta / 100.0 * reference.abs(),
+    }
+}
+
+/// Values of one axis in both binaries
+#[derive(Debug)]
+struct Values {
+    working: Vec<f64>,
+    reference: Vec<f64>,
+}
+
+impl Values {
+    fn read(
+        axis: &Axis,
+        working: &mut FileGuard,
+        reference: &mut FileGuard,
+    ) -> std::io::Result<Self> {
+        Ok(Self {
+            working: axis.read(working)?,
+            reference: axis.read(reference)?,
+        })
+    }
+}
+
+/// A table as stored in the working binary next to the same table in the reference binary
+#[derive(Debug)]
+pub struct TableCompareView {
+    pane_id: usize,
+    pub table: Table,
+    x: Values,
+    y: Values,
+    z: Values,
+    pub mode: DeltaMode,
+    /// Display values with thousands separators
+    pub separators: bool,
+    source: FileGuard,
+    reference: FileGuard,
+    /// Vertical scroll offset of the grid
+    scroll_y: f32,
+    /// Height of the visible part of the grid, `None` until it has been scrolled
+    viewport_height: Option<f32>,
+}
+
+impl TableCompareView {
+    pub fn new(
+        pane_id: usize,
+        table: Table,
+        mut source: FileGuard,
+        mut reference: FileGuard,
+    ) -> std::io::Result<Self> {
+        Ok(Self {
+            pane_id,
+            x: Values::read(&table.x, &mut source, &mut reference)?,
+            y: Values::read(&table.y, &mut source, &mut reference)?,
+            z: Values::read(&table.z, &mut source, &mut reference)?,
+            table,
+            mode: DeltaMode::default(),
+            separators: false,
+            source,
+            reference,
+            scroll_y: 0.0,
+            viewport_height: None,
+        })
+    }
+
+    pub fn pane_id(&self) -> usize {
+        self.pane_id
+    }
+
+    /// Read both binaries again, the working side only shows values that have been written.
+    pub fn reload(&mut self) -> std::io::Result<()> {
+        self.x = Values::read(&self.table.x, &mut self.source, &mut self.reference)?;
+        self.y = Values::read(&self.table.y, &mut self.source, &mut self.reference)?;
+        self.z = Values::read(&self.table.z, &mut self.source, &mut self.reference)?;
+        Ok(())
+    }
+
+    pub fn scrolled(&mut self, viewport: scrollable::Viewport) {
+        self.scroll_y = viewport.absolute_offset().y;
+        self.viewport_height = Some(viewport.bounds().height);
+    }
+
+    /// Data rows in or near the visible part of the grid, below the x axis row
+    fn visible_rows(&self) -> Range<usize> {
+        visible_rows(
+            self.scroll_y - ROW_HEIGHT,
+            self.viewport_height.unwrap_or(DEFAULT_VIEWPORT_HEIGHT),
+            ROW_HEIGHT,
+            self.y.working.len(),
+            OVERSCAN,
+        )
+    }
+
+    fn format(&self, axis: &Axis, value: f64) -> String {
+        let value = axis.format(value);
+        if self.separators {
+            group_thousands(&value)
+        } else {
+            value
+        }
+    }
+
+    fn format_delta(&self, working: f64, reference: f64) -> String {
+        match (delta(working, reference, self.mode), self.mode) {
+            (None, _) => "n/a".to_string(),
+            (Some(d), DeltaMode::Absolute) => format!("{d:+}"),
+            (Some(d), DeltaMode::Percent) => format!("{d:+.1}%"),
+        }
+    }
+
+    /// Axis value, with the reference value beside it when they differ
+    fn axis_cell(&self, axis: &Axis, values: &Values, i: usize) -> Element<Message> {
+        let working = values.working[i];
+        let reference = values.reference[i];
+        let label = if working == reference {
+            self.format(axis, working)
+        } else {
+            format!(
+                "{} (ref {})",
+                self.format(axis, working),
+                self.format(axis, reference)
+            )
+        };
+        container(text(label).size(12))
+            .width(CELL_WIDTH)
+            .height(ROW_HEIGHT)
+            .align_y(iced::alignment::Vertical::Center)
+            .into()
+    }
+
+    fn data_cell(&self, i: usize) -> Element<Message> {
+        let working = self.z.working[i];
+        let reference = self.z.reference[i];
+        let same = working == reference;
+
+        let dim = move |theme: &iced::Theme| text::Style {
+            color: same.then(|| theme.extended_palette().background.strong.color),
+        };
+        let mut cell = column![text(self.format(&self.table.z, working))
+            .size(12)
+            .style(dim)];
+        if !same {
+            cell = cell.push(text(self.format_delta(working, reference)).size(10));
+        }
+
+        let background = match working.partial_cmp(&reference) {
+            Some(std::cmp::Ordering::Greater) => Some(Color::from_rgba(1.0, 0.3, 0.2, 0.25)),
+            Some(std::cmp::Ordering::Less) => Some(Color::from_rgba(0.2, 0.5, 1.0, 0.25)),
+            _ => None,
+        };
+        let cell = container(cell)
+            .width(CELL_WIDTH)
+            .height(ROW_HEIGHT)
+            .padding([0, 4])
+            .style(move |_| container::Style {
+                background: background.map(Into::into),
+                ..Default::default()
+            });
+        let reference = format!("Reference {}", self.format(&self.table.z, reference));
+        tooltip(cell, text(reference), tooltip::Position::FollowCursor)
+            .style(container::rounded_box)
+            .into()
+    }
+
+    fn grid(&self) -> Element<Message> {
+        let width = self.x.working.len();
+        let header = (0..width).map(|x| self.axis_cell(&self.table.x, &self.x, x));
+        let header = row(std::iter::once(Space::new(CELL_WIDTH, ROW_HEIGHT).into()).chain(header));
+        let total = self.y.working.len();
+
+        grid_body([header.into()], self.visible_rows(), total, |y| {
+            let cells = (0..width).map(|x| self.data_cell(y * width + x));
+            row(std::iter::once(self.axis_cell(&self.table.y, &self.y, y)).chain(cells)).into()
+        })
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let changed = self
+            .z
+            .working
+            .iter()
+            .zip(&self.z.reference)
+            .filter(|(w, r)| w != r)
+            .count();
+        let toolbar = container(
+            row![
+                pick_list(DeltaMode::ALL, Some(self.mode), |mode| {
+                    Message::SetDeltaMode {
+                        pane: self.pane_id,
+                        mode,
+                    }
+                }),
+                button("Refresh").on_press(Message::RefreshCompare { pane: self.pane_id }),
+                text(format!(
+                    "{changed} cells differ from the reference, unwritten edits are not shown"
+                )),
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding(5)
+        .style(style::title_bar_active);
+
+        column![
+            toolbar,
+            scrollable(container(self.grid()).padding(Padding::new(0.0).bottom(15).right(15)))
+                .direction(scrollable::Direction::Both {
+                    vertical: scrollable::Scrollbar::new(),
+                    horizontal: scrollable::Scrollbar::new(),
+                })
+                .on_scroll(|viewport| Message::CompareScrolled {
+                    pane: self.pane_id,
+                    viewport,
+                }),
+        ]
+        .spacing(5)
+        .padding(5)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::region::{RegionField, RegionView};
+
+    #[test]
+    fn delta_absolute() {
+        assert_eq!(delta(12.5, 10.0, DeltaMode::Absolute), Some(2.5));
+        assert_eq!(delta(-3.0, 2.0, DeltaMode::Absolute), Some(-5.0));
+        assert_eq!(delta(4.0, 0.0, DeltaMode::Absolute), Some(4.0));
+    }
+
+    #[test]
+    fn delta_percent() {
+        assert_eq!(delta(15.0, 10.0, DeltaMode::Percent), Some(50.0));
+        assert_eq!(delta(5.0, 10.0, DeltaMode::Percent), Some(-50.0));
+        // Relative to the size of a negative reference, moving up is positive
+        assert_eq!(delta(-5.0, -10.0, DeltaMode::Percent), Some(50.0));
+    }
+
+    #[test]
+    fn delta_percent_of_zero_reference() {
+        assert_eq!(delta(0.0, 0.0, DeltaMode::Percent), Some(0.0));
+        assert_eq!(delta(3.0, 0.0, DeltaMode::Percent), None);
+        assert_eq!(delta(-3.0, 0.0, DeltaMode::Percent), None);
+    }
+
+    #[test]
+    fn apply_delta_inverts_delta() {
+        for mode in DeltaMode::ALL {
+            for (working, reference) in [(12.5, 10.0), (5.0, 10.0), (-5.0, -10.0), (0.0, 0.0)] {
+                let change = delta(working, reference, mode).unwrap();
+                assert_eq!(apply_delta(change, reference, mode), working);
+            }
+        }
+        // Any percentage of a zero reference is still zero
+        assert_eq!(apply_delta(50.0, 0.0, DeltaMode::Percent), 0.0);
+    }
+
+    #[test]
+    fn large_comparison_builds_visible_rows() {
+        let source = FileGuard::from(vec![0u8; 64 * 64]);
+        let mut region = RegionView::new(0, source.clone());
+        region.edit(RegionField::Rows, "64".to_string());
+        region.edit(RegionField::Columns, "64".to_string());
+        let reference = FileGuard::from(vec![1u8; 64 * 64]);
+        let mut view =
+            TableCompareView::new(0, region.table().unwrap(), source, reference).unwrap();
+        view.viewport_height = Some(10.0 * ROW_HEIGHT);
+        assert_eq!(view.visible_rows(), 0..13);
+        view.scroll_y = 40.0 * ROW_HEIGHT;
+        assert_eq!(view.visible_rows(), 35..53);
+        assert!(view.visible_rows().len() * 64 <= (11 + 2 * OVERSCAN) * 64);
+        view.scroll_y = 60.0 * ROW_HEIGHT;
+        assert_eq!(view.visible_rows(), 55..64);
+    }
+}
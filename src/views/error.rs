@@ -1,4 +1,7 @@
-use iced::{widget::text, Element};
+use iced::{
+    widget::{scrollable, text},
+    Element,
+};
 
 use crate::Message;
 
@@ -10,6 +13,6 @@ impl ErrorView {
         Self { text }
     }
     pub fn view(&self) -> Element<Message> {
-        text(&self.text).into()
+        scrollable(text(&self.text)).into()
     }
 }
@@ -0,0 +1,90 @@
+use iced::{
+    widget::{column, row, text, text_input},
+    Element,
+};
+
+use crate::{definitions::parse_raw, eval, Message};
+
+/// Input of the expression tester being edited
+#[derive(Debug, Clone, Copy)]
+pub enum TesterField {
+    Expression,
+    Raw,
+    Value,
+}
+
+/// Scratch pad to try a conversion expression in both directions
+#[derive(Debug)]
+pub struct ExprTesterView {
+    pane_id: usize,
+    pub expression: String,
+    pub raw: String,
+    pub value: String,
+}
+
+impl ExprTesterView {
+    pub fn new(pane_id: usize) -> Self {
+        Self {
+            pane_id,
+            expression: "X".to_string(),
+            raw: "0".to_string(),
+            value: "0".to_string(),
+        }
+    }
+
+    pub fn edit(&mut self, field: TesterField, value: String) {
+        match field {
+            TesterField::Expression => self.expression = value,
+            TesterField::Raw => self.raw = value,
+            TesterField::Value => self.value = value,
+        }
+    }
+
+    fn input(&self, label: &str, value: &str, field: TesterField) -> Element<Message> {
+        let pane = self.pane_id;
+        row![
+            text(label.to_string()).width(100),
+            text_input(label, value).on_input(move |value| Message::EditTester {
+                pane,
+                field,
+                value
+            }),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let (forward, reverse) = match eval::parse(&self.expression) {
+            Ok(expression) => (
+                match parse_raw(&self.raw, 4) {
+                    Some(raw) => format!("{raw} -> {}", expression.eval(raw)),
+                    None => "Raw value must be an unsigned 32 bit integer".to_string(),
+                },
+                match self.value.trim().parse::<f64>() {
+                    Ok(value) => expression
+                        .eval_reverse(value)
+                        .map(|raw| format!("{value} -> {raw}"))
+                        .map_err(|e| format!("Cannot convert back: {e}")),
+                    Err(e) => Err(format!("Invalid value: {e}")),
+                },
+            ),
+            Err(e) => (format!("Invalid expression: {e}"), Ok(String::new())),
+        };
+        let reverse = match reverse {
+            Ok(reverse) => text(reverse),
+            Err(e) => text(e).style(text::danger),
+        };
+
+        column![
+            self.input("Expression", &self.expression, TesterField::Expression),
+            self.input("Raw", &self.raw, TesterField::Raw),
+            text(forward),
+            self.input("Value", &self.value, TesterField::Value),
+            reverse,
+        ]
+        .spacing(5)
+        .padding(5)
+        .into()
+    }
+}
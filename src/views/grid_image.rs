@@ -0,0 +1,133 @@
+//! Table grid drawn to a PNG for documentation, with the colours and markers shown in its pane.
+
+use std::path::Path;
+
+use plotters::{
+    coord::Shift,
+    prelude::*,
+    style::text_anchor::{HPos, Pos, VPos},
+};
+
+use super::table::ROW_HEIGHT;
+
+/// One cell of the image
+#[derive(Debug)]
+pub struct ImageCell {
+    pub text: String,
+    /// Heatmap colour, translucent colours are blended over white
+    pub background: Option<iced::Color>,
+    /// Border of modified or invalid cells
+    pub border: Option<iced::Color>,
+}
+
+/// Table as laid out in its pane, rows and columns in the order they are shown
+#[derive(Debug)]
+pub struct GridImage {
+    /// Drawn above the grid
+    pub caption: String,
+    pub columns: Vec<ImageCell>,
+    /// Header and data cells of each row
+    pub rows: Vec<(ImageCell, Vec<ImageCell>)>,
+    pub column_widths: Vec<f32>,
+    pub header_width: f32,
+}
+
+const MARGIN: f64 = 10.0;
+const CAPTION_HEIGHT: f64 = 30.0;
+const CAPTION_SIZE: f64 = 16.0;
+const TEXT_SIZE: f64 = 12.0;
+const TEXT_PADDING: f64 = 4.0;
+/// Longest side of the image in pixels, larger grids are scaled down along with their text
+const MAX_SIDE: f64 = 8000.0;
+
+/// Colour as it looks drawn over a white background
+fn blend(color: iced::Color) -> RGBColor {
+    let channel = |c: f32| ((c * color.a + 1.0 - color.a) * 255.0).round() as u8;
+    RGBColor(channel(color.r), channel(color.g), channel(color.b))
+}
+
+/// Draw one cell with its top left corner at `(x, y)`, sizes are already scaled.
+fn draw_cell<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    cell: &ImageCell,
+    (x, y): (f64, f64),
+    (width, height): (f64, f64),
+    scale: f64,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let corners = [
+        (x.round() as i32, y.round() as i32),
+        ((x + width).round() as i32, (y + height).round() as i32),
+    ];
+    let fill = cell.background.map_or(WHITE, blend);
+    area.draw(&Rectangle::new(corners, fill.filled()))?;
+    let border = match cell.border {
+        Some(color) => blend(color).stroke_width((2.0 * scale).ceil() as u32),
+        None => RGBColor(200, 200, 200).stroke_width(1),
+    };
+    area.draw(&Rectangle::new(corners, border))?;
+    let style = ("sans-serif", TEXT_SIZE * scale)
+        .into_font()
+        .color(&BLACK)
+        .pos(Pos::new(HPos::Left, VPos::Center));
+    let anchor = (
+        (x + TEXT_PADDING * scale).round() as i32,
+        (y + height / 2.0).round() as i32,
+    );
+    area.draw(&Text::new(cell.text.clone(), anchor, style))
+}
+
+/// Write the grid to a PNG at `path`.
+pub fn render(grid: &GridImage, path: &Path) -> anyhow::Result<()> {
+    let row_height = ROW_HEIGHT as f64;
+    let header_width = grid.header_width as f64;
+    let widths: Vec<f64> = grid.column_widths.iter().map(|&w| w as f64).collect();
+    let width = MARGIN * 2.0 + header_width + widths.iter().sum::<f64>();
+    let height = MARGIN * 2.0 + CAPTION_HEIGHT + row_height * (grid.rows.len() + 1) as f64;
+    let scale = (MAX_SIDE / width.max(height)).min(1.0);
+
+    let size = (
+        (width * scale).ceil() as u32,
+        (height * scale).ceil() as u32,
+    );
+    let area = BitMapBackend::new(path, size).into_drawing_area();
+    area.fill(&WHITE)?;
+    let caption = ("sans-serif", CAPTION_SIZE * scale)
+        .into_font()
+        .color(&BLACK);
+    let origin = ((MARGIN * scale) as i32, (MARGIN * scale) as i32);
+    area.draw(&Text::new(grid.caption.clone(), origin, caption))?;
+
+    let lefts: Vec<f64> = widths
+        .iter()
+        .scan(MARGIN + header_width, |left, width| {
+            let start = *left;
+            *left += width;
+            Some(start)
+        })
+        .collect();
+    let top = MARGIN + CAPTION_HEIGHT;
+    let header_rows = std::iter::once((None, &grid.columns));
+    let data_rows = grid
+        .rows
+        .iter()
+        .map(|(header, cells)| (Some(header), cells));
+    for (r, (header, cells)) in header_rows.chain(data_rows).enumerate() {
+        let y = (top + r as f64 * row_height) * scale;
+        if let Some(header) = header {
+            let position = (MARGIN * scale, y);
+            draw_cell(
+                &area,
+                header,
+                position,
+                (header_width * scale, row_height * scale),
+                scale,
+            )?;
+        }
+        for ((cell, left), width) in cells.iter().zip(&lefts).zip(&widths) {
+            let size = (width * scale, row_height * scale);
+            draw_cell(&area, cell, (left * scale, y), size, scale)?;
+        }
+    }
+    area.present()?;
+    Ok(())
+}
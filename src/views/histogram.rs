@@ -0,0 +1,110 @@
+use iced::{
+    widget::canvas::{Cache, Frame, Geometry},
+    Size,
+};
+use plotters_iced::Chart;
+
+use crate::Message;
+
+/// Frequency distribution of a table's values
+#[derive(Debug)]
+pub struct Histogram {
+    /// Lower bound of the first bin and upper bound of the last bin
+    range: (f64, f64),
+    counts: Vec<usize>,
+    cache: Cache,
+}
+
+/// Count `values` into evenly sized bins, a flat set of values is widened by 0.5 either way
+/// so there is still a range to split.
+pub fn bin_counts(values: &[f64], bins: usize) -> ((f64, f64), Vec<usize>) {
+    let mut counts = vec![0; bins.max(1)];
+    let Some((min, max)) = values.iter().fold(None, |range, &v| match range {
+        None => Some((v, v)),
+        Some((min, max)) => Some((v.min(min), v.max(max))),
+    }) else {
+        return ((0.0, 1.0), counts);
+    };
+
+    let (min, max) = if max - min <= f64::EPSILON {
+        (min - 0.5, max + 0.5)
+    } else {
+        (min, max)
+    };
+
+    let width = (max - min) / counts.len() as f64;
+    for v in values {
+        let bin = (((v - min) / width) as usize).min(counts.len() - 1);
+        counts[bin] += 1;
+    }
+
+    ((min, max), counts)
+}
+
+impl Histogram {
+    pub fn new(data: &[String]) -> Self {
+        let mut histogram = Self {
+            range: (0.0, 1.0),
+            counts: Vec::new(),
+            cache: Cache::new(),
+        };
+        histogram.set_data(data);
+        histogram
+    }
+    pub fn set_data(&mut self, data: &[String]) {
+        let values: Vec<f64> = data.iter().filter_map(|v| v.parse().ok()).collect();
+        // Square root choice, good enough for table sized data
+        let bins = (values.len() as f64).sqrt().ceil() as usize;
+        (self.range, self.counts) = bin_counts(&values, bins);
+        self.cache.clear();
+    }
+}
+
+impl Chart<Message> for Histogram {
+    type State = ();
+
+    #[inline]
+    fn draw<R: plotters_iced::Renderer, F: Fn(&mut Frame)>(
+        &self,
+        renderer: &R,
+        bounds: Size,
+        draw_fn: F,
+    ) -> Geometry {
+        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    }
+    fn build_chart<DB: plotters_iced::DrawingBackend>(
+        &self,
+        _state: &Self::State,
+        mut builder: plotters_iced::ChartBuilder<DB>,
+    ) {
+        use plotters::prelude::*;
+
+        let (min, max) = self.range;
+        let max_count = self.counts.iter().copied().max().unwrap_or_default().max(1);
+        let width = (max - min) / self.counts.len() as f64;
+
+        let mut chart = builder
+            .x_label_area_size(28)
+            .y_label_area_size(28)
+            .margin(20)
+            .build_cartesian_2d(min..max, 0..max_count)
+            .expect("failed to build chart");
+        chart
+            .configure_mesh()
+            .bold_line_style(plotters::style::colors::BLUE.mix(0.1))
+            .light_line_style(plotters::style::colors::BLUE.mix(0.05))
+            .axis_style(ShapeStyle::from(plotters::style::colors::BLUE.mix(0.45)).stroke_width(1))
+            .draw()
+            .expect("failed to draw chart mesh");
+
+        chart
+            .draw_series(self.counts.iter().enumerate().map(|(i, &count)| {
+                let start = min + width * i as f64;
+                Rectangle::new(
+                    [(start, 0), (start + width, count)],
+                    plotters::style::colors::BLUE.mix(0.5).filled(),
+                )
+            }))
+            .expect("failed to draw chart data");
+    }
+}
@@ -4,21 +4,71 @@ use iced::{
     widget::{
         self,
         button::{Status, Style},
-        column, scrollable, text,
+        column, container, mouse_area, row, scrollable, text,
     },
     Color, Element, Length, Theme,
 };
 
 use crate::{
-    definitions::{Scalar, Table},
+    definitions::{BinaryDefinition, Scalar, Table},
+    settings::PanePlacement,
     Message, Open,
 };
 
 #[derive(Default, Clone, Debug)]
 pub struct MapNav {
+    pub title: String,
     pub tables: Vec<Table>,
     pub scalars: Vec<Scalar>,
     pub categories: HashMap<u32, String>,
+    /// Items chosen with Ctrl/Shift-click, in the order they were chosen
+    pub selected: Vec<NavItem>,
+    /// Item whose right-click menu is open
+    pub menu: Option<NavItem>,
+}
+
+/// Index of a table or scalar in the nav
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavItem {
+    Table(usize),
+    Scalar(usize),
+}
+
+/// Entries of the right-click menu of a nav item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavAction {
+    Open,
+    /// Open beside the focused pane instead of below it
+    OpenSplit,
+    CopyAddress,
+    /// Show the definition as it was parsed from the XDF
+    ShowDefinition,
+    /// Show how the stored values are decoded
+    ShowStorage,
+    /// Discard unwritten edits in panes showing the item
+    Reset,
+}
+
+impl NavAction {
+    pub const ALL: [NavAction; 6] = [
+        Self::Open,
+        Self::OpenSplit,
+        Self::CopyAddress,
+        Self::ShowDefinition,
+        Self::ShowStorage,
+        Self::Reset,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Open => "Open",
+            Self::OpenSplit => "Open in new split",
+            Self::CopyAddress => "Copy address",
+            Self::ShowDefinition => "Show raw XDF",
+            Self::ShowStorage => "Show storage",
+            Self::Reset => "Reset to original",
+        }
+    }
 }
 
 fn button_color(_: &Theme, status: Status) -> Style {
@@ -29,21 +79,212 @@ fn button_color(_: &Theme, status: Status) -> Style {
     })
 }
 
+fn selected_button_color(_: &Theme, status: Status) -> Style {
+    Style::default().with_background(match status {
+        Status::Hovered => Color::from_rgba(0.0, 1.0, 1.0, 0.5),
+        Status::Pressed => Color::from_rgb(0.0, 1.0, 1.0),
+        _ => Color::from_rgba(0.0, 1.0, 1.0, 0.25),
+    })
+}
+
 impl MapNav {
+    pub fn new(bin_def: BinaryDefinition) -> Self {
+        Self {
+            title: bin_def.info.name,
+            categories: bin_def.info.categories,
+            tables: bin_def.tables,
+            scalars: bin_def.scalars,
+            selected: Vec::new(),
+            menu: None,
+        }
+    }
+
+    pub fn name(&self, item: NavItem) -> &str {
+        match item {
+            NavItem::Table(i) => &self.tables[i].name,
+            NavItem::Scalar(i) => &self.scalars[i].name,
+        }
+    }
+
+    /// Start of the stored data, the map data for tables.
+    pub fn address(&self, item: NavItem) -> Option<u64> {
+        match item {
+            NavItem::Table(i) => self.tables[i].z.data.element_address(0),
+            NavItem::Scalar(i) => Some(self.scalars[i].address),
+        }
+    }
+
+    /// Definition of the item as it was parsed, for checking how the XDF was understood.
+    pub fn definition(&self, item: NavItem) -> String {
+        match item {
+            NavItem::Table(i) => format!("{:#?}", self.tables[i]),
+            NavItem::Scalar(i) => format!("{:#?}", self.scalars[i]),
+        }
+    }
+
+    /// How the stored values of the item are decoded, per axis for tables.
+    pub fn storage(&self, item: NavItem) -> String {
+        match item {
+            NavItem::Table(i) => {
+                let table = &self.tables[i];
+                [("x", &table.x), ("y", &table.y), ("z", &table.z)]
+                    .into_iter()
+                    .map(|(name, axis)| {
+                        let storage = axis
+                            .storage()
+                            .unwrap_or("User defined, not stored in the binary".to_string());
+                        format!("{name} axis\n{storage}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            }
+            NavItem::Scalar(i) => self.scalars[i].storage(),
+        }
+    }
+
+    pub fn toggle_selected(&mut self, item: NavItem) {
+        if let Some(i) = self.selected.iter().position(|s| *s == item) {
+            self.selected.remove(i);
+        } else {
+            self.selected.push(item);
+        }
+    }
+
+    pub fn open_kind(&self, item: NavItem) -> Open {
+        match item {
+            NavItem::Table(i) => Open::Table(self.tables[i].clone()),
+            NavItem::Scalar(i) => Open::Scalar(self.scalars[i].clone()),
+        }
+    }
+
+    /// Clear the selection, returning what should be opened for it.
+    pub fn take_selected(&mut self) -> Vec<Open> {
+        std::mem::take(&mut self.selected)
+            .into_iter()
+            .map(|item| self.open_kind(item))
+            .collect()
+    }
+
+    fn item_button(&self, name: &str, item: NavItem) -> Element<Message> {
+        let button = widget::button(text(name.to_string()))
+            .on_press(Message::NavClick(item))
+            .width(Length::Fill)
+            .style(if self.selected.contains(&item) {
+                selected_button_color
+            } else {
+                button_color
+            });
+        let button = mouse_area(button).on_right_press(Message::NavMenu(Some(item)));
+
+        if self.menu != Some(item) {
+            return button.into();
+        }
+        let actions = NavAction::ALL.into_iter().map(|action| {
+            widget::button(text(action.label()).size(14))
+                .style(widget::button::secondary)
+                .padding(3)
+                .width(Length::Fill)
+                .on_press(Message::NavAction(item, action))
+                .into()
+        });
+        let close = widget::button(text("Close menu").size(14))
+            .style(widget::button::text)
+            .padding(3)
+            .on_press(Message::NavMenu(None));
+        column![
+            button,
+            column(actions).push(close).spacing(2).padding([0, 10])
+        ]
+        .spacing(2)
+        .into()
+    }
+
+    /// Nav docked beside the pane grid, collapsed to just a button to expand it again.
+    pub fn sidebar(
+        &self,
+        collapsed: bool,
+        separators: bool,
+        placement: PanePlacement,
+    ) -> Element<Message> {
+        if collapsed {
+            return container(
+                widget::button(text(">>").size(14))
+                    .style(widget::button::secondary)
+                    .padding(3)
+                    .on_press(Message::ToggleNav),
+            )
+            .padding(10)
+            .into();
+        }
+
+        container(column![
+            row![
+                text(self.title.clone()).width(Length::Fill),
+                widget::button(text("<<").size(14))
+                    .style(widget::button::secondary)
+                    .padding(3)
+                    .on_press(Message::ToggleNav),
+            ]
+            .spacing(5),
+            widget::checkbox("Thousands separators", separators)
+                .on_toggle(|_| Message::ToggleSeparators),
+            widget::pick_list(PanePlacement::ALL, Some(placement), Message::SetPlacement)
+                .width(Length::Fill),
+            widget::button(text("Write all"))
+                .style(widget::button::secondary)
+                .on_press(Message::WriteAll)
+                .width(Length::Fill),
+            widget::button(text("Reload binary"))
+                .style(widget::button::secondary)
+                .on_press(Message::ReloadBinary)
+                .width(Length::Fill),
+            widget::button(text("Load reference"))
+                .style(widget::button::secondary)
+                .on_press(Message::LoadReference)
+                .width(Length::Fill),
+            widget::row![
+                widget::button(text("Export tune"))
+                    .style(widget::button::secondary)
+                    .on_press(Message::ExportTune)
+                    .width(Length::Fill),
+                widget::button(text("Import tune"))
+                    .style(widget::button::secondary)
+                    .on_press(Message::ImportTune)
+                    .width(Length::Fill),
+            ]
+            .spacing(5),
+            widget::button(text("Expression tester"))
+                .style(widget::button::secondary)
+                .on_press(Message::OpenExprTester)
+                .width(Length::Fill),
+            widget::button(text("Probe"))
+                .style(widget::button::secondary)
+                .on_press(Message::OpenProbe)
+                .width(Length::Fill),
+            widget::button(text("Region as table"))
+                .style(widget::button::secondary)
+                .on_press(Message::OpenRegion)
+                .width(Length::Fill),
+            widget::button(text("Verify checksums"))
+                .style(widget::button::secondary)
+                .on_press(Message::VerifyChecksums)
+                .width(Length::Fill),
+            self.view(),
+        ])
+        .width(Length::Fixed(250.0))
+        .padding(10)
+        .into()
+    }
+
     pub fn view(&self) -> Element<Message> {
         let categories = column(self.categories.iter().map(|(index, name)| {
-            let mut column = column![text(name).size(30)];
             let scalars: Vec<Element<Message>> = self
                 .scalars
                 .iter()
-                .filter_map(|s| {
+                .enumerate()
+                .filter_map(|(i, s)| {
                     if s.categories.contains(index) {
-                        Some(Element::from(
-                            widget::button(text(s.name.clone()))
-                                .on_press(Message::Open(Open::Scalar(s.clone())))
-                                .width(Length::Fill)
-                                .style(button_color),
-                        ))
+                        Some(self.item_button(&s.name, NavItem::Scalar(i)))
                     } else {
                         None
                     }
@@ -53,14 +294,10 @@ impl MapNav {
             let tables: Vec<Element<Message>> = self
                 .tables
                 .iter()
-                .filter_map(|t| {
+                .enumerate()
+                .filter_map(|(i, t)| {
                     if t.categories.contains(index) {
-                        Some(Element::from(
-                            widget::button(text(t.name.clone()))
-                                .on_press(Message::Open(Open::Table(t.clone())))
-                                .width(Length::Fill)
-                                .style(button_color),
-                        ))
+                        Some(self.item_button(&t.name, NavItem::Table(i)))
                     } else {
                         None
                     }
@@ -68,6 +305,11 @@ impl MapNav {
                 .collect();
 
             let scalars_empty = scalars.is_empty();
+            let open_group = widget::button(text("Open scalars").size(14))
+                .style(widget::button::secondary)
+                .on_press_maybe((!scalars_empty).then_some(Message::OpenScalarGroup(*index)));
+            let mut column = column![row![text(name).size(30).width(Length::Fill), open_group]
+                .align_y(iced::Alignment::Center)];
 
             if !scalars_empty {
                 if !tables.is_empty() {
@@ -86,6 +328,17 @@ impl MapNav {
             Element::from(column)
         }));
 
-        scrollable(categories).into()
+        let open_selected =
+            widget::button(text(format!("Open selected ({})", self.selected.len())))
+                .on_press_maybe((!self.selected.is_empty()).then_some(Message::OpenSelected))
+                .width(Length::Fill);
+
+        column![
+            text("Ctrl/Shift-click to select several").size(14),
+            open_selected,
+            scrollable(categories)
+        ]
+        .spacing(5)
+        .into()
     }
 }
@@ -1,6 +1,13 @@
+pub mod compare;
 pub mod error;
+pub mod expr_tester;
+pub mod grid_image;
+pub mod histogram;
 pub mod load_file;
 pub mod map_nav;
 pub mod panes;
+pub mod probe;
+pub mod region;
 pub mod scalar;
+pub mod scalar_group;
 pub mod table;
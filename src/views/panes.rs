@@ -6,14 +6,22 @@ use iced::{
     },
     Element,
     Length::Fill,
+    Size,
 };
 
+use std::ops::Range;
+
 use crate::{
-    definitions::{BinaryDefinition, Scalar, Table},
+    definitions::{ranges_overlap, Scalar, Table},
+    project::ChartLayout,
+    settings::PanePlacement,
     FileGuard, Message,
 };
 
-use super::{error::ErrorView, map_nav::MapNav, scalar::ScalarView, table::TableView};
+use super::{
+    compare::TableCompareView, error::ErrorView, expr_tester::ExprTesterView, probe::ProbeView,
+    region::RegionView, scalar::ScalarView, scalar_group::ScalarGroupView, table::TableView,
+};
 
 pub struct Pane {
     is_pinned: bool,
@@ -21,30 +29,72 @@ pub struct Pane {
     title: String,
 }
 impl Pane {
-    pub fn nav(bin_def: BinaryDefinition) -> Self {
+    /// Placeholder shown until a map is opened, replaced by the first pane opened into it
+    pub fn empty() -> Self {
         Self {
-            is_pinned: true,
-            content: PaneContent::Nav(MapNav {
-                categories: bin_def.info.categories,
-                tables: bin_def.tables,
-                scalars: bin_def.scalars,
-            }),
-            title: bin_def.info.name,
+            is_pinned: false,
+            content: PaneContent::Empty,
+            title: "HEXTuner".to_string(),
         }
     }
 
-    pub fn table(table: Table, file: FileGuard, id: usize) -> Self {
+    pub fn table(
+        table: Table,
+        file: FileGuard,
+        id: usize,
+        layout: Option<ChartLayout>,
+    ) -> anyhow::Result<Self> {
+        let title = table.name.clone();
+        let mut view = TableView::new(id, table, file)?;
+        if let Some(layout) = layout {
+            view.set_chart_layout(layout)?;
+        }
+        Ok(Self {
+            is_pinned: false,
+            title,
+            content: PaneContent::Table(view),
+        })
+    }
+    pub fn table_compare(view: TableCompareView) -> Self {
         Self {
             is_pinned: false,
-            title: table.name.clone(),
-            content: PaneContent::Table(TableView::new(id, table, file)),
+            title: format!("{} (compared to reference)", view.table.name),
+            content: PaneContent::TableCompare(view),
         }
     }
-    pub fn scalar(scalar: Scalar, file: FileGuard, id: usize) -> Self {
-        Self {
+    pub fn scalar(scalar: Scalar, file: FileGuard, id: usize) -> anyhow::Result<Self> {
+        Ok(Self {
             is_pinned: false,
             title: scalar.name.clone(),
-            content: PaneContent::Scalar(ScalarView::new(id, scalar, file)),
+            content: PaneContent::Scalar(ScalarView::new(id, scalar, file)?),
+        })
+    }
+    pub fn scalar_group(view: ScalarGroupView) -> Self {
+        Self {
+            is_pinned: false,
+            title: format!("{} (scalars)", view.name),
+            content: PaneContent::ScalarGroup(view),
+        }
+    }
+    pub fn expr_tester(id: usize) -> Self {
+        Self {
+            is_pinned: false,
+            title: "Expression tester".to_string(),
+            content: PaneContent::ExprTester(ExprTesterView::new(id)),
+        }
+    }
+    pub fn probe(file: FileGuard, id: usize) -> Self {
+        Self {
+            is_pinned: false,
+            title: "Probe".to_string(),
+            content: PaneContent::Probe(ProbeView::new(id, file)),
+        }
+    }
+    pub fn region(file: FileGuard, id: usize) -> Self {
+        Self {
+            is_pinned: false,
+            title: "Region as table".to_string(),
+            content: PaneContent::Region(RegionView::new(id, file)),
         }
     }
     pub fn error(error: String) -> Self {
@@ -54,12 +104,115 @@ impl Pane {
             content: PaneContent::Error(ErrorView::new(error)),
         }
     }
+    pub fn definition(name: String, text: String) -> Self {
+        Self {
+            is_pinned: false,
+            title: format!("{name} (definition)"),
+            content: PaneContent::Error(ErrorView::new(text)),
+        }
+    }
+    pub fn summary(title: String, text: String) -> Self {
+        Self {
+            is_pinned: false,
+            title,
+            content: PaneContent::Error(ErrorView::new(text)),
+        }
+    }
+    pub fn warning(warning: String) -> Self {
+        Self {
+            is_pinned: false,
+            title: "Warning".to_string(),
+            content: PaneContent::Error(ErrorView::new(warning)),
+        }
+    }
 }
 pub enum PaneContent {
     Table(TableView),
-    Nav(MapNav),
+    Empty,
     Scalar(ScalarView),
     Error(ErrorView),
+    ExprTester(ExprTesterView),
+    TableCompare(TableCompareView),
+    Probe(ProbeView),
+    Region(RegionView),
+    ScalarGroup(ScalarGroupView),
+}
+
+impl PaneContent {
+    /// Whether the pane has edits that have not been written to the binary
+    pub fn is_dirty(&self) -> bool {
+        match self {
+            PaneContent::Table(t) => t.is_dirty(),
+            PaneContent::Scalar(s) => s.is_dirty(),
+            PaneContent::ScalarGroup(g) => g.is_dirty(),
+            PaneContent::Empty
+            | PaneContent::Error(_)
+            | PaneContent::ExprTester(_)
+            | PaneContent::TableCompare(_)
+            | PaneContent::Probe(_)
+            | PaneContent::Region(_) => false,
+        }
+    }
+
+    /// Whether the pane shows values stored in `range` of the binary, probes are left out
+    /// as they are read every time they are drawn.
+    pub fn reads(&self, range: &Range<u64>) -> bool {
+        match self {
+            PaneContent::Table(t) => t.table.overlaps(range),
+            PaneContent::TableCompare(c) => c.table.overlaps(range),
+            PaneContent::Scalar(s) => ranges_overlap(&s.scalar.byte_range(), range),
+            PaneContent::ScalarGroup(g) => g.reads(range),
+            PaneContent::Probe(p) => p
+                .byte_range()
+                .is_some_and(|bytes| ranges_overlap(&bytes, range)),
+            PaneContent::Empty
+            | PaneContent::Error(_)
+            | PaneContent::ExprTester(_)
+            | PaneContent::Region(_) => false,
+        }
+    }
+
+    /// Re-read values after another pane wrote bytes this one reads, keeping unwritten edits
+    pub fn refresh(&mut self) -> anyhow::Result<()> {
+        match self {
+            PaneContent::Table(t) => t.refresh_unedited()?,
+            PaneContent::Scalar(s) => s.refresh()?,
+            PaneContent::ScalarGroup(g) => g.refresh()?,
+            _ => self.reload()?,
+        }
+        Ok(())
+    }
+
+    /// Re-read values from the binary, discarding unwritten edits
+    pub fn reload(&mut self) -> anyhow::Result<()> {
+        match self {
+            PaneContent::Table(t) => t.reload()?,
+            PaneContent::Scalar(s) => s.reload()?,
+            PaneContent::ScalarGroup(g) => g.reload()?,
+            PaneContent::TableCompare(c) => c.reload()?,
+            PaneContent::Probe(p) => p.reload(),
+            // Regions only read the length of the binary
+            PaneContent::Empty
+            | PaneContent::Error(_)
+            | PaneContent::ExprTester(_)
+            | PaneContent::Region(_) => {}
+        }
+        Ok(())
+    }
+
+    pub fn set_separators(&mut self, separators: bool) {
+        match self {
+            PaneContent::Table(t) => t.set_separators(separators),
+            PaneContent::Scalar(s) => s.separators = separators,
+            PaneContent::ScalarGroup(g) => g.set_separators(separators),
+            PaneContent::TableCompare(c) => c.separators = separators,
+            PaneContent::Empty
+            | PaneContent::Error(_)
+            | PaneContent::ExprTester(_)
+            | PaneContent::Probe(_)
+            | PaneContent::Region(_) => {}
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -70,6 +223,14 @@ pub(crate) enum PaneAction {
     Resized(pane_grid::ResizeEvent),
     Clicked(pane_grid::Pane),
     Restore,
+    /// Maximize the focused pane, or restore if a pane is already maximized
+    ToggleMaximize,
+    /// Focus the next pane in reading order, or the previous one
+    Cycle {
+        backward: bool,
+    },
+    /// Focus the pane at this position in reading order, counting from 0
+    FocusNth(usize),
 }
 
 impl Into<Message> for PaneAction {
@@ -91,29 +252,207 @@ pub fn update_panes(app: &mut crate::App, action: PaneAction) {
         PaneAction::Resized(ResizeEvent { split, ratio }) => app.panes.resize(split, ratio),
         PaneAction::Clicked(pane) => app.focus = Some(pane),
         PaneAction::Restore => app.panes.restore(),
+        PaneAction::ToggleMaximize => {
+            if app.panes.maximized().is_some() {
+                app.panes.restore();
+            } else if let Some(pane) = app.focus {
+                app.panes.maximize(pane);
+            }
+        }
+        PaneAction::Cycle { backward } => {
+            let order = reading_order(app);
+            let current = app.focus.and_then(|f| order.iter().position(|p| *p == f));
+            let next = match (current, backward) {
+                (None, _) => 0,
+                (Some(i), false) => (i + 1) % order.len(),
+                (Some(i), true) => (i + order.len() - 1) % order.len(),
+            };
+            focus_pane(app, order[next]);
+        }
+        PaneAction::FocusNth(n) => {
+            if let Some(&pane) = reading_order(app).get(n) {
+                focus_pane(app, pane);
+            }
+        }
     }
 }
 
+/// Open panes from the top left, row by row. Based on the layout so the order only changes
+/// when panes are moved, opened or closed.
+fn reading_order(app: &crate::App) -> Vec<pane_grid::Pane> {
+    let mut regions: Vec<_> = app
+        .panes
+        .layout()
+        .pane_regions(0.0, Size::new(16.0, 9.0))
+        .into_iter()
+        .collect();
+    regions.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+    regions.into_iter().map(|(pane, _)| pane).collect()
+}
+
+/// Focus a pane, a maximized pane is swapped for it so the focused pane is always visible.
+fn focus_pane(app: &mut crate::App, pane: pane_grid::Pane) {
+    if app.panes.maximized().is_some() {
+        app.panes.maximize(pane);
+    }
+    app.focus = Some(pane);
+}
+
 pub fn open(app: &mut crate::App, kind: crate::Open, binary: FileGuard) -> Option<pane_grid::Pane> {
+    let (target, axis) = match app.settings.placement {
+        PanePlacement::SplitFocus => (
+            app.focus
+                .unwrap_or(app.panes.iter().last().unwrap().0.clone()),
+            pane_grid::Axis::Horizontal,
+        ),
+        PanePlacement::Grid => roomiest_pane(app),
+    };
+    open_at(app, kind, binary, target, axis)
+}
+
+/// Pane with the largest area and the axis splitting its longer side. The window size is not
+/// known here so a 16:9 window is assumed, which is close enough to choose between panes.
+fn roomiest_pane(app: &crate::App) -> (pane_grid::Pane, pane_grid::Axis) {
+    let regions = app.panes.layout().pane_regions(0.0, Size::new(16.0, 9.0));
+    let (pane, region) = regions
+        .into_iter()
+        .reduce(|best, next| {
+            if next.1.area() > best.1.area() {
+                next
+            } else {
+                best
+            }
+        })
+        .unwrap();
+    let axis = if region.width >= region.height {
+        pane_grid::Axis::Vertical
+    } else {
+        pane_grid::Axis::Horizontal
+    };
+    (pane, axis)
+}
+
+/// Same as [`open`] but splits the focused pane side by side.
+pub fn open_beside(
+    app: &mut crate::App,
+    kind: crate::Open,
+    binary: FileGuard,
+) -> Option<pane_grid::Pane> {
+    let target = app.focus.unwrap_or(*app.panes.iter().last().unwrap().0);
+    open_at(app, kind, binary, target, pane_grid::Axis::Vertical)
+}
+
+/// Open several panes at once, each one splitting the previous so they end up tiled.
+pub fn open_tiled(app: &mut crate::App, kinds: Vec<crate::Open>, binary: FileGuard) {
+    let mut target = app.focus.unwrap_or(*app.panes.iter().last().unwrap().0);
+    let mut axis = pane_grid::Axis::Horizontal;
+    for kind in kinds {
+        if let Some(pane) = open_at(app, kind, binary.clone(), target, axis) {
+            target = pane;
+        }
+        axis = match axis {
+            pane_grid::Axis::Horizontal => pane_grid::Axis::Vertical,
+            pane_grid::Axis::Vertical => pane_grid::Axis::Horizontal,
+        };
+    }
+    app.focus = Some(target);
+}
+
+fn open_at(
+    app: &mut crate::App,
+    kind: crate::Open,
+    binary: FileGuard,
+    target: pane_grid::Pane,
+    axis: pane_grid::Axis,
+) -> Option<pane_grid::Pane> {
     let id = app.panes_created;
     app.panes_created += 1;
 
-    if let Some((pane, _)) = app.panes.split(
-        pane_grid::Axis::Horizontal,
-        app.focus
-            .unwrap_or(app.panes.iter().last().unwrap().0.clone()),
-        match kind {
-            // crate::Open::Nav(binary_definition) => Pane::nav(binary_definition),
-            crate::Open::Error(error) => Pane::error(error),
-            crate::Open::Table(table) => Pane::table(table, binary, id),
-            crate::Open::Scalar(scalar) => Pane::scalar(scalar, binary, id),
+    let mut new_pane = match kind {
+        crate::Open::Error(error) => Pane::error(error),
+        crate::Open::Warning(warning) => Pane::warning(warning),
+        crate::Open::Table(mut table) => match table.check_axes() {
+            Ok(()) => {
+                let decimals = app.project.decimals.get(&table.name).copied();
+                table.z.set_decimals(decimals);
+                let layout = app.project.charts.get(&table.name).copied();
+                match Pane::table(table, binary, id, layout) {
+                    Ok(pane) => pane,
+                    Err(e) => Pane::error(format!("{e:#}")),
+                }
+            }
+            Err(error) => Pane::error(error),
         },
-    ) {
-        app.pane_id_map.insert(id, pane);
-        Some(pane)
-    } else {
-        None
+        crate::Open::Scalar(mut scalar) => {
+            scalar.decimals = app.project.decimals.get(&scalar.name).copied();
+            // Usually an address past the end of the binary
+            match Pane::scalar(scalar, binary, id) {
+                Ok(pane) => pane,
+                Err(e) => Pane::error(format!("{e:#}")),
+            }
+        }
+        crate::Open::ScalarGroup(category) => {
+            let scalars = app
+                .nav
+                .scalars
+                .iter()
+                .filter(|s| s.categories.contains(&category))
+                .map(|s| Scalar {
+                    decimals: app.project.decimals.get(&s.name).copied(),
+                    ..s.clone()
+                })
+                .collect();
+            let name = app
+                .nav
+                .categories
+                .get(&category)
+                .cloned()
+                .unwrap_or_default();
+            Pane::scalar_group(ScalarGroupView::new(id, name, scalars, binary))
+        }
+        crate::Open::ExprTester => Pane::expr_tester(id),
+        crate::Open::Probe => Pane::probe(binary, id),
+        crate::Open::Region => Pane::region(binary, id),
+        crate::Open::TableCompare(table) => match &app.reference {
+            Some(reference) => match TableCompareView::new(id, table, binary, reference.clone()) {
+                Ok(view) => Pane::table_compare(view),
+                Err(e) => Pane::error(format!("Could not read the table from both binaries: {e}")),
+            },
+            None => Pane::error("Load a reference binary from the sidebar first".to_string()),
+        },
+        crate::Open::Definition { name, text } => Pane::definition(name, text),
+        crate::Open::Summary { title, text } => Pane::summary(title, text),
+    };
+    new_pane
+        .content
+        .set_separators(app.settings.thousands_separators);
+    if let PaneContent::Table(table_view) = &mut new_pane.content {
+        table_view.chart.set_colormap(app.settings.colormap);
+        table_view.axes_locked = app.settings.lock_axes;
+        table_view.notes = app.project.notes.for_map(&table_view.table.name);
+    }
+    if let PaneContent::Scalar(scalar_view) = &mut new_pane.content {
+        scalar_view.presets = app.project.presets.get(&scalar_view.scalar.name).to_vec();
     }
+
+    let pane = match app.panes.get_mut(target) {
+        Some(existing) if matches!(existing.content, PaneContent::Empty) => {
+            *existing = new_pane;
+            target
+        }
+        _ => app.panes.split(axis, target, new_pane)?.0,
+    };
+    app.pane_id_map.insert(id, pane);
+
+    if let PaneContent::Scalar(ScalarView {
+        error: Some(error), ..
+    }) = &app.panes.get(pane)?.content
+    {
+        let error = crate::Open::Error(error.clone());
+        let binary = app.binary.clone();
+        open_at(app, error, binary, pane, axis);
+    }
+    Some(pane)
 }
 
 pub fn view_grid<'a>(app: &crate::App) -> Element<Message> {
@@ -123,7 +462,11 @@ pub fn view_grid<'a>(app: &crate::App) -> Element<Message> {
     let pane_grid = PaneGrid::new(&app.panes, |id, pane, is_maximized| {
         let is_focused = focus == Some(id);
 
-        let title = text(pane.title.clone());
+        let title = text(if pane.content.is_dirty() {
+            format!("{} *", pane.title)
+        } else {
+            pane.title.clone()
+        });
 
         let title_bar = pane_grid::TitleBar::new(title)
             .controls(pane_grid::Controls::dynamic(
@@ -147,9 +490,14 @@ pub fn view_grid<'a>(app: &crate::App) -> Element<Message> {
         pane_grid::Content::new(iced::widget::responsive(|_size| {
             container(match &pane.content {
                 PaneContent::Table(v) => v.view(),
-                PaneContent::Nav(m) => m.view(),
+                PaneContent::Empty => text("Open a map from the sidebar").into(),
                 PaneContent::Scalar(s) => s.view(),
+                PaneContent::ScalarGroup(g) => g.view(),
                 PaneContent::Error(e) => e.view(),
+                PaneContent::ExprTester(t) => t.view(),
+                PaneContent::TableCompare(c) => c.view(),
+                PaneContent::Probe(p) => p.view(),
+                PaneContent::Region(r) => r.view(),
             })
             .clip(true)
             .into()
@@ -210,7 +558,7 @@ fn view_controls<'a>(
     row.push(close).into()
 }
 
-mod style {
+pub(super) mod style {
     use iced::widget::container;
     use iced::{Border, Theme};
 
@@ -0,0 +1,202 @@
+use std::{
+    fmt::Display,
+    io::{Read, Seek},
+    ops::Range,
+};
+
+use iced::{
+    widget::{column, pick_list, row, text, text_input},
+    Element,
+};
+
+use crate::{
+    definitions::{bytes_to_u32, format_raw_hex},
+    eval, FileGuard, Message,
+};
+
+/// Byte order of the probed value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endian {
+    pub const ALL: [Endian; 2] = [Self::Little, Self::Big];
+}
+
+impl Display for Endian {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Little => "Little endian",
+            Self::Big => "Big endian",
+        })
+    }
+}
+
+/// Text input of the probe being edited
+#[derive(Debug, Clone, Copy)]
+pub enum ProbeField {
+    Address,
+    Expression,
+}
+
+/// Parse an address, `0x` prefixed values are hex and anything else is decimal.
+pub fn parse_address(value: &str) -> Option<u64> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Reads a value at any address with a layout and expression picked on the spot, nothing
+/// is kept once the pane is closed
+#[derive(Debug)]
+pub struct ProbeView {
+    pane_id: usize,
+    address: String,
+    /// Bytes to read, 1 to 4
+    size: usize,
+    endian: Endian,
+    expression: String,
+    source: FileGuard,
+    /// Value read with the current inputs or why it could not be, read again when they change
+    result: String,
+}
+
+impl ProbeView {
+    pub fn new(pane_id: usize, source: FileGuard) -> Self {
+        let mut view = Self {
+            pane_id,
+            address: "0x0".to_string(),
+            size: 2,
+            endian: Endian::default(),
+            expression: "X".to_string(),
+            source,
+            result: String::new(),
+        };
+        view.reload();
+        view
+    }
+
+    pub fn edit(&mut self, field: ProbeField, value: String) {
+        match field {
+            ProbeField::Address => self.address = value,
+            ProbeField::Expression => self.expression = value,
+        }
+        self.reload();
+    }
+
+    pub fn set_size(&mut self, size: usize) {
+        self.size = size;
+        self.reload();
+    }
+
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+        self.reload();
+    }
+
+    /// Bytes read at the current address, `None` while it is not a valid number.
+    pub fn byte_range(&self) -> Option<Range<u64>> {
+        let address = parse_address(&self.address)?;
+        Some(address..address.saturating_add(self.size as u64))
+    }
+
+    /// Read the value again, after the inputs change or another pane wrote the bytes.
+    pub fn reload(&mut self) {
+        self.result = match parse_address(&self.address) {
+            None => "Address must be a decimal or 0x prefixed hex number".to_string(),
+            Some(address) => match self.read_raw(address) {
+                Err(e) => format!("Could not read {} bytes at 0x{address:X}: {e}", self.size),
+                Ok(raw) => {
+                    let raw_text = format!("Raw {} ({raw})", format_raw_hex(raw, self.size));
+                    match eval::parse(&self.expression) {
+                        Ok(expression) => format!("{raw_text} -> {}", expression.eval(raw)),
+                        Err(e) => format!("{raw_text}, invalid expression: {e}"),
+                    }
+                }
+            },
+        };
+    }
+
+    fn read_raw(&self, address: u64) -> std::io::Result<u32> {
+        let mut source = self.source.clone();
+        source.seek(std::io::SeekFrom::Start(address))?;
+        let mut buf = vec![0u8; self.size];
+        source.read_exact(&mut buf)?;
+        if self.endian == Endian::Big {
+            buf.reverse();
+        }
+        Ok(bytes_to_u32(&buf))
+    }
+
+    fn input(&self, label: &str, value: &str, field: ProbeField) -> Element<Message> {
+        let pane = self.pane_id;
+        row![
+            text(label.to_string()).width(100),
+            text_input(label, value).on_input(move |value| Message::EditProbe {
+                pane,
+                field,
+                value
+            }),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let pane = self.pane_id;
+
+        column![
+            self.input("Address", &self.address, ProbeField::Address),
+            row![
+                text("Size").width(100),
+                pick_list([1, 2, 3, 4], Some(self.size), move |size| {
+                    Message::SetProbeSize { pane, size }
+                }),
+                pick_list(Endian::ALL, Some(self.endian), move |endian| {
+                    Message::SetProbeEndian { pane, endian }
+                }),
+            ]
+            .spacing(5),
+            self.input("Expression", &self.expression, ProbeField::Expression),
+            text(&self.result),
+        ]
+        .spacing(5)
+        .padding(5)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn probe_reads_once_per_input_change() {
+        let mut source = FileGuard::from(vec![0x12, 0x34, 0x56, 0x78]);
+        let mut view = ProbeView::new(0, source.clone());
+        assert_eq!(view.result, "Raw 0x3412 (13330) -> 13330");
+        view.set_endian(Endian::Big);
+        assert!(view.result.starts_with("Raw 0x1234"));
+        view.edit(ProbeField::Expression, "X/2".to_string());
+        assert!(view.result.ends_with("-> 2330"));
+        source.rewind().unwrap();
+        source.write_all(&[0, 0]).unwrap();
+        assert!(view.result.ends_with("-> 2330"));
+        view.reload();
+        assert!(view.result.ends_with("-> 0"));
+        view.set_size(4);
+        assert_eq!(view.byte_range(), Some(0..4));
+        view.edit(ProbeField::Address, "0x2".to_string());
+        assert!(view.result.starts_with("Could not read 4 bytes at 0x2"));
+    }
+}
@@ -0,0 +1,235 @@
+use std::io::{Seek, SeekFrom};
+
+use iced::{
+    widget::{button, column, pick_list, row, text, text_input},
+    Element,
+};
+
+use super::probe::{parse_address, Endian};
+use crate::{
+    definitions::{Axis, AxisData, DisplayScale, Table},
+    eval, FileGuard, Message,
+};
+
+/// Text input of the region being edited
+#[derive(Debug, Clone, Copy)]
+pub enum RegionField {
+    Address,
+    Columns,
+    Rows,
+    Expression,
+}
+
+/// Lays a grid over any bytes of the binary so a suspected map can be viewed as a table
+/// before it has a definition
+#[derive(Debug)]
+pub struct RegionView {
+    pane_id: usize,
+    address: String,
+    columns: String,
+    rows: String,
+    /// Bytes of one element, 1 to 4
+    size: usize,
+    endian: Endian,
+    expression: String,
+    source: FileGuard,
+    /// Table over the inputs, worked out again when one of them changes
+    table: Result<Table, String>,
+}
+
+/// Axis numbering the rows or columns from 0
+fn index_axis(len: usize) -> Axis {
+    Axis {
+        units: String::new(),
+        data: AxisData::User((0..len).map(|i| i as f64).collect()),
+        names: Vec::new(),
+    }
+}
+
+impl RegionView {
+    pub fn new(pane_id: usize, source: FileGuard) -> Self {
+        let mut view = Self {
+            pane_id,
+            address: "0x0".to_string(),
+            columns: "8".to_string(),
+            rows: "8".to_string(),
+            size: 1,
+            endian: Endian::default(),
+            expression: "X".to_string(),
+            source,
+            table: Err(String::new()),
+        };
+        view.table = view.read_table();
+        view
+    }
+
+    pub fn edit(&mut self, field: RegionField, value: String) {
+        match field {
+            RegionField::Address => self.address = value,
+            RegionField::Columns => self.columns = value,
+            RegionField::Rows => self.rows = value,
+            RegionField::Expression => self.expression = value,
+        }
+        self.table = self.read_table();
+    }
+
+    pub fn set_size(&mut self, size: usize) {
+        self.size = size;
+        self.table = self.read_table();
+    }
+
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+        self.table = self.read_table();
+    }
+
+    /// Table over the region as entered, not added to the definition. Fails with the reason
+    /// when an input is invalid or the region runs past the end of the binary.
+    pub fn table(&self) -> Result<Table, String> {
+        self.table.clone()
+    }
+
+    fn read_table(&self) -> Result<Table, String> {
+        let address = parse_address(&self.address)
+            .ok_or("Address must be a decimal or 0x prefixed hex number")?;
+        let count = |value: &str, name: &str| {
+            value
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or(format!("{name} must be a whole number above 0"))
+        };
+        let columns = count(&self.columns, "Columns")?;
+        let rows = count(&self.rows, "Rows")?;
+        eval::parse(&self.expression).map_err(|e| format!("Invalid expression: {e}"))?;
+
+        let end = columns
+            .checked_mul(rows)
+            .and_then(|count| count.checked_mul(self.size))
+            .and_then(|len| address.checked_add(len as u64))
+            .ok_or(format!(
+                "Region of {rows}x{columns} at 0x{address:X} ends past the largest address"
+            ))?;
+        let len = self
+            .source
+            .clone()
+            .seek(SeekFrom::End(0))
+            .map_err(|e| e.to_string())?;
+        if end > len {
+            return Err(format!(
+                "Region 0x{address:X}..0x{end:X} runs past the end of the binary at 0x{len:X}"
+            ));
+        }
+
+        Ok(Table {
+            name: format!("Region 0x{address:X} ({rows}x{columns})"),
+            description: String::new(),
+            x: index_axis(columns),
+            y: index_axis(rows),
+            z: Axis {
+                units: String::new(),
+                data: AxisData::Binary {
+                    address,
+                    element_size: self.size,
+                    count: columns * rows,
+                    expression: self.expression.clone(),
+                    row_stride: None,
+                    big_endian: self.endian == Endian::Big,
+                    signed: false,
+                    display: DisplayScale::default(),
+                    flag: false,
+                    decimals: None,
+                    type_flags: None,
+                },
+                names: Vec::new(),
+            },
+            categories: Vec::new(),
+        })
+    }
+
+    fn input(&self, label: &str, value: &str, field: RegionField) -> Element<Message> {
+        let pane = self.pane_id;
+        row![
+            text(label.to_string()).width(100),
+            text_input(label, value).on_input(move |value| Message::EditRegion {
+                pane,
+                field,
+                value
+            }),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let pane = self.pane_id;
+        let status = match &self.table {
+            Ok(table) => format!(
+                "{} bytes, edits to the table are written to the binary",
+                table.z.byte_range().map_or(0, |r| r.end - r.start)
+            ),
+            Err(e) => e.clone(),
+        };
+
+        column![
+            self.input("Address", &self.address, RegionField::Address),
+            self.input("Columns", &self.columns, RegionField::Columns),
+            self.input("Rows", &self.rows, RegionField::Rows),
+            row![
+                text("Size").width(100),
+                pick_list([1, 2, 3, 4], Some(self.size), move |size| {
+                    Message::SetRegionSize { pane, size }
+                }),
+                pick_list(Endian::ALL, Some(self.endian), move |endian| {
+                    Message::SetRegionEndian { pane, endian }
+                }),
+            ]
+            .spacing(5),
+            self.input("Expression", &self.expression, RegionField::Expression),
+            text(status),
+            button("Open as table").on_press_maybe(
+                self.table
+                    .is_ok()
+                    .then_some(Message::OpenRegionTable { pane }),
+            ),
+        ]
+        .spacing(5)
+        .padding(5)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_past_largest_address() {
+        let mut view = RegionView::new(0, FileGuard::from(vec![0u8; 64]));
+        assert!(view.table().is_ok());
+        view.edit(RegionField::Address, "0xFFFFFFFFFFFFFFF0".to_string());
+        assert!(view.table().unwrap_err().contains("largest address"));
+        view.edit(RegionField::Address, "0x0".to_string());
+        view.edit(RegionField::Rows, usize::MAX.to_string());
+        assert!(view.table().unwrap_err().contains("largest address"));
+    }
+
+    #[test]
+    fn region_follows_inputs() {
+        let mut view = RegionView::new(0, FileGuard::from(vec![0u8; 64]));
+        view.set_size(2);
+        assert!(view.table().unwrap_err().contains("past the end"));
+        view.edit(RegionField::Rows, "4".to_string());
+        let table = view.table().unwrap();
+        assert_eq!(table.z.byte_range(), Some(0..64));
+        view.set_endian(Endian::Big);
+        assert!(matches!(
+            view.table().unwrap().z.data,
+            AxisData::Binary {
+                big_endian: true,
+                ..
+            }
+        ));
+    }
+}
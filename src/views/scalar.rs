@@ -1,35 +1,643 @@
-use iced::{widget::text_input, Element};
+use anyhow::{bail, Context};
+use iced::{
+    widget::{
+        button, checkbox, column, container, pick_list, row, scrollable, slider, text, text_input,
+        tooltip,
+    },
+    Element, Length,
+};
 
-use crate::{definitions::Scalar, FileGuard, Message};
+use super::table::{differs, Decimals};
+use crate::{
+    definitions::{
+        bit, format_raw_hex, group_thousands, is_hex_entry, parse_raw, strip_thousands, with_bit,
+        Scalar,
+    },
+    project::Preset,
+    FileGuard, Message,
+};
 
 #[derive(Debug)]
 pub struct ScalarView {
     pane_id: usize,
     pub scalar: Scalar,
     pub value: String,
+    /// Value as it was read from or last written to the binary
+    pub baseline: String,
     pub source: FileGuard,
+    /// Display the value with thousands separators
+    pub separators: bool,
+    /// Show and take the stored integer, written without going through the expression
+    pub raw: bool,
+    /// Why the stored value could not be converted, writing is disabled when set
+    pub error: Option<String>,
+    /// Value before typing started, restored by Escape
+    pre_edit: Option<String>,
+    /// What was typed when the last write stored a different value, cleared by the next edit
+    rounded_from: Option<String>,
+    /// Presets of this scalar from the project file, shown as buttons
+    pub presets: Vec<Preset>,
+    /// Name typed for the next preset
+    pub preset_name: String,
 }
 
 impl ScalarView {
-    pub fn new(pane_id: usize, scalar: Scalar, mut source: FileGuard) -> Self {
-        let value = scalar.read(&mut source).unwrap().to_string();
+    pub fn new(pane_id: usize, scalar: Scalar, mut source: FileGuard) -> anyhow::Result<Self> {
+        let context = || {
+            format!(
+                "Could not read {} ({} byte(s) at 0x{:X})",
+                scalar.name, scalar.size, scalar.address
+            )
+        };
+        let raw = scalar.read_raw(&mut source).with_context(context)?;
+        let read = scalar.read(&mut source).with_context(context)?;
 
-        Self {
+        // Usually a division by zero in the expression, writing it back would go wrong
+        let (value, error) = if read.is_finite() {
+            (scalar.format(read), None)
+        } else {
+            (
+                String::new(),
+                Some(format!(
+                    "{} converts to {read} from raw value {raw}, check its expression \"{}\" for a division by zero",
+                    scalar.name, scalar.expression
+                )),
+            )
+        };
+
+        Ok(Self {
             pane_id,
             scalar,
+            baseline: value.clone(),
             value,
             source,
+            separators: false,
+            raw: false,
+            error,
+            pre_edit: None,
+            rounded_from: None,
+            presets: Vec::new(),
+            preset_name: String::new(),
+        })
+    }
+
+    pub fn edit(&mut self, value: String) {
+        self.rounded_from = None;
+        self.pre_edit.get_or_insert_with(|| self.value.clone());
+        self.value = if self.separators && !self.raw {
+            strip_thousands(&value)
+        } else {
+            value
+        };
+    }
+
+    /// Restore the value from before typing started.
+    pub fn cancel_edit(&mut self) {
+        if let Some(value) = self.pre_edit.take() {
+            self.value = value;
+        }
+    }
+
+    /// Read the value from the binary again, discarding any edit.
+    pub fn reload(&mut self) -> anyhow::Result<()> {
+        let (separators, raw) = (self.separators, self.raw);
+        let presets = std::mem::take(&mut self.presets);
+        let preset_name = std::mem::take(&mut self.preset_name);
+        *self = Self::new(self.pane_id, self.scalar.clone(), self.source.clone())?;
+        self.separators = separators;
+        self.raw = raw;
+        self.presets = presets;
+        self.preset_name = preset_name;
+        if raw {
+            let stored = self.scalar.read_raw(&mut self.source)?;
+            self.value = format_raw_hex(stored, self.scalar.size);
+            self.baseline = self.value.clone();
+        }
+        Ok(())
+    }
+
+    /// Switch between the converted value and the stored integer. An edit is converted to
+    /// the other form when it can be, otherwise it is kept as typed.
+    pub fn toggle_raw(&mut self) -> anyhow::Result<()> {
+        let dirty = self.is_dirty();
+        let (value, pre_edit) = (self.value.clone(), self.pre_edit.take());
+        self.raw = !self.raw;
+        self.reload()?;
+        if dirty {
+            self.value = self.convert(&value).unwrap_or(value);
+        }
+        self.pre_edit = pre_edit.map(|pre_edit| self.convert(&pre_edit).unwrap_or(pre_edit));
+        Ok(())
+    }
+
+    /// Text typed in the other mode converted to the current one
+    fn convert(&self, value: &str) -> Option<String> {
+        if self.raw {
+            let value = value.trim().parse::<f64>().ok().filter(|v| v.is_finite())?;
+            Some(format_raw_hex(
+                self.scalar.to_raw(value).ok()?,
+                self.scalar.size,
+            ))
+        } else {
+            let raw = parse_raw(value, self.scalar.size)?;
+            Some(self.scalar.format(self.scalar.from_raw(raw)))
+        }
+    }
+
+    /// Move the value `steps` stored integers up or down, so every value passed through can be
+    /// stored exactly. Does nothing while the value is not a number.
+    pub fn step(&mut self, steps: i64) {
+        let Some(raw) = self.current_raw() else {
+            return;
+        };
+        self.edit_raw(self.scalar.offset_raw(raw, steps));
+    }
+
+    /// Flip one bit of the stored integer, leaving the others as they are. Does nothing while
+    /// the value is not a number.
+    pub fn toggle_bit(&mut self, n: u8) {
+        if let Some(raw) = self.current_raw() {
+            self.edit_raw(with_bit(raw, n, !bit(raw, n)));
+        }
+    }
+
+    /// Edit the value to the one stored as `raw`, shown the way the pane shows values
+    fn edit_raw(&mut self, raw: u32) {
+        let value = if self.raw {
+            format_raw_hex(raw, self.scalar.size)
+        } else {
+            self.scalar.format(self.scalar.from_raw(raw))
+        };
+        self.edit(value);
+    }
+
+    /// Edit the value to a preset, as if it had been typed so it is validated the same way.
+    /// In raw mode it is shown as the stored integer when it fits.
+    pub fn apply_preset(&mut self, index: usize) {
+        let Some(value) = self.presets.get(index).map(|p| p.value) else {
+            return;
+        };
+        match self.scalar.to_raw(value) {
+            Ok(raw) if self.raw && self.scalar.fits(value) => self.edit_raw(raw),
+            _ => self.edit(self.scalar.format(value)),
+        }
+    }
+
+    /// Converted value of a valid entry, stored integers go through the expression
+    pub fn current_value(&self) -> Option<f64> {
+        if !self.is_valid() {
+            return None;
+        }
+        if self.is_raw_entry() {
+            self.raw_value().map(|raw| self.scalar.from_raw(raw))
+        } else {
+            self.value.trim().parse().ok()
+        }
+    }
+
+    /// Stored integer of the value shown
+    pub fn current_raw(&self) -> Option<u32> {
+        if self.is_raw_entry() {
+            self.raw_value()
+        } else {
+            let value = self.value.trim().parse::<f64>().ok()?;
+            value.is_finite().then_some(())?;
+            self.scalar.to_raw(value).ok()
+        }
+    }
+
+    /// Stored integer of a raw mode value, hex or decimal
+    pub fn raw_value(&self) -> Option<u32> {
+        parse_raw(&self.value, self.scalar.size)
+    }
+
+    /// Whether the value is a stored integer, in raw mode or typed with `0x`, which is
+    /// written without going through the expression
+    pub fn is_raw_entry(&self) -> bool {
+        self.raw || is_hex_entry(&self.value)
+    }
+
+    /// Whether the value can be written
+    pub fn is_valid(&self) -> bool {
+        self.invalid_reason().is_none()
+    }
+
+    /// Why the value cannot be written, shown under the input
+    pub fn invalid_reason(&self) -> Option<String> {
+        if self.is_raw_entry() {
+            return self.raw_value().is_none().then(|| {
+                format!(
+                    "Not a hex or decimal integer that fits in {} byte(s)",
+                    self.scalar.size
+                )
+            });
+        }
+        let Ok(value) = self.value.trim().parse::<f64>() else {
+            return Some("Not a number".to_string());
+        };
+        if let Err(e) = self.scalar.to_raw(value) {
+            return Some(format!("{e}, tick Raw hex to enter the stored integer"));
+        }
+        (!self.scalar.fits(value)).then(|| {
+            let (min, max) = self.scalar.value_limits();
+            format!(
+                "Out of range, {} to {} can be stored",
+                self.scalar.format(min),
+                self.scalar.format(max)
+            )
+        })
+    }
+
+    /// Read the value from the binary again after another pane wrote over it, an edit is
+    /// kept and only the baseline it is compared to changes.
+    pub fn refresh(&mut self) -> anyhow::Result<()> {
+        if !self.is_dirty() {
+            return self.reload();
+        }
+        let (value, pre_edit) = (self.value.clone(), self.pre_edit.take());
+        self.reload()?;
+        self.value = value;
+        self.pre_edit = pre_edit;
+        Ok(())
+    }
+
+    /// Read the stored integer as two's complement or not, discarding any edit.
+    pub fn toggle_signed(&mut self) -> anyhow::Result<()> {
+        self.scalar.signed = !self.scalar.signed;
+        self.reload()
+    }
+
+    /// Show the value with a fixed number of decimals, or all of them with `None`. An edit is
+    /// kept, an unedited value is read again at the new precision.
+    pub fn set_decimals(&mut self, decimals: Option<usize>) -> anyhow::Result<()> {
+        self.scalar.decimals = decimals;
+        self.refresh()
+    }
+
+    /// Value has been written, it is read back so any rounding from converting it to the
+    /// stored integer shows. Escape no longer restores the old value.
+    pub fn commit(&mut self) -> anyhow::Result<()> {
+        self.reload()
+    }
+
+    /// Fail with the reason the value cannot be written.
+    pub fn check_writable(&self) -> anyhow::Result<()> {
+        if let Some(error) = &self.error {
+            bail!("Not writing, {error}");
+        }
+        if let Some(reason) = self.invalid_reason() {
+            bail!(reason);
+        }
+        Ok(())
+    }
+
+    /// Write the value to the binary and read it back, stored integers skip the expression.
+    pub fn write(&mut self) -> anyhow::Result<()> {
+        self.check_writable()?;
+        let typed = match (self.is_raw_entry(), self.raw_value()) {
+            (true, Some(raw)) => {
+                self.scalar.write_raw(&mut self.source, raw)?;
+                None
+            }
+            _ => {
+                let value = self.value.trim().parse()?;
+                self.scalar.write(&mut self.source, value)?;
+                Some(value)
+            }
+        };
+        self.commit()?;
+        // Compared at display precision so only visible rounding is mentioned
+        self.rounded_from = typed
+            .map(|value| self.scalar.format(value))
+            .filter(|typed| differs(typed, &self.value));
+        Ok(())
+    }
+
+    /// Discard the edit, showing the value last read or written again.
+    pub fn revert(&mut self) {
+        self.value = self.baseline.clone();
+        self.pre_edit = None;
+    }
+
+    pub fn pane_id(&self) -> usize {
+        self.pane_id
+    }
+
+    /// Whether the value has been edited but not written
+    pub fn is_dirty(&self) -> bool {
+        match (self.raw, self.raw_value()) {
+            (true, Some(raw)) => parse_raw(&self.baseline, self.scalar.size) != Some(raw),
+            (false, Some(raw)) if self.is_raw_entry() => {
+                let baseline = self.baseline.trim().parse().ok();
+                baseline.and_then(|value| self.scalar.to_raw(value).ok()) != Some(raw)
+            }
+            _ => differs(&self.value, &self.baseline),
         }
     }
 
     pub fn view(&self) -> Element<Message> {
-        text_input("", &self.value)
-            .on_input(|value| Message::EditScalar {
-                value,
-                pane: self.pane_id,
-            })
-            .on_submit(Message::WriteScalar { pane: self.pane_id })
+        let value = if self.separators && !self.raw {
+            group_thousands(&self.value)
+        } else {
+            self.value.clone()
+        };
+        let dirty = self.is_dirty();
+        let invalid = self.invalid_reason();
+        let writable = dirty && invalid.is_none();
+        // Same marker as modified table cells
+        let input = text_input("Not a number", &value)
             .width(100)
+            .style(move |theme, status| {
+                let mut style = text_input::default(theme, status);
+                if dirty {
+                    style.border.color = iced::Color::from_rgb(1.0, 0.6, 0.0);
+                    style.border.width = 2.0;
+                }
+                style
+            });
+        // Reading as signed may fix a value that could not be converted, so it is always offered
+        let signed =
+            checkbox("Signed", self.scalar.signed).on_toggle_maybe((!dirty).then_some(|_| {
+                Message::ToggleSigned {
+                    pane: self.pane_id,
+                    axis: None,
+                }
+            }));
+        let units = text(if self.raw {
+            String::new()
+        } else {
+            self.scalar.units.clone()
+        });
+        let step = self
+            .current_raw()
+            .map(|raw| self.scalar.step_at(raw as u64));
+        let step_button = |label: &'static str, up: bool| {
+            let button = button(text(label).size(14))
+                .padding([2, 8])
+                .on_press_maybe(step.map(|_| Message::StepScalar {
+                    pane: self.pane_id,
+                    up,
+                }));
+            tooltip(
+                button,
+                text(match step {
+                    Some(step) => format!("One stored step, {step} here. Shift for 10."),
+                    None => "Value is not a number".to_string(),
+                }),
+                tooltip::Position::Bottom,
+            )
+            .style(container::rounded_box)
+        };
+        let raw = checkbox("Raw hex", self.raw)
+            .on_toggle(|_| Message::ToggleScalarRaw { pane: self.pane_id });
+        let controls = if self.error.is_some() {
+            row![input, units, signed]
+        } else {
+            let decimals = pick_list(
+                Decimals::ALL,
+                Some(Decimals::from(self.scalar.decimals)),
+                |decimals| Message::SetDecimals {
+                    pane: self.pane_id,
+                    decimals,
+                },
+            );
+            row![
+                input.on_input(|value| Message::EditScalar {
+                    value,
+                    pane: self.pane_id,
+                }),
+                step_button("-", false),
+                step_button("+", true),
+                units,
+                button("Write").on_press_maybe(
+                    writable.then_some(Message::WriteScalar { pane: self.pane_id })
+                ),
+                button("Revert")
+                    .on_press_maybe(dirty.then_some(Message::RevertScalar { pane: self.pane_id })),
+                signed,
+                raw,
+                decimals,
+            ]
+        };
+
+        let bytes = if self.scalar.size == 1 {
+            "byte"
+        } else {
+            "bytes"
+        };
+        let storage = text(format!(
+            "0x{:X}, {} {bytes}",
+            self.scalar.address, self.scalar.size
+        ))
+        .size(12)
+        .style(|theme: &iced::Theme| text::Style {
+            color: Some(theme.extended_palette().background.strong.color),
+        });
+        let mut body = column![text(self.scalar.name.clone()).size(18)].spacing(5);
+        // Long descriptions scroll instead of pushing the value out of the pane
+        if !self.scalar.description.is_empty() {
+            body = body.push(
+                container(scrollable(text(self.scalar.description.clone())).width(Length::Fill))
+                    .max_height(120),
+            );
+        }
+        body = body.push(controls.spacing(5).align_y(iced::Alignment::Center));
+        if let (None, Some(invalid)) = (&self.error, invalid) {
+            body = body.push(text(invalid).size(12).style(text::danger));
+        }
+        if self.error.is_none() {
+            body = body.push(self.presets());
+        }
+        if let Some(typed) = &self.rounded_from {
+            body = body.push(
+                text(format!(
+                    "{typed} was stored as {}, the nearest value that fits",
+                    self.value
+                ))
+                .size(12),
+            );
+        }
+        if let (None, Some(labels)) = (&self.error, &self.scalar.bit_labels) {
+            body = body.push(self.bits(labels));
+        }
+        if self.error.is_none() && !self.raw {
+            if let Some(slider) = self.slider() {
+                body = body.push(slider);
+            }
+        }
+        body.push(storage).into()
+    }
+
+    /// A button per preset, setting the value without writing it, and the inputs to add the
+    /// current value as one.
+    fn presets(&self) -> Element<Message> {
+        let pane = self.pane_id;
+        let buttons = self.presets.iter().enumerate().map(|(index, preset)| {
+            row![
+                tooltip(
+                    button(text(preset.name.clone()).size(12))
+                        .padding([2, 6])
+                        .on_press(Message::ApplyScalarPreset { pane, index }),
+                    text(self.scalar.format(preset.value)),
+                    tooltip::Position::Bottom,
+                )
+                .style(container::rounded_box),
+                button(text("x").size(12))
+                    .padding([2, 4])
+                    .style(button::secondary)
+                    .on_press(Message::RemoveScalarPreset { pane, index }),
+            ]
             .into()
+        });
+        let name = self.preset_name.trim();
+        let add = (!name.is_empty() && self.current_value().is_some())
+            .then_some(Message::AddScalarPreset { pane });
+        column![
+            row(buttons).spacing(5).wrap(),
+            row![
+                text_input("Preset name", &self.preset_name)
+                    .on_input(move |name| Message::EditPresetName { pane, name })
+                    .on_submit_maybe(add.clone())
+                    .width(150),
+                button("Add current as preset").on_press_maybe(add),
+            ]
+            .spacing(5),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// A checkbox per bit of a bitfield under the hex of the whole value, bits without a
+    /// label are shown by number so none are hidden.
+    fn bits(&self, labels: &[(u8, String)]) -> Element<Message> {
+        let raw = self.current_raw();
+        let readout = match raw {
+            Some(raw) => format_raw_hex(raw, self.scalar.size),
+            None => "Value is not a number".to_string(),
+        };
+        let pane = self.pane_id;
+        let boxes = (0..(self.scalar.size.min(4) * 8) as u8).map(|n| {
+            let label = labels
+                .iter()
+                .find(|(i, _)| *i == n)
+                .map_or(format!("bit {n}"), |(_, label)| format!("{n}: {label}"));
+            checkbox(label, raw.is_some_and(|raw| bit(raw, n)))
+                .on_toggle_maybe(
+                    raw.is_some()
+                        .then_some(move |_| Message::ToggleScalarBit { pane, bit: n }),
+                )
+                .into()
+        });
+        column![
+            text(readout),
+            container(scrollable(column(boxes).spacing(2)).width(Length::Fill)).max_height(300),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// Slider over every storable value, dragging it edits the text the same way typing does
+    /// so the two stay in sync.
+    fn slider(&self) -> Option<Element<Message>> {
+        let (range, step) = self.scalar.slider_range()?;
+        let current = match self.value.trim().parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => self.baseline.trim().parse().unwrap_or(*range.start()),
+        };
+        let current = current.clamp(*range.start(), *range.end());
+        let pane = self.pane_id;
+        let slider = slider(range, current, move |value| Message::EditScalar {
+            value: self.scalar.format(self.scalar.snap(value)),
+            pane,
+        })
+        .step(step)
+        .width(300);
+        Some(slider.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One byte "Maximum boost" scalar of the demo, `X*0.01`
+    fn boost() -> ScalarView {
+        let scalar = crate::demo::definition().scalars.remove(1);
+        ScalarView::new(0, scalar, FileGuard::from(crate::demo::binary())).unwrap()
+    }
+
+    #[test]
+    fn hex_entry_written_as_stored_integer() {
+        let mut view = boost();
+        view.edit("0xFF".to_string());
+        assert!(view.is_raw_entry());
+        assert!(view.is_dirty());
+        assert_eq!(view.current_raw(), Some(0xFF));
+        let converted = view.scalar.from_raw(0xFF);
+        assert_eq!(view.current_value(), Some(converted));
+        view.write().unwrap();
+        assert_eq!(view.scalar.read_raw(&mut view.source).unwrap(), 0xFF);
+        assert!(!view.is_raw_entry());
+        assert_eq!(view.value, view.scalar.format(converted));
+    }
+
+    #[test]
+    fn hex_entry_must_fit() {
+        let mut view = boost();
+        let stored = view.scalar.read_raw(&mut view.source).unwrap();
+        view.edit("0x100".to_string());
+        assert_eq!(
+            view.invalid_reason().as_deref(),
+            Some("Not a hex or decimal integer that fits in 1 byte(s)")
+        );
+        assert!(view.write().is_err());
+        assert_eq!(view.scalar.read_raw(&mut view.source).unwrap(), stored);
+    }
+
+    #[test]
+    fn decimal_entry_goes_through_expression() {
+        let mut view = boost();
+        view.edit("1.5".to_string());
+        assert!(!view.is_raw_entry());
+        assert_eq!(view.current_raw(), Some(150));
+        view.write().unwrap();
+        assert_eq!(view.scalar.read_raw(&mut view.source).unwrap(), 150);
+    }
+
+    #[test]
+    fn division_by_zero_refuses_writes() {
+        let scalar = Scalar {
+            address: 0,
+            expression: "100/X".to_string(),
+            ..crate::demo::definition().scalars.remove(1)
+        };
+        let mut view = ScalarView::new(0, scalar, FileGuard::from(vec![0u8; 4])).unwrap();
+        let error = view.error.clone().unwrap();
+        assert!(error.contains("from raw value 0"), "{error}");
+        assert_eq!(view.value, "");
+        view.edit("5".to_string());
+        let error = view.write().unwrap_err().to_string();
+        assert!(error.starts_with("Not writing, "), "{error}");
+        assert_eq!(view.scalar.read_raw(&mut view.source).unwrap(), 0);
+    }
+
+    #[test]
+    fn rounded_value_shown_as_stored() {
+        let scalar = Scalar {
+            expression: "X*40".to_string(),
+            ..crate::demo::definition().scalars.remove(1)
+        };
+        let mut view = ScalarView::new(0, scalar, FileGuard::from(crate::demo::binary())).unwrap();
+        view.edit("50".to_string());
+        view.write().unwrap();
+        let stored = view.scalar.read(&mut view.source).unwrap();
+        assert_eq!(stored, 40.0);
+        assert_eq!(view.value, view.scalar.format(stored));
+        assert_eq!(view.rounded_from.as_deref(), Some("50"));
+        // Nothing is noted when the value is stored exactly
+        view.edit("80".to_string());
+        view.write().unwrap();
+        assert_eq!(view.value, "80");
+        assert_eq!(view.rounded_from, None);
     }
 }
@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use iced::{
+    widget::{button, column, container, row, scrollable, text, text_input},
+    Element, Length,
+};
+
+use super::{panes::style, scalar::ScalarView};
+use crate::{
+    definitions::{group_thousands, ranges_overlap, Scalar},
+    FileGuard, Message,
+};
+
+/// Every scalar of a category in one pane, a row each. Rows are edited and validated like
+/// single scalar panes.
+pub struct ScalarGroupView {
+    pane_id: usize,
+    /// Name of the category
+    pub name: String,
+    pub rows: Vec<ScalarView>,
+    /// Scalars that could not be read, left out of the rows
+    pub unreadable: Vec<String>,
+}
+
+impl ScalarGroupView {
+    /// Scalars that fail to read are listed instead of stopping the others from opening.
+    pub fn new(pane_id: usize, name: String, scalars: Vec<Scalar>, source: FileGuard) -> Self {
+        let mut rows = Vec::new();
+        let mut unreadable = Vec::new();
+        for scalar in scalars {
+            match ScalarView::new(pane_id, scalar, source.clone()) {
+                Ok(view) => rows.push(view),
+                Err(e) => unreadable.push(format!("{e:#}")),
+            }
+        }
+        Self {
+            pane_id,
+            name,
+            rows,
+            unreadable,
+        }
+    }
+
+    pub fn pane_id(&self) -> usize {
+        self.pane_id
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.rows.iter().any(ScalarView::is_dirty)
+    }
+
+    /// Rows with an edit that can be written
+    pub fn writable(&self) -> Vec<usize> {
+        (0..self.rows.len())
+            .filter(|&i| self.rows[i].is_dirty() && self.rows[i].check_writable().is_ok())
+            .collect()
+    }
+
+    pub fn reads(&self, range: &Range<u64>) -> bool {
+        self.rows
+            .iter()
+            .any(|row| ranges_overlap(&row.scalar.byte_range(), range))
+    }
+
+    pub fn refresh(&mut self) -> anyhow::Result<()> {
+        self.rows.iter_mut().try_for_each(ScalarView::refresh)
+    }
+
+    pub fn reload(&mut self) -> anyhow::Result<()> {
+        self.rows.iter_mut().try_for_each(ScalarView::reload)
+    }
+
+    pub fn set_separators(&mut self, separators: bool) {
+        for row in &mut self.rows {
+            row.separators = separators;
+        }
+    }
+
+    /// Write every row in `rows`, a row that fails does not stop the others. Returns the
+    /// bytes written with the names of the scalars, and the failures.
+    pub fn write(&mut self, rows: &[usize]) -> (Vec<(String, Range<u64>)>, Vec<String>) {
+        let mut written = Vec::new();
+        let mut failed = Vec::new();
+        for &i in rows {
+            let row = &mut self.rows[i];
+            match row.write() {
+                Ok(()) => written.push((row.scalar.name.clone(), row.scalar.byte_range())),
+                Err(e) => failed.push(format!("{}: {e}", row.scalar.name)),
+            }
+        }
+        (written, failed)
+    }
+
+    fn row(&self, index: usize) -> Element<Message> {
+        let view = &self.rows[index];
+        let pane = self.pane_id;
+        let name = text(view.scalar.name.clone()).width(Length::FillPortion(2));
+        if let Some(error) = &view.error {
+            return column![name, text(error.clone()).size(12).style(text::danger)].into();
+        }
+
+        let value = if view.separators && !view.raw {
+            group_thousands(&view.value)
+        } else {
+            view.value.clone()
+        };
+        let dirty = view.is_dirty();
+        // Same marker as single scalar panes
+        let input = text_input("Not a number", &value)
+            .on_input(move |value| Message::EditGroupScalar {
+                pane,
+                row: index,
+                value,
+            })
+            .width(100)
+            .style(move |theme, status| {
+                let mut style = text_input::default(theme, status);
+                if dirty {
+                    style.border.color = iced::Color::from_rgb(1.0, 0.6, 0.0);
+                    style.border.width = 2.0;
+                }
+                style
+            });
+        let can_step = view.current_raw().is_some();
+        let step = |label: &'static str, up: bool| {
+            button(text(label).size(14))
+                .padding([2, 8])
+                .on_press_maybe(can_step.then_some(Message::StepGroupScalar {
+                    pane,
+                    row: index,
+                    up,
+                }))
+        };
+        let controls = row![
+            name,
+            input,
+            step("-", false),
+            step("+", true),
+            text(view.scalar.units.clone()).width(Length::FillPortion(1)),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+
+        match view.invalid_reason() {
+            Some(reason) => column![controls, text(reason).size(12).style(text::danger)].into(),
+            None => controls.into(),
+        }
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let changed = self.rows.iter().filter(|row| row.is_dirty()).count();
+        let writable = self.writable();
+        let toolbar = container(
+            row![
+                button("Write all changed").on_press_maybe(
+                    (!writable.is_empty())
+                        .then_some(Message::WriteScalarGroup { pane: self.pane_id })
+                ),
+                text(format!("{changed} of {} changed", self.rows.len())),
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding(5)
+        .style(style::title_bar_active);
+
+        let mut rows = column((0..self.rows.len()).map(|i| self.row(i))).spacing(5);
+        if !self.unreadable.is_empty() {
+            rows = rows.push(
+                text(format!("Not shown:\n{}", self.unreadable.join("\n"))).style(text::danger),
+            );
+        }
+        column![toolbar, scrollable(rows.padding(5))]
+            .spacing(5)
+            .padding(5)
+            .into()
+    }
+}
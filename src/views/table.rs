@@ -1,19 +1,50 @@
-use std::f64::consts::PI;
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    f64::consts::{FRAC_PI_2, PI},
+    ops::{Range, RangeInclusive},
+    path::Path,
+};
 
+use anyhow::{bail, Context};
 use iced::{
+    event, mouse,
     widget::{
-        canvas::{Cache, Frame, Geometry},
-        column, container, row,
-        scrollable::{Direction, Scrollbar},
-        text_input::Status,
-        TextInput,
+        button,
+        canvas::{Cache, Event, Frame, Geometry},
+        checkbox, column, container, keyed_column, mouse_area, pick_list, row,
+        scrollable::{self, AbsoluteOffset, Direction, Scrollbar, Viewport},
+        slider, stack, text,
+        text_input::{self, Status},
+        tooltip,
+        tooltip::Position,
+        Space, TextInput,
     },
-    Element, Length, Padding, Size,
+    Color, Element, Length, Padding, Point, Rectangle, Size, Task,
 };
-use iced_aw::{Grid, GridRow};
 use plotters_iced::{Chart, ChartWidget};
 
-use crate::{definitions::Table, FileGuard, Message};
+use super::{
+    compare::{apply_delta, delta, DeltaMode},
+    grid_image::{GridImage, ImageCell},
+    histogram::Histogram,
+    panes::style,
+};
+use crate::{
+    colormap::Colormap,
+    definitions::{
+        bracket, format_raw_hex, group_thousands, is_hex_entry, parse_raw, strip_thousands, Axis,
+        CsvPrecision, DisplayScale, Table, TableValues,
+    },
+    eval,
+    ops::{
+        clamp_to, interpolate, parse_tsv, smooth, transpose_rows, BulkOp, Interpolation, PasteMode,
+        Target,
+    },
+    project::ChartLayout,
+    FileGuard, Message,
+};
 
 #[derive(Debug)]
 pub struct TableView {
@@ -22,11 +53,581 @@ pub struct TableView {
     pub x_head: Vec<String>,
     pub y_head: Vec<String>,
     pub data: Vec<String>,
+    /// Data rows read so far for tables too large to read up front, `None` when every row is
+    /// held. Rows that are not loaded are empty here and in the baseline, and have no edits.
+    loaded_rows: Option<Vec<bool>>,
     pub source: FileGuard,
     pub chart: Chart2D,
+    /// Values changed since the chart was last rebuilt, it is rebuilt when an edit is
+    /// committed rather than on every keystroke
+    chart_stale: bool,
+    pub histogram: Histogram,
+    /// Show the histogram in place of the chart
+    pub show_histogram: bool,
+    /// Show the chart column beside the grid, a hidden chart and histogram are not rebuilt
+    pub show_chart: bool,
+    /// Share of the pane width given to the chart, the grid gets the rest
+    pub chart_fraction: f32,
+    /// Color cell backgrounds based on their value
+    pub heatmap: bool,
+    /// Show row and column numbers beside the axis headers
+    pub show_indices: bool,
+    /// Minimum and maximum of the parseable data values
+    data_range: Option<(f64, f64)>,
+    /// Minimum and maximum of every stored data value of a table read as it is scrolled, so
+    /// the heatmap colours do not change as rows are read. `None` when every row is held.
+    stored_range: Option<(f64, f64)>,
+    /// Values as they were read from or last written to the binary
+    pub baseline: Baseline,
+    /// Cells whose value is not a number or cannot be stored
+    pub invalid: HashSet<EditSource>,
+    /// Display stored integers as hex instead of converted values
+    pub raw: bool,
+    /// Display converted values with thousands separators
+    pub separators: bool,
+    pub csv_precision: CsvPrecision,
+    /// Size of the chart when exported as an image
+    pub chart_image_size: ImageSize,
+    /// How many cells of the last write were stored rounded, cleared by the next edit
+    rounded_note: Option<String>,
+    /// Display data cells as their change from the baseline
+    pub show_change: bool,
+    /// Whether changes are shown as a difference or a percentage
+    pub change_mode: DeltaMode,
+    /// Show the x axis down the side and the y axis along the top
+    pub transposed: bool,
+    /// Keep the x and y axes read only and out of writes, even if the definition allows it
+    pub axes_locked: bool,
+    /// Where the values overlaid on the chart came from
+    pub overlay: Overlay,
+    comparison: Option<Vec<String>>,
+    /// Text typed while in raw or change mode, kept so partial input is not reformatted
+    /// while typing
+    typed_edits: HashMap<EditSource, String>,
+    pub selection: Option<Selection>,
+    /// Selection is being dragged out with the right mouse button
+    selecting: bool,
+    pub interpolation: Interpolation,
+    /// Smoothing strength in percent
+    pub smooth_strength: f64,
+    /// Row or column whose operation menu is open
+    pub header_menu: Option<Target>,
+    /// Value typed into the row/column operation menu
+    pub operand: String,
+    pub paste_mode: PasteMode,
+    /// Why the last paste did not cover the cells it was meant to
+    pub paste_notice: Option<String>,
+    /// Cell edits made since the last write
+    pub history: EditHistory,
+    /// Operating point typed into the lookup inputs
+    pub lookup_x: String,
+    pub lookup_y: String,
+    /// Display scale and offset of the data as typed, applied with a button
+    pub display_scale: String,
+    pub display_offset: String,
+    /// Cell being typed in and the value it had before, restored by Escape
+    pending: Option<(EditSource, String)>,
+    /// Cell last typed in or moved to, shown in the formula bar
+    focused: Option<EditSource>,
+    /// Relative expression typed into the formula bar, applied on commit
+    formula: Option<String>,
+    /// Notes from the project file keyed by (row, column)
+    pub notes: HashMap<(usize, usize), String>,
+    /// Cell whose note is being edited and the text typed so far
+    pub note_editor: Option<((usize, usize), String)>,
+    pub density: Density,
+    /// Width of each data column and of the y axis column, fitted to the text they show
+    column_widths: Vec<f32>,
+    header_column_width: f32,
+    /// Scroll offset of the cell grid
+    scroll_x: f32,
+    scroll_y: f32,
+    /// Size of the visible part of the cell grid, `None` until it has been scrolled
+    viewport_height: Option<f32>,
+    viewport_width: Option<f32>,
+}
+
+/// Marks a change shown as an absolute difference in percent mode
+const ABSOLUTE_MARKER: char = 'Δ';
+
+/// Parse a change typed in change mode, with or without the marker and `%` sign.
+fn parse_change(text: &str) -> Option<f64> {
+    let text = text.trim().trim_start_matches(ABSOLUTE_MARKER);
+    text.trim_end_matches('%').trim().parse().ok()
+}
+
+/// Height of every cell, rows must be the same height to know which are visible
+pub const ROW_HEIGHT: f32 = 32.0;
+pub const CELL_WIDTH: f32 = 100.0;
+/// Limits of the width fitted to a column's values
+const MIN_CELL_WIDTH: f32 = 40.0;
+const MAX_CELL_WIDTH: f32 = 160.0;
+/// Rows rendered past each edge of the viewport
+pub const OVERSCAN: usize = 4;
+/// Tables with more data cells than this read their rows as they are scrolled to
+const LAZY_CELLS: usize = 1 << 16;
+/// Viewport size assumed before the first scroll event
+pub const DEFAULT_VIEWPORT_HEIGHT: f32 = 1000.0;
+const DEFAULT_VIEWPORT_WIDTH: f32 = 600.0;
+/// Size of the row and column numbers shown with `show_indices`
+const INDEX_HEIGHT: f32 = 16.0;
+const INDEX_WIDTH: f32 = 32.0;
+
+/// Text size and spacing of table cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Density {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+impl Density {
+    pub const ALL: [Density; 2] = [Self::Comfortable, Self::Compact];
+
+    fn text_size(self) -> f32 {
+        match self {
+            Self::Comfortable => 16.0,
+            Self::Compact => 12.0,
+        }
+    }
+
+    fn padding(self) -> f32 {
+        match self {
+            Self::Comfortable => 5.0,
+            Self::Compact => 2.0,
+        }
+    }
+
+    /// Width of a cell fitting `chars` characters, estimated from the average width of a digit.
+    fn cell_width(self, chars: usize) -> f32 {
+        let text = chars as f32 * self.text_size() * 0.6;
+        (text + 2.0 * self.padding() + 4.0).clamp(MIN_CELL_WIDTH, MAX_CELL_WIDTH)
+    }
+}
+
+impl std::fmt::Display for Density {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Comfortable => "Comfortable",
+            Self::Compact => "Compact",
+        })
+    }
+}
+
+/// Decimals shown for a table or scalar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Decimals {
+    /// Worked out from the expression, every decimal for scalars
+    #[default]
+    Auto,
+    Fixed(usize),
+}
+
+impl Decimals {
+    pub const ALL: [Decimals; 10] = [
+        Self::Auto,
+        Self::Fixed(0),
+        Self::Fixed(1),
+        Self::Fixed(2),
+        Self::Fixed(3),
+        Self::Fixed(4),
+        Self::Fixed(5),
+        Self::Fixed(6),
+        Self::Fixed(7),
+        Self::Fixed(8),
+    ];
+}
+
+impl From<Option<usize>> for Decimals {
+    fn from(value: Option<usize>) -> Self {
+        value.map_or(Self::Auto, Self::Fixed)
+    }
+}
+
+impl From<Decimals> for Option<usize> {
+    fn from(value: Decimals) -> Self {
+        match value {
+            Decimals::Auto => None,
+            Decimals::Fixed(decimals) => Some(decimals),
+        }
+    }
+}
+
+impl std::fmt::Display for Decimals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => f.write_str("Auto decimals"),
+            Self::Fixed(decimals) => write!(f, "{decimals} decimals"),
+        }
+    }
+}
+
+/// Width of each column fitting its longest text. Rows may differ in length, there are as
+/// many widths as cells in the longest row.
+pub fn column_widths<S: AsRef<str>>(
+    rows: impl IntoIterator<Item = impl IntoIterator<Item = S>>,
+    density: Density,
+) -> Vec<f32> {
+    let mut longest: Vec<usize> = Vec::new();
+    for row in rows {
+        for (col, value) in row.into_iter().enumerate() {
+            let len = value.as_ref().chars().count();
+            match longest.get_mut(col) {
+                Some(max) => *max = (*max).max(len),
+                None => longest.push(len),
+            }
+        }
+    }
+    longest.into_iter().map(|n| density.cell_width(n)).collect()
+}
+
+/// Rows above the data such as the x axis, then the data rows in `visible`. Rows outside it are
+/// replaced by empty space of the same height so the scrollbar stays the right size.
+pub fn grid_body<'a>(
+    above: impl IntoIterator<Item = Element<'a, Message>>,
+    visible: Range<usize>,
+    total: usize,
+    data_row: impl Fn(usize) -> Element<'a, Message>,
+) -> Element<'a, Message> {
+    column(above)
+        .push(Space::with_height(visible.start as f32 * ROW_HEIGHT))
+        .push(keyed_column(visible.clone().map(|y| (y, data_row(y)))))
+        .push(Space::with_height(
+            (total - visible.end) as f32 * ROW_HEIGHT,
+        ))
+        .into()
+}
+
+/// Rows intersecting `offset..offset + height`, widened by `overscan` rows on each side.
+pub fn visible_rows(
+    offset: f32,
+    height: f32,
+    row_height: f32,
+    total: usize,
+    overscan: usize,
+) -> Range<usize> {
+    let first = (offset / row_height).floor().max(0.0) as usize;
+    let last = ((offset + height) / row_height).ceil().max(0.0) as usize;
+    first.saturating_sub(overscan).min(total)..(last + overscan).min(total)
+}
+
+/// Rectangular selection of data cells in (row, column) coordinates
+#[derive(Debug, Clone, Copy)]
+pub struct Selection {
+    pub anchor: (usize, usize),
+    pub end: (usize, usize),
+}
+
+impl Selection {
+    pub fn rows(&self) -> RangeInclusive<usize> {
+        self.anchor.0.min(self.end.0)..=self.anchor.0.max(self.end.0)
+    }
+    pub fn cols(&self) -> RangeInclusive<usize> {
+        self.anchor.1.min(self.end.1)..=self.anchor.1.max(self.end.1)
+    }
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        self.rows().contains(&row) && self.cols().contains(&col)
+    }
+    pub fn is_single(&self) -> bool {
+        self.anchor == self.end
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum SelectAction {
+    /// Start a new selection at a data cell index
+    Start(usize),
+    /// Extend the selection being dragged to a data cell index
+    Hover(usize),
+    End,
+    Clear,
+}
+
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    pub x_head: Vec<String>,
+    pub y_head: Vec<String>,
+    pub data: Vec<String>,
+}
+
+/// Edits kept for undo, older edits are dropped past this
+const MAX_HISTORY: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct CellEdit {
+    pub source: EditSource,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    undo: Vec<CellEdit>,
+    redo: Vec<CellEdit>,
+    /// Whether the next edit to the same cell extends the last one
+    coalesce: bool,
+}
+
+impl EditHistory {
+    /// Record an edit, consecutive edits to the same cell are merged into one.
+    pub fn push(&mut self, source: EditSource, old: String, new: String) {
+        self.redo.clear();
+        if let (true, Some(last)) = (self.coalesce, self.undo.last_mut()) {
+            if last.source == source {
+                last.new = new;
+                return;
+            }
+        }
+        self.undo.push(CellEdit { source, old, new });
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.coalesce = true;
+    }
+
+    pub fn undo(&mut self) -> Option<CellEdit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+        self.coalesce = false;
+        Some(edit)
+    }
+
+    pub fn redo(&mut self) -> Option<CellEdit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        self.coalesce = false;
+        Some(edit)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Whether an edited value is different from the baseline, compared as numbers where
+/// possible so reformatting a value is not a change.
+pub fn differs(current: &str, baseline: &str) -> bool {
+    match (
+        current.trim().parse::<f64>(),
+        baseline.trim().parse::<f64>(),
+    ) {
+        (Ok(a), Ok(b)) => a != b,
+        _ => current.trim() != baseline.trim(),
+    }
+}
+
+pub fn count_modified(current: &[String], baseline: &[String]) -> usize {
+    current
+        .iter()
+        .zip(baseline)
+        .filter(|(c, b)| differs(c, b))
+        .count()
+}
+
+/// Whether a cell value is a number that fits in the axis' storage range.
+pub fn valid_value(value: &str, range: Option<(f64, f64)>) -> bool {
+    match (value.trim().parse::<f64>(), range) {
+        (Ok(v), _) if !v.is_finite() => false,
+        (Ok(v), Some((a, b))) => {
+            let tolerance = (b - a).abs() * 1e-9;
+            v >= a.min(b) - tolerance && v <= a.max(b) + tolerance
+        }
+        (Ok(_), None) => true,
+        (Err(_), _) => false,
+    }
+}
+
+/// How a cell should be drawn besides its value
+#[derive(Debug, Clone, Copy, Default)]
+struct CellState {
+    background: Option<Color>,
+    selected: bool,
+    modified: bool,
+    invalid: bool,
+}
+
+/// Value of a relative expression typed into the formula bar: `+5`, `*1.03` and `/2` are applied
+/// to the current value and `=12.5` sets it, anything else is a plain value and gives `None`.
+/// A leading `-` is a negative value rather than a subtraction.
+pub fn relative_value(input: &str, current: f64) -> Option<Result<f64, String>> {
+    let input = input.trim();
+    let expression = match input.chars().next()? {
+        '=' => input[1..].to_string(),
+        '+' | '*' | '/' => format!("X{input}"),
+        _ => return None,
+    };
+    Some(eval::parse(&expression).map(|e| e.eval(current)))
 }
 
+/// Direction to move the focused cell in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveDirection {
+    Up,
+    Down,
+}
+
+/// Cell above or below `source` in a table of `width` x `height` data cells, `None` past the
+/// edge. The x axis is above the first data row.
+pub fn next_cell(
+    source: EditSource,
+    direction: MoveDirection,
+    width: usize,
+    height: usize,
+) -> Option<EditSource> {
+    match (source, direction) {
+        (EditSource::XHead(x), MoveDirection::Down) => (height > 0).then_some(EditSource::Data(x)),
+        (EditSource::XHead(_), MoveDirection::Up) => None,
+        (EditSource::YHead(y), MoveDirection::Down) => {
+            (y + 1 < height).then_some(EditSource::YHead(y + 1))
+        }
+        (EditSource::YHead(y), MoveDirection::Up) => y.checked_sub(1).map(EditSource::YHead),
+        (EditSource::Data(i), MoveDirection::Down) => {
+            (i + width < width * height).then_some(EditSource::Data(i + width))
+        }
+        (EditSource::Data(i), MoveDirection::Up) if i < width => Some(EditSource::XHead(i)),
+        (EditSource::Data(i), MoveDirection::Up) => Some(EditSource::Data(i - width)),
+    }
+}
+
+/// The cell in the same place once a table of `width` x `height` data cells is transposed, x and
+/// y headers trade places. Calling it again with `width` and `height` swapped undoes it.
+pub fn transpose_source(source: EditSource, width: usize, height: usize) -> EditSource {
+    match source {
+        EditSource::XHead(x) => EditSource::YHead(x),
+        EditSource::YHead(y) => EditSource::XHead(y),
+        EditSource::Data(i) => EditSource::Data((i % width) * height + i / width),
+    }
+}
+
+/// One of the three axes of a table, `Z` being the map data
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TableAxis {
+    X,
+    Y,
+    Z,
+}
+
+/// Values drawn over the chart to compare the data with
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Overlay {
+    #[default]
+    Off,
+    /// The values as they were when the overlay was picked, before any further edits
+    Snapshot,
+    /// The same table in the reference binary
+    Reference,
+}
+
+impl Overlay {
+    pub const ALL: [Overlay; 3] = [Self::Off, Self::Snapshot, Self::Reference];
+}
+
+impl std::fmt::Display for Overlay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Off => "No overlay",
+            Self::Snapshot => "Overlay snapshot",
+            Self::Reference => "Overlay reference",
+        })
+    }
+}
+
+/// Pixel size of an exported chart image
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageSize {
+    pub const ALL: [ImageSize; 4] = [
+        Self::new(800, 600),
+        Self::new(1600, 1200),
+        Self::new(2400, 1800),
+        Self::new(3200, 2400),
+    ];
+
+    const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+}
+
+impl Default for ImageSize {
+    fn default() -> Self {
+        Self::ALL[1]
+    }
+}
+
+impl std::fmt::Display for ImageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// Input of the display scale of the data
 #[derive(Debug, Copy, Clone)]
+pub enum DisplayField {
+    Scale,
+    Offset,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Extreme {
+    Min,
+    Max,
+}
+
+/// Minimum and maximum of `values` together with `range`.
+fn value_range(
+    values: impl IntoIterator<Item = f64>,
+    range: Option<(f64, f64)>,
+) -> Option<(f64, f64)> {
+    values.into_iter().fold(range, |range, v| match range {
+        None => Some((v, v)),
+        Some((min, max)) => Some((min.min(v), max.max(v))),
+    })
+}
+
+/// Consecutive rows grouped into ranges, so each range can be read at once.
+pub fn row_runs(rows: impl IntoIterator<Item = usize>) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for y in rows {
+        match runs.last_mut() {
+            Some(run) if run.end == y => run.end += 1,
+            _ => runs.push(y..y + 1),
+        }
+    }
+    runs
+}
+
+/// Index of the smallest or largest value, skipping values that are invalid or not numbers.
+/// Ties go to the first occurrence.
+pub fn find_extreme(
+    values: &[String],
+    invalid: impl Fn(usize) -> bool,
+    extreme: Extreme,
+) -> Option<usize> {
+    let mut found: Option<(usize, f64)> = None;
+    for (i, value) in values.iter().enumerate() {
+        let Ok(value) = value.parse::<f64>() else {
+            continue;
+        };
+        if value.is_nan() || invalid(i) {
+            continue;
+        }
+        let better = match (found, extreme) {
+            (None, _) => true,
+            (Some((_, best)), Extreme::Min) => value < best,
+            (Some((_, best)), Extreme::Max) => value > best,
+        };
+        if better {
+            found = Some((i, value));
+        }
+    }
+    found.map(|(i, _)| i)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum EditSource {
     YHead(usize),
     XHead(usize),
@@ -34,179 +635,2726 @@ pub enum EditSource {
 }
 
 impl TableView {
-    pub fn new(pane_id: usize, table: Table, mut source: FileGuard) -> Self {
-        let x_head: Vec<String> = table.x.read_strings(&mut source).unwrap();
-        let y_head = table.y.read_strings(&mut source).unwrap();
-        let data = table.z.read_strings(&mut source).unwrap();
-        let chart = Chart2D::new(x_head.as_slice(), y_head.as_slice(), data.as_slice());
+    pub fn new(pane_id: usize, table: Table, mut source: FileGuard) -> anyhow::Result<Self> {
+        // Usually a region running past the end of the binary
+        let mut read = |name: &str, axis: &Axis| {
+            axis.read_strings(&mut source).with_context(|| {
+                let range = axis.byte_range().unwrap_or_default();
+                format!(
+                    "Could not read the {name} axis of {} (0x{:X}..0x{:X})",
+                    table.name, range.start, range.end
+                )
+            })
+        };
+        let x_head = read("x", &table.x)?;
+        let y_head = read("y", &table.y)?;
+        let cells = table.z.len();
+        let lazy = cells > LAZY_CELLS
+            && cells == x_head.len() * y_head.len()
+            && table.z.byte_range().is_some();
+        let (data, stored_range) = if lazy {
+            // Rows are formatted as they are shown, only the range of the values is kept
+            let range = table.z.byte_range().unwrap_or_default();
+            let values = table.z.read(&mut source).with_context(|| {
+                format!(
+                    "Could not read the z axis of {} (0x{:X}..0x{:X})",
+                    table.name, range.start, range.end
+                )
+            })?;
+            (vec![String::new(); cells], value_range(values, None))
+        } else {
+            (read("z", &table.z)?, None)
+        };
+        let loaded_rows = lazy.then(|| vec![false; y_head.len()]);
+        let chart = Chart2D::new(
+            pane_id,
+            &table,
+            x_head.as_slice(),
+            y_head.as_slice(),
+            data.as_slice(),
+        );
 
-        Self {
+        let histogram = Histogram::new(&data);
+        let baseline = Baseline {
+            x_head: x_head.clone(),
+            y_head: y_head.clone(),
+            data: data.clone(),
+        };
+
+        let mut view = Self {
             chart,
+            chart_stale: lazy,
+            histogram,
+            show_histogram: false,
+            // Plotting a large table would read all of it
+            show_chart: !lazy,
+            chart_fraction: 0.5,
             pane_id,
             table,
             x_head,
             y_head,
             data,
+            loaded_rows,
             source,
+            heatmap: true,
+            show_indices: false,
+            data_range: None,
+            stored_range,
+            baseline,
+            invalid: HashSet::new(),
+            raw: false,
+            separators: false,
+            csv_precision: CsvPrecision::default(),
+            chart_image_size: ImageSize::default(),
+            rounded_note: None,
+            show_change: false,
+            change_mode: DeltaMode::Percent,
+            transposed: false,
+            axes_locked: false,
+            overlay: Overlay::default(),
+            comparison: None,
+            typed_edits: HashMap::new(),
+            selection: None,
+            selecting: false,
+            interpolation: Interpolation::default(),
+            smooth_strength: 50.0,
+            header_menu: None,
+            operand: String::new(),
+            paste_mode: PasteMode::default(),
+            paste_notice: None,
+            history: EditHistory::default(),
+            pending: None,
+            focused: None,
+            formula: None,
+            notes: HashMap::new(),
+            note_editor: None,
+            lookup_x: String::new(),
+            lookup_y: String::new(),
+            display_scale: "1".to_string(),
+            display_offset: "0".to_string(),
+            density: Density::default(),
+            column_widths: Vec::new(),
+            header_column_width: CELL_WIDTH,
+            scroll_x: 0.0,
+            scroll_y: 0.0,
+            viewport_height: None,
+            viewport_width: None,
+        };
+        view.load_visible()?;
+        view.update_data_range();
+        view.update_widths();
+        Ok(view)
+    }
+
+    /// Whether the stored data row `y` has been read
+    fn row_loaded(&self, y: usize) -> bool {
+        self.loaded_rows.as_ref().is_none_or(|rows| rows[y])
+    }
+
+    /// Stored data rows kept in memory: the ones in or near the visible part of the grid and
+    /// the ones the lookup point is interpolated from. A transposed table shows part of every
+    /// stored row and the chart plots all of them, so either holds every row.
+    fn held_rows(&self) -> Vec<Range<usize>> {
+        let rows = self.y_head.len();
+        if self.transposed || self.show_chart {
+            return std::iter::once(0..rows).collect();
         }
+        let visible = visible_rows(
+            self.scroll_y - self.header_height(),
+            self.viewport_height.unwrap_or(DEFAULT_VIEWPORT_HEIGHT),
+            ROW_HEIGHT,
+            rows,
+            OVERSCAN,
+        );
+        std::iter::once(visible).chain(self.lookup_rows()).collect()
     }
 
-    fn cell<'a>(
-        &'a self,
-        value: &'a str,
-        source: EditSource,
-        writeable: bool,
-    ) -> Element<'a, Message> {
-        let mut text_box = TextInput::new("", value).width(Length::Fixed(100.0));
+    /// Stored data rows the lookup point is interpolated from
+    fn lookup_rows(&self) -> Option<Range<usize>> {
+        if self.lookup_x.is_empty() {
+            return None;
+        }
+        if self.y_head.len() == 1 {
+            return Some(0..1);
+        }
+        let y = self.lookup_y.trim().parse().ok()?;
+        let (y0, y1, _) = bracket(&parse_values(&self.y_head), y)?;
+        Some(y0..y1 + 1)
+    }
 
-        if writeable {
-            text_box = text_box
-                .on_submit(Message::WriteTable { pane: self.pane_id })
-                .on_input(move |value| Message::EditCell {
-                    value,
-                    pane: self.pane_id,
-                    source,
-                });
-        } else {
-            text_box = text_box.style(|theme, status| {
-                let mut style = iced::widget::text_input::default(theme, status);
-                style.value = iced::widget::text_input::default(theme, Status::Active).value;
-                style
+    /// Read the stored data rows in `rows` that have not been read yet, returning whether
+    /// any were. Cells set before their row was read keep their value.
+    fn load_rows(&mut self, rows: Range<usize>) -> std::io::Result<bool> {
+        let Some(loaded) = &self.loaded_rows else {
+            return Ok(false);
+        };
+        let rows = rows.start.min(loaded.len())..rows.end.min(loaded.len());
+        let runs = row_runs(rows.filter(|&y| !loaded[y]));
+        let width = self.x_head.len();
+        for run in &runs {
+            let cells = run.start * width..run.end * width;
+            let values = self
+                .table
+                .z
+                .read_strings_range(&mut self.source, cells.clone())?;
+            for (i, value) in cells.zip(values) {
+                if self.data[i].is_empty() && !self.invalid.contains(&EditSource::Data(i)) {
+                    self.data[i].clone_from(&value);
+                }
+                self.baseline.data[i] = value;
+            }
+            if let Some(loaded) = &mut self.loaded_rows {
+                loaded[run.clone()].fill(true);
+            }
+        }
+        Ok(!runs.is_empty())
+    }
+
+    /// Read the row of a data cell before it is changed, so the change is compared to what is
+    /// stored (load-then-edit).
+    fn load_cell(&mut self, source: EditSource) -> std::io::Result<()> {
+        if let EditSource::Data(i) = source {
+            let y = i / self.x_head.len();
+            self.load_rows(y..y + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Forget the stored data rows outside `held` that have no edits, returning whether any
+    /// were forgotten.
+    fn unload_rows(&mut self, held: &[Range<usize>]) -> bool {
+        let width = self.x_head.len();
+        let Some(loaded) = &mut self.loaded_rows else {
+            return false;
+        };
+        let mut unloaded = false;
+        for (y, row_loaded) in loaded.iter_mut().enumerate() {
+            if !*row_loaded || held.iter().any(|rows| rows.contains(&y)) {
+                continue;
+            }
+            let cells = y * width..(y + 1) * width;
+            let edited = cells.clone().any(|i| {
+                let source = EditSource::Data(i);
+                differs(&self.data[i], &self.baseline.data[i])
+                    || self.invalid.contains(&source)
+                    || self.typed_edits.contains_key(&source)
             });
+            if !edited {
+                for i in cells {
+                    self.data[i] = String::new();
+                    self.baseline.data[i] = String::new();
+                }
+                *row_loaded = false;
+                unloaded = true;
+            }
         }
+        unloaded
+    }
 
-        text_box.into()
+    /// Read the data rows that are held and forget the others, see [`Self::held_rows`].
+    /// Does nothing when the whole table is read up front.
+    fn load_visible(&mut self) -> std::io::Result<()> {
+        if self.loaded_rows.is_none() {
+            return Ok(());
+        }
+        let held = self.held_rows();
+        let mut changed = self.unload_rows(&held);
+        for rows in held {
+            changed |= self.load_rows(rows)?;
+        }
+        if changed {
+            let widths = std::mem::take(&mut self.column_widths);
+            self.update_data_range();
+            self.update_widths();
+            // Columns only grow while scrolling so the grid does not jump around
+            for (width, old) in self.column_widths.iter_mut().zip(widths) {
+                *width = width.max(old);
+            }
+            self.chart_stale = true;
+            self.refresh_chart();
+        }
+        Ok(())
     }
 
-    pub fn view(&self) -> Element<Message> {
-        let x_writeable = self.table.x.writeable();
-        let y_writeable = self.table.y.writeable();
-        let data_writeable = self.table.z.writeable();
+    /// Data values of every cell, rows that are not loaded are read from the binary for this
+    /// call only.
+    pub fn z_values(&self) -> std::io::Result<Cow<'_, [String]>> {
+        let Some(loaded) = &self.loaded_rows else {
+            return Ok(Cow::Borrowed(&self.data));
+        };
+        let runs = row_runs((0..loaded.len()).filter(|&y| !loaded[y]));
+        if runs.is_empty() {
+            return Ok(Cow::Borrowed(&self.data));
+        }
+        let mut source = self.source.clone();
+        let mut data = self.data.clone();
+        let width = self.x_head.len();
+        for run in runs {
+            let cells = run.start * width..run.end * width;
+            let values = self
+                .table
+                .z
+                .read_strings_range(&mut source, cells.clone())?;
+            for (i, value) in cells.zip(values) {
+                if data[i].is_empty() && !self.invalid.contains(&EditSource::Data(i)) {
+                    data[i] = value;
+                }
+            }
+        }
+        Ok(Cow::Owned(data))
+    }
+
+    /// Data as stored, only the loaded rows are read when the table is read as it is scrolled.
+    fn read_data(&mut self) -> std::io::Result<Vec<String>> {
+        let Some(loaded) = &self.loaded_rows else {
+            return self.table.z.read_strings(&mut self.source);
+        };
+        self.stored_range = value_range(self.table.z.read(&mut self.source)?, None);
+        let runs = row_runs((0..loaded.len()).filter(|&y| loaded[y]));
+        let width = self.x_head.len();
+        let mut data = vec![String::new(); self.data.len()];
+        for run in runs {
+            let cells = run.start * width..run.end * width;
+            let values = self
+                .table
+                .z
+                .read_strings_range(&mut self.source, cells.clone())?;
+            data[cells].clone_from_slice(&values);
+        }
+        Ok(data)
+    }
+
+    /// Recompute the cached data range and histogram, must be called whenever `data` changes.
+    pub fn update_data_range(&mut self) {
+        if self.show_chart {
+            self.histogram.set_data(&self.data);
+        }
+        let values = self.data.iter().filter_map(|v| v.parse::<f64>().ok());
+        self.data_range = value_range(values, self.stored_range);
+    }
+
+    /// Fit the column widths to the text shown in every cell, must be called whenever that
+    /// text may change.
+    fn update_widths(&mut self) {
+        let (width, height) = self.display_size();
+        let shown = |source| self.display_value(self.stored_at(source));
+        let mut rows: Vec<Vec<String>> =
+            vec![(0..width).map(|x| shown(EditSource::XHead(x))).collect()];
+        // Rows that are not loaded have nothing to fit
+        let loaded = (0..height).filter(|&y| self.transposed || self.row_loaded(y));
+        rows.extend(loaded.map(|y| {
+            (0..width)
+                .map(|x| shown(EditSource::Data(y * width + x)))
+                .collect()
+        }));
+        let y_head = (0..height).map(|y| [shown(EditSource::YHead(y))]);
+        let header = column_widths(y_head, self.density);
+
+        self.column_widths = column_widths(rows, self.density);
+        self.header_column_width = header.first().copied().unwrap_or(MIN_CELL_WIDTH);
+    }
+
+    pub fn set_density(&mut self, density: Density) {
+        self.density = density;
+        self.update_widths();
+    }
+
+    pub fn set_separators(&mut self, separators: bool) {
+        self.separators = separators;
+        self.update_widths();
+    }
+
+    /// Width of the cells of a column
+    fn cell_width(&self, source: EditSource) -> f32 {
+        match self.to_display(source) {
+            EditSource::XHead(x) => self.column_widths[x],
+            EditSource::YHead(_) => self.header_column_width,
+            EditSource::Data(i) => self.column_widths[i % self.display_size().0],
+        }
+    }
+
+    /// Number of (columns, rows) of data cells on screen.
+    fn display_size(&self) -> (usize, usize) {
+        let size = (self.x_head.len(), self.y_head.len());
+        if self.transposed {
+            (size.1, size.0)
+        } else {
+            size
+        }
+    }
+
+    /// Position on screen of a stored cell, as the cell that would be there without transposing.
+    fn to_display(&self, source: EditSource) -> EditSource {
+        if self.transposed {
+            transpose_source(source, self.x_head.len(), self.y_head.len())
+        } else {
+            source
+        }
+    }
+
+    /// Stored cell shown at a position on screen, the inverse of [`Self::to_display`].
+    fn stored_at(&self, source: EditSource) -> EditSource {
+        if self.transposed {
+            transpose_source(source, self.y_head.len(), self.x_head.len())
+        } else {
+            source
+        }
+    }
+
+    /// Swap the displayed rows and columns, the table is still stored and written the same way.
+    pub fn toggle_transposed(&mut self) -> std::io::Result<()> {
+        self.transposed = !self.transposed;
+        self.load_visible()?;
+        self.chart.swap_axes();
+        self.chart_stale = true;
+        self.refresh_chart();
+        if let Some(source) = self.focused {
+            self.highlight(source);
+        }
+        self.update_widths();
+        Ok(())
+    }
+
+    fn axis(&self, source: EditSource) -> &Axis {
+        match source {
+            EditSource::XHead(_) => &self.table.x,
+            EditSource::YHead(_) => &self.table.y,
+            EditSource::Data(_) => &self.table.z,
+        }
+    }
+
+    /// Whether a cell can be typed in, stored axes are read only while locked.
+    pub fn editable(&self, source: EditSource) -> bool {
+        let locked = self.axes_locked && !matches!(source, EditSource::Data(_));
+        self.axis(source).writeable() && !locked
+    }
+
+    /// Set a cell's value and validate it, in raw mode the value is a stored integer.
+    pub fn edit(&mut self, source: EditSource, value: String) -> std::io::Result<()> {
+        if !self.editable(source) {
+            return Ok(());
+        }
+        self.load_cell(source)?;
+        self.highlight(source);
+        if self.pending.as_ref().is_none_or(|(s, _)| *s != source) {
+            self.pending = Some((source, self.value(source).to_string()));
+        }
+        let old = self.value(source).to_string();
+        self.apply_edit(source, value);
+        let new = self.value(source).to_string();
+        self.history.push(source, old, new);
+        Ok(())
+    }
+
+    fn apply_edit(&mut self, source: EditSource, value: String) {
+        self.rounded_note = None;
+        let value = if self.separators && !self.raw {
+            strip_thousands(&value)
+        } else {
+            value
+        };
+        // `0x` entries are stored integers outside raw mode too
+        let raw = self.raw || is_hex_entry(&value);
+        if let (true, Some(size)) = (raw, self.axis(source).element_size()) {
+            self.typed_edits.insert(source, value.clone());
+            let axis = self.axis(source);
+            match parse_raw(&value, size).and_then(|raw| axis.from_raw(raw)) {
+                Some(converted) => {
+                    let converted = axis.format(converted);
+                    self.set_value(source, converted);
+                }
+                None => {
+                    self.invalid.insert(source);
+                }
+            }
+            return;
+        }
+        if let (true, EditSource::Data(i)) = (self.show_change, source) {
+            self.typed_edits.insert(source, value.clone());
+            match (parse_change(&value), self.baseline.data[i].parse::<f64>()) {
+                (Some(change), Ok(base)) => {
+                    let mode = self.cell_change_mode(base);
+                    let converted = self.format_data(apply_delta(change, base, mode));
+                    self.set_value(source, converted);
+                }
+                _ => {
+                    self.invalid.insert(source);
+                }
+            }
+            return;
+        }
+
+        self.typed_edits.remove(&source);
+        self.set_value(source, value);
+    }
+
+    /// Change of a value from its baseline as shown in change mode.
+    fn format_change(&self, value: f64, base: f64) -> String {
+        let mode = self.cell_change_mode(base);
+        match delta(value, base, mode) {
+            Some(change) if mode == DeltaMode::Absolute => {
+                let sign = if change < 0.0 { "" } else { "+" };
+                // Only marked when the other cells show percentages
+                let marker = if mode == self.change_mode {
+                    String::new()
+                } else {
+                    ABSOLUTE_MARKER.to_string()
+                };
+                format!("{marker}{sign}{}", self.format_data(change))
+            }
+            Some(change) => format!("{change:+.2}%"),
+            None => value.to_string(),
+        }
+    }
+
+    /// Percent changes of a zero baseline are undefined, those cells use the absolute change.
+    fn cell_change_mode(&self, base: f64) -> DeltaMode {
+        if base == 0.0 {
+            DeltaMode::Absolute
+        } else {
+            self.change_mode
+        }
+    }
+
+    /// Finish typing in a cell, the chart is rebuilt and Escape no longer restores it.
+    pub fn commit_edit(&mut self) {
+        self.pending = None;
+        // Hex typed outside raw mode is shown converted once done, malformed entries stay
+        if !self.raw && !self.show_change {
+            let invalid = &self.invalid;
+            self.typed_edits
+                .retain(|source, _| invalid.contains(source));
+        }
+        self.refresh_chart();
+    }
+
+    /// Restore the cell being typed in to the text it showed before typing started.
+    pub fn cancel_edit(&mut self) {
+        if let Some((source, original)) = self.pending.take() {
+            let old = self.value(source).to_string();
+            self.typed_edits.remove(&source);
+            self.set_value(source, original.clone());
+            self.history.push(source, old, original);
+            self.commit_edit();
+        }
+    }
+
+    /// Step back one cell edit, does nothing if there is nothing to undo.
+    pub fn undo(&mut self) -> std::io::Result<()> {
+        self.pending = None;
+        if let Some(edit) = self.history.undo() {
+            self.load_cell(edit.source)?;
+            self.typed_edits.remove(&edit.source);
+            self.set_value(edit.source, edit.old);
+            self.refresh_chart();
+        }
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> std::io::Result<()> {
+        self.pending = None;
+        if let Some(edit) = self.history.redo() {
+            self.load_cell(edit.source)?;
+            self.typed_edits.remove(&edit.source);
+            self.set_value(edit.source, edit.new);
+            self.refresh_chart();
+        }
+        Ok(())
+    }
+
+    /// Rebuild the chart if any values changed since it was last built, a hidden chart is
+    /// left stale until it is shown.
+    pub fn refresh_chart(&mut self) {
+        if self.chart_stale && self.show_chart {
+            if self.transposed {
+                let data = self.transpose_data(&self.data);
+                self.chart.set_data(&self.y_head, &self.x_head, &data);
+                let comparison = self.comparison.as_ref().map(|z| self.transpose_data(z));
+                self.chart.set_comparison(comparison.as_deref());
+            } else {
+                self.chart.set_data(&self.x_head, &self.y_head, &self.data);
+                self.chart.set_comparison(self.comparison.as_deref());
+            }
+            self.chart_stale = false;
+        }
+    }
+
+    /// Show or hide the chart, it is brought up to date with any edits made while hidden.
+    pub fn toggle_chart(&mut self) -> std::io::Result<()> {
+        self.show_chart = !self.show_chart;
+        self.load_visible()?;
+        if self.show_chart {
+            self.histogram.set_data(&self.data);
+            self.refresh_chart();
+        }
+        Ok(())
+    }
+
+    /// Chart visibility and width, kept in the project file for each table.
+    pub fn chart_layout(&self) -> ChartLayout {
+        ChartLayout {
+            visible: self.show_chart,
+            fraction: self.chart_fraction,
+        }
+    }
+
+    pub fn set_chart_layout(&mut self, layout: ChartLayout) -> std::io::Result<()> {
+        self.chart_fraction = layout
+            .fraction
+            .clamp(*CHART_FRACTION_RANGE.start(), *CHART_FRACTION_RANGE.end());
+        if layout.visible != self.show_chart {
+            self.toggle_chart()?;
+        }
+        Ok(())
+    }
+
+    /// Data values in the order they are shown in a transposed table.
+    fn transpose_data(&self, data: &[String]) -> Vec<String> {
+        let (width, height) = self.display_size();
+        (0..width * height)
+            .map(|i| match self.stored_at(EditSource::Data(i)) {
+                EditSource::Data(i) => data[i].clone(),
+                EditSource::XHead(_) | EditSource::YHead(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    /// Overlay other values of the data on the chart, or stop with `None`.
+    pub fn set_comparison(&mut self, overlay: Overlay, values: Option<Vec<String>>) {
+        self.overlay = overlay;
+        self.comparison = values;
+        self.chart_stale = true;
+        self.refresh_chart();
+    }
+
+    fn set_value(&mut self, source: EditSource, value: String) {
+        self.chart_stale = true;
+        if valid_value(&value, self.axis(source).range()) {
+            self.invalid.remove(&source);
+        } else {
+            self.invalid.insert(source);
+        }
+
+        match source {
+            EditSource::XHead(n) => self.x_head[n] = value,
+            EditSource::YHead(n) => self.y_head[n] = value,
+            EditSource::Data(n) => {
+                self.data[n] = value;
+                self.update_data_range();
+            }
+        }
+        self.update_widths();
+    }
+
+    /// Switch between converted and raw display. Edits are always stored as converted values
+    /// so they carry over, only raw text that could not be parsed is dropped.
+    pub fn toggle_raw(&mut self) {
+        self.raw = !self.raw;
+        self.show_change = false;
+        self.drop_typed_edits();
+        self.update_widths();
+    }
+
+    /// Switch data cells between absolute values and their change from the baseline, edits
+    /// carry over the same way as for [`Self::toggle_raw`].
+    pub fn toggle_change(&mut self) {
+        self.show_change = !self.show_change;
+        self.raw = false;
+        self.drop_typed_edits();
+        self.update_widths();
+    }
+
+    /// Show changes as a difference or a percentage, partly typed changes are dropped.
+    pub fn set_change_mode(&mut self, mode: DeltaMode) {
+        self.change_mode = mode;
+        self.drop_typed_edits();
+        self.update_widths();
+    }
+
+    fn drop_typed_edits(&mut self) {
+        for (source, _) in std::mem::take(&mut self.typed_edits) {
+            let value = self.value(source).to_string();
+            self.set_value(source, value);
+        }
+    }
+
+    fn value(&self, source: EditSource) -> &str {
+        match source {
+            EditSource::XHead(n) => &self.x_head[n],
+            EditSource::YHead(n) => &self.y_head[n],
+            EditSource::Data(n) => &self.data[n],
+        }
+    }
+
+    /// Text shown in a cell, the stored integer in hex when in raw mode or the change from
+    /// the baseline in change mode.
+    fn display_value(&self, source: EditSource) -> String {
+        if let EditSource::XHead(n) | EditSource::YHead(n) = source {
+            if let Some(name) = self.axis(source).name(n) {
+                return name.to_string();
+            }
+        }
+        let value = self.value(source);
+        if let (true, EditSource::Data(i)) = (self.show_change, source) {
+            if let Some(edit) = self.typed_edits.get(&source) {
+                return edit.clone();
+            }
+            return match (value.parse::<f64>(), self.baseline.data[i].parse::<f64>()) {
+                (Ok(v), Ok(base)) => self.format_change(v, base),
+                _ => value.to_string(),
+            };
+        }
+        if let Some(edit) = self.typed_edits.get(&source) {
+            return edit.clone();
+        }
+        if !self.raw {
+            return if self.separators {
+                group_thousands(value)
+            } else {
+                value.to_string()
+            };
+        }
+        let axis = self.axis(source);
+        match (axis.element_size(), value.trim().parse()) {
+            (Some(size), Ok(v)) => axis
+                .to_raw(v)
+                .map_or(value.to_string(), |raw| format_raw_hex(raw, size)),
+            _ => value.to_string(),
+        }
+    }
+
+    /// Address and raw stored form of a cell's value, shown when hovering it.
+    fn cell_info(&self, source: EditSource) -> String {
+        let index = match source {
+            EditSource::XHead(n) | EditSource::YHead(n) | EditSource::Data(n) => n,
+        };
+        let axis = self.axis(source);
+        let (Some(address), Some(size)) = (axis.data.element_address(index), axis.element_size())
+        else {
+            return "User defined axis, not stored in the binary".to_string();
+        };
+        let raw = match self
+            .value(source)
+            .trim()
+            .parse()
+            .ok()
+            .and_then(|v| axis.to_raw(v))
+        {
+            Some(raw) => format!("{} ({raw})", format_raw_hex(raw, size)),
+            None => "-".to_string(),
+        };
+        let info = format!("Address 0x{address:X}\nRaw {raw}");
+        match source {
+            EditSource::XHead(_) | EditSource::YHead(_) => {
+                format!("{info}\n{}", Self::storage_info(axis))
+            }
+            EditSource::Data(_) => info,
+        }
+    }
+
+    /// How an axis is stored, to check a definition against the firmware.
+    fn storage_info(axis: &Axis) -> String {
+        let (Some(size), Some((raw_min, raw_max)), Some((min, max)), Some(expression)) = (
+            axis.element_size(),
+            axis.raw_range(),
+            axis.range(),
+            axis.expression(),
+        ) else {
+            return String::new();
+        };
+        let signedness = if axis.signed() { "signed" } else { "unsigned" };
+        format!(
+            "{size} byte {signedness} elements, raw {raw_min}..={raw_max}\n\
+             Converted {}..={}\nExpression {expression}",
+            axis.format(min),
+            axis.format(max)
+        )
+    }
+
+    /// Human readable position of a cell for messages
+    fn describe(&self, source: EditSource) -> String {
+        match source {
+            EditSource::XHead(n) => format!("x axis {n}"),
+            EditSource::YHead(n) => format!("y axis {n}"),
+            EditSource::Data(i) => format!("{:?}", self.coords(i)),
+        }
+    }
+
+    /// Fail listing all invalid cells, nothing may be written while there are any.
+    pub fn check_valid(&self) -> anyhow::Result<()> {
+        if self.invalid.is_empty() {
+            return Ok(());
+        }
+        let mut invalid: Vec<EditSource> = self.invalid.iter().copied().collect();
+        invalid.sort_by_key(|s| match *s {
+            EditSource::XHead(n) => (0, n),
+            EditSource::YHead(n) => (1, n),
+            EditSource::Data(n) => (2, n),
+        });
+        let cells: Vec<String> = invalid.into_iter().map(|s| self.describe(s)).collect();
+        bail!("Not writing, invalid values at: {}", cells.join(", "));
+    }
+
+    /// Number of cells, headers included, that differ from the baseline.
+    pub fn modified_count(&self) -> usize {
+        count_modified(&self.x_head, &self.baseline.x_head)
+            + count_modified(&self.y_head, &self.baseline.y_head)
+            + count_modified(&self.data, &self.baseline.data)
+    }
+
+    /// Whether there are edits that have not been written
+    pub fn is_dirty(&self) -> bool {
+        self.modified_count() > 0
+    }
+
+    /// Whether any value of an axis differs from the one last read or written, compared at
+    /// display precision
+    pub fn axis_modified(&self, axis: TableAxis) -> bool {
+        let (values, baseline) = match axis {
+            TableAxis::X => (&self.x_head, &self.baseline.x_head),
+            TableAxis::Y => (&self.y_head, &self.baseline.y_head),
+            TableAxis::Z => (&self.data, &self.baseline.data),
+        };
+        count_modified(values, baseline) > 0
+    }
+
+    fn is_modified(&self, source: EditSource) -> bool {
+        match source {
+            EditSource::XHead(n) => differs(&self.x_head[n], &self.baseline.x_head[n]),
+            EditSource::YHead(n) => differs(&self.y_head[n], &self.baseline.y_head[n]),
+            EditSource::Data(n) => differs(&self.data[n], &self.baseline.data[n]),
+        }
+    }
+
+    /// Whether the Write button is enabled, there must be changes and all of them valid.
+    pub fn can_write(&self) -> bool {
+        self.invalid.is_empty() && self.modified_count() > 0
+    }
+
+    /// Values for export at full precision, unmodified values are read from the binary so
+    /// they are not rounded to the display precision.
+    fn export_values(&self) -> std::io::Result<TableValues> {
+        let mut source = self.source.clone();
+        let mut values = TableValues {
+            x: self.table.x.read(&mut source)?,
+            y: self.table.y.read(&mut source)?,
+            data: self.table.z.read(&mut source)?,
+        };
+
+        let use_edits = |values: &mut [f64], source: fn(usize) -> EditSource| {
+            for (n, value) in values.iter_mut().enumerate() {
+                if !self.is_modified(source(n)) {
+                    continue;
+                }
+                if let Ok(v) = self.value(source(n)).trim().parse() {
+                    *value = v;
+                }
+            }
+        };
+        use_edits(&mut values.x, EditSource::XHead);
+        use_edits(&mut values.y, EditSource::YHead);
+        use_edits(&mut values.data, EditSource::Data);
+        Ok(values)
+    }
+
+    pub fn to_csv(&self) -> std::io::Result<String> {
+        Ok(Table::to_csv(&self.export_values()?, self.csv_precision))
+    }
+
+    /// Load a grid from CSV as unwritten edits, axes that are not stored in the binary are
+    /// left as they are.
+    pub fn import_csv(&mut self, csv: &str) -> anyhow::Result<()> {
+        let TableValues { x, y, data } = self.table.grid_from_csv(csv)?;
+        self.load_rows(0..self.y_head.len())?;
+
+        let mut values = Vec::new();
+        if self.table.x.writeable() {
+            values.extend(
+                x.into_iter()
+                    .enumerate()
+                    .map(|(n, v)| (EditSource::XHead(n), v)),
+            );
+        }
+        if self.table.y.writeable() {
+            values.extend(
+                y.into_iter()
+                    .enumerate()
+                    .map(|(n, v)| (EditSource::YHead(n), v)),
+            );
+        }
+        values.extend(
+            data.into_iter()
+                .enumerate()
+                .map(|(i, v)| (EditSource::Data(i), v)),
+        );
+
+        for (source, value) in values {
+            let value = self.axis(source).format(value);
+            self.typed_edits.remove(&source);
+            self.set_value(source, value);
+        }
+        self.refresh_chart();
+        Ok(())
+    }
+
+    /// Read the `written` axes back as the new baseline, so cells show what was stored rather
+    /// than what was typed, which can differ once converted to the stored integer. Axes that
+    /// were not written, such as locked ones, keep their edits.
+    pub fn read_back(&mut self, written: &[TableAxis]) -> std::io::Result<()> {
+        self.pending = None;
+        self.typed_edits.clear();
+        self.history.clear();
+        let mut rounded = 0;
+        for &axis in written {
+            let stored = match axis {
+                TableAxis::X => self.table.x.read_strings(&mut self.source)?,
+                TableAxis::Y => self.table.y.read_strings(&mut self.source)?,
+                TableAxis::Z => self.read_data()?,
+            };
+            let source = match axis {
+                TableAxis::X => &self.table.x,
+                TableAxis::Y => &self.table.y,
+                TableAxis::Z => &self.table.z,
+            };
+            let (values, baseline) = match axis {
+                TableAxis::X => (&mut self.x_head, &mut self.baseline.x_head),
+                TableAxis::Y => (&mut self.y_head, &mut self.baseline.y_head),
+                TableAxis::Z => (&mut self.data, &mut self.baseline.data),
+            };
+            // Compared at display precision so only visible rounding is counted
+            rounded += values
+                .iter()
+                .zip(&stored)
+                .filter(|(typed, stored)| {
+                    let typed = typed
+                        .trim()
+                        .parse()
+                        .map_or(typed.to_string(), |v| source.format(v));
+                    differs(&typed, stored)
+                })
+                .count();
+            values.clone_from(&stored);
+            *baseline = stored;
+        }
+        self.rounded_note = (rounded > 0).then(|| {
+            format!("{rounded} cells were rounded to the nearest value that can be stored")
+        });
+        self.chart_stale = true;
+        self.update_data_range();
+        self.update_widths();
+        Ok(())
+    }
+
+    /// Restore all cells to the baseline values without touching the binary.
+    pub fn revert(&mut self) {
+        self.x_head = self.baseline.x_head.clone();
+        self.y_head = self.baseline.y_head.clone();
+        self.data = self.baseline.data.clone();
+        self.invalid.clear();
+        self.typed_edits.clear();
+        self.history.clear();
+        self.pending = None;
+        self.update_data_range();
+        self.update_widths();
+        self.chart_stale = true;
+        self.refresh_chart();
+    }
+
+    /// Read the values from the binary again, discarding any edits.
+    pub fn reload(&mut self) -> std::io::Result<()> {
+        self.baseline = Baseline {
+            x_head: self.table.x.read_strings(&mut self.source)?,
+            y_head: self.table.y.read_strings(&mut self.source)?,
+            data: self.read_data()?,
+        };
+        self.revert();
+        Ok(())
+    }
+
+    /// Read the values from the binary again after another pane wrote over part of the table.
+    /// Edited cells keep their edits, only the baseline they are compared to changes.
+    pub fn refresh_unedited(&mut self) -> std::io::Result<()> {
+        let baseline = Baseline {
+            x_head: self.table.x.read_strings(&mut self.source)?,
+            y_head: self.table.y.read_strings(&mut self.source)?,
+            data: self.read_data()?,
+        };
+        for (values, old, new) in [
+            (&mut self.x_head, &self.baseline.x_head, &baseline.x_head),
+            (&mut self.y_head, &self.baseline.y_head, &baseline.y_head),
+            (&mut self.data, &self.baseline.data, &baseline.data),
+        ] {
+            for ((value, old), new) in values.iter_mut().zip(old).zip(new) {
+                if !differs(value, old) {
+                    value.clone_from(new);
+                }
+            }
+        }
+        self.baseline = baseline;
+        self.update_data_range();
+        self.update_widths();
+        self.chart_stale = true;
+        self.refresh_chart();
+        Ok(())
+    }
+
+    /// Show the data with a fixed number of decimals, or the ones worked out from the expression
+    /// with `None`. Unedited cells are read again at the new precision so they do not become
+    /// edits, edited cells keep what was typed.
+    pub fn set_decimals(&mut self, decimals: Option<usize>) -> std::io::Result<()> {
+        self.table.z.set_decimals(decimals);
+        self.refresh_unedited()
+    }
+
+    /// Start editing the note of a cell, or stop with `None`.
+    pub fn open_note(&mut self, cell: Option<(usize, usize)>) {
+        self.note_editor =
+            cell.map(|cell| (cell, self.notes.get(&cell).cloned().unwrap_or_default()));
+    }
+
+    /// Start editing the note of the cell last typed in or moved to.
+    pub fn open_current_note(&mut self) {
+        self.open_note(self.current().map(|i| self.coords(i)));
+    }
+
+    /// Finish editing a note, returning the cell and its text, `None` when it was cleared.
+    pub fn save_note(&mut self) -> Option<((usize, usize), Option<String>)> {
+        let (cell, text) = self.note_editor.take()?;
+        let text = text.trim();
+        if text.is_empty() {
+            self.notes.remove(&cell);
+            Some((cell, None))
+        } else {
+            self.notes.insert(cell, text.to_string());
+            Some((cell, Some(text.to_string())))
+        }
+    }
+
+    /// Reload, then scroll back to where the grid was and focus the same cell again.
+    pub fn reload_in_place(&mut self) -> std::io::Result<Task<Message>> {
+        self.reload()?;
+        let scroll = scrollable::scroll_to(
+            self.scroll_id(),
+            AbsoluteOffset {
+                x: self.scroll_x,
+                y: self.scroll_y,
+            },
+        );
+        Ok(match self.focused {
+            Some(cell) => scroll.chain(text_input::focus(self.cell_id(cell))),
+            None => scroll,
+        })
+    }
+
+    /// Read an axis as two's complement or not, values are read again so edits are discarded.
+    pub fn toggle_signed(&mut self, axis: TableAxis) -> std::io::Result<()> {
+        let axis = match axis {
+            TableAxis::X => &mut self.table.x,
+            TableAxis::Y => &mut self.table.y,
+            TableAxis::Z => &mut self.table.z,
+        };
+        axis.set_signed(!axis.signed());
+        self.reload()
+    }
+
+    pub fn edit_display(&mut self, field: DisplayField, value: String) {
+        match field {
+            DisplayField::Scale => self.display_scale = value,
+            DisplayField::Offset => self.display_offset = value,
+        }
+    }
+
+    /// Show the data scaled and offset from its converted value, values are read again so
+    /// edits are discarded.
+    pub fn apply_display_scale(&mut self) -> anyhow::Result<()> {
+        let (Ok(scale), Ok(offset)) = (
+            self.display_scale.trim().parse::<f64>(),
+            self.display_offset.trim().parse::<f64>(),
+        ) else {
+            bail!("Display scale and offset must be numbers");
+        };
+        if scale == 0.0 || !scale.is_finite() || !offset.is_finite() {
+            bail!("Display scale must be a finite number other than zero");
+        }
+        self.table.z.set_display(DisplayScale { scale, offset });
+        Ok(self.reload()?)
+    }
+
+    /// Mark the chart point of a data cell, header cells have no point and clear it.
+    fn highlight(&mut self, source: EditSource) {
+        let width = self.display_size().0;
+        let cell = match self.to_display(source) {
+            EditSource::Data(i) => Some((i / width, i % width)),
+            EditSource::XHead(_) | EditSource::YHead(_) => None,
+        };
+        if self.focused != Some(source) {
+            self.formula = None;
+        }
+        self.focused = Some(source);
+        self.chart.set_highlight(cell);
+    }
+
+    /// Data cell last typed in or moved to
+    fn current(&self) -> Option<usize> {
+        match self.focused? {
+            EditSource::Data(i) => Some(i),
+            EditSource::XHead(_) | EditSource::YHead(_) => None,
+        }
+    }
+
+    /// Text typed into the formula bar. Plain values are applied straight away like typing
+    /// in the cell, relative expressions wait for [`TableView::commit_formula`].
+    pub fn edit_formula(&mut self, value: String) -> std::io::Result<()> {
+        let Some(source) = self.focused else {
+            return Ok(());
+        };
+        if relative_value(&value, 0.0).is_some() {
+            self.formula = Some(value);
+        } else {
+            self.formula = None;
+            self.edit(source, value)?;
+        }
+        Ok(())
+    }
+
+    /// Apply a relative expression typed into the formula bar to the focused cell.
+    pub fn commit_formula(&mut self) -> anyhow::Result<()> {
+        let Some(source) = self.focused else {
+            return Ok(());
+        };
+        if let Some(formula) = self.formula.take() {
+            self.load_cell(source)?;
+            let Ok(current) = self.value(source).trim().parse::<f64>() else {
+                bail!("{} is not a number", self.describe(source));
+            };
+            let value = match relative_value(&formula, current) {
+                Some(Ok(value)) => value,
+                Some(Err(e)) => bail!("Invalid expression {formula}: {e}"),
+                None => return Ok(()),
+            };
+            let old = self.value(source).to_string();
+            let new = self.axis(source).format(value);
+            self.typed_edits.remove(&source);
+            self.set_value(source, new.clone());
+            self.history.push(source, old, new);
+        }
+        self.commit_edit();
+        Ok(())
+    }
+
+    /// (row, column) of a data cell index
+    fn coords(&self, index: usize) -> (usize, usize) {
+        (index / self.x_head.len(), index % self.x_head.len())
+    }
+
+    /// Format a data value the same way values read from the binary are.
+    pub fn format_data(&self, value: f64) -> String {
+        self.table.z.format(value)
+    }
+
+    pub fn pane_id(&self) -> usize {
+        self.pane_id
+    }
+
+    fn cell_id(&self, source: EditSource) -> text_input::Id {
+        text_input::Id::new(format!("cell-{}-{source:?}", self.pane_id))
+    }
+
+    fn scroll_id(&self) -> scrollable::Id {
+        scrollable::Id::new(format!("cells-{}", self.pane_id))
+    }
+
+    /// Focus the cell next to `source`, scrolling it into view if needed. Edits are applied as
+    /// they are typed so there is nothing else to commit.
+    pub fn move_focus(
+        &mut self,
+        source: EditSource,
+        direction: MoveDirection,
+    ) -> std::io::Result<Task<Message>> {
+        let (width, height) = self.display_size();
+        match next_cell(self.to_display(source), direction, width, height) {
+            Some(next) => self.focus_cell(self.stored_at(next)),
+            None => Ok(Task::none()),
+        }
+    }
+
+    /// Focus the cell drawn at a chart point, `(row, column)` as the chart shows them.
+    pub fn focus_chart_point(
+        &mut self,
+        (row, col): (usize, usize),
+    ) -> std::io::Result<Task<Message>> {
+        let (width, height) = self.display_size();
+        if row >= height || col >= width {
+            return Ok(Task::none());
+        }
+        self.focus_cell(self.stored_at(EditSource::Data(row * width + col)))
+    }
+
+    /// Focus the data cell with the smallest or largest value.
+    pub fn jump_to(&mut self, extreme: Extreme) -> std::io::Result<Task<Message>> {
+        let invalid = |i| self.invalid.contains(&EditSource::Data(i));
+        match find_extreme(&self.z_values()?, invalid, extreme) {
+            Some(i) => self.focus_cell(EditSource::Data(i)),
+            None => Ok(Task::none()),
+        }
+    }
+
+    /// Height of the rows above the first data row
+    fn header_height(&self) -> f32 {
+        ROW_HEIGHT + if self.show_indices { INDEX_HEIGHT } else { 0.0 }
+    }
+
+    /// Width of the columns left of the first data column
+    fn header_width(&self) -> f32 {
+        self.header_column_width + if self.show_indices { INDEX_WIDTH } else { 0.0 }
+    }
+
+    /// Offset to scroll to so `start..start + size` is visible, `None` if it already is.
+    fn scroll_into_view(start: f32, size: f32, offset: f32, visible: f32) -> Option<f32> {
+        if start < offset {
+            Some(start)
+        } else if start + size > offset + visible {
+            Some(start + size - visible)
+        } else {
+            None
+        }
+    }
+
+    fn focus_cell(&mut self, cell: EditSource) -> std::io::Result<Task<Message>> {
+        self.highlight(cell);
+
+        let width = self.display_size().0;
+        let (top, left) = match self.to_display(cell) {
+            EditSource::XHead(x) => (0.0, Some(x)),
+            EditSource::YHead(y) => (self.header_height() + y as f32 * ROW_HEIGHT, None),
+            EditSource::Data(i) => (
+                self.header_height() + (i / width) as f32 * ROW_HEIGHT,
+                Some(i % width),
+            ),
+        };
+        let left = left.map_or(0.0, |x| {
+            self.header_width() + self.column_widths[..x].iter().sum::<f32>()
+        });
+        let height = self.viewport_height.unwrap_or(DEFAULT_VIEWPORT_HEIGHT);
+        let width = self.viewport_width.unwrap_or(DEFAULT_VIEWPORT_WIDTH);
+        let scroll_y = Self::scroll_into_view(top, ROW_HEIGHT, self.scroll_y, height);
+        let scroll_x = Self::scroll_into_view(left, self.cell_width(cell), self.scroll_x, width);
+
+        let scroll = if scroll_x.is_some() || scroll_y.is_some() {
+            self.scroll_x = scroll_x.unwrap_or(self.scroll_x);
+            self.scroll_y = scroll_y.unwrap_or(self.scroll_y);
+            // Rows at the new offset are read now so the focused cell is not shown empty
+            self.load_visible()?;
+            scrollable::scroll_to(
+                self.scroll_id(),
+                AbsoluteOffset {
+                    x: self.scroll_x,
+                    y: self.scroll_y,
+                },
+            )
+        } else {
+            Task::none()
+        };
+        Ok(scroll.chain(text_input::focus(self.cell_id(cell))))
+    }
+
+    pub fn scrolled(&mut self, viewport: Viewport) -> std::io::Result<()> {
+        self.scroll_x = viewport.absolute_offset().x;
+        self.scroll_y = viewport.absolute_offset().y;
+        self.viewport_height = Some(viewport.bounds().height);
+        self.viewport_width = Some(viewport.bounds().width);
+        self.load_visible()
+    }
+
+    /// Set the operating point to look up, reading the rows it is interpolated from.
+    pub fn set_lookup(&mut self, x: String, y: String) -> std::io::Result<()> {
+        self.lookup_x = x;
+        self.lookup_y = y;
+        self.load_visible()
+    }
+
+    pub fn select(&mut self, action: SelectAction) {
+        match action {
+            SelectAction::Start(i) => {
+                let cell = self.coords(i);
+                self.selection = Some(Selection {
+                    anchor: cell,
+                    end: cell,
+                });
+                self.selecting = true;
+            }
+            SelectAction::Hover(i) => {
+                let cell = self.coords(i);
+                if let (true, Some(selection)) = (self.selecting, self.selection.as_mut()) {
+                    selection.end = cell;
+                }
+            }
+            SelectAction::End => {
+                // A right click without dragging opens the note of that cell
+                if let (true, Some(selection)) = (self.selecting, self.selection) {
+                    if selection.anchor == selection.end {
+                        self.open_note(Some(selection.anchor));
+                    }
+                }
+                self.selecting = false;
+            }
+            SelectAction::Clear => {
+                self.selection = None;
+                self.selecting = false;
+            }
+        }
+    }
+
+    /// Data values by row and column, `None` where a cell is not a number.
+    fn parsed_grid(&self) -> Vec<Vec<Option<f64>>> {
+        self.data
+            .chunks(self.x_head.len())
+            .map(|row| row.iter().map(|v| v.parse().ok()).collect())
+            .collect()
+    }
+
+    /// Rows and columns covered by a target, the selection target is the whole table if
+    /// nothing is selected.
+    fn target_region(&self, target: Target) -> (RangeInclusive<usize>, RangeInclusive<usize>) {
+        let all_rows = 0..=self.y_head.len().saturating_sub(1);
+        let all_cols = 0..=self.x_head.len().saturating_sub(1);
+        match (target, self.selection) {
+            (Target::Selection, Some(s)) => (s.rows(), s.cols()),
+            (Target::Selection, None) => (all_rows, all_cols),
+            (Target::Row(r), _) => (r..=r, all_cols),
+            (Target::Column(c), _) => (all_rows, c..=c),
+        }
+    }
+
+    /// Fail listing the coordinates of any cells that are not numbers.
+    fn require_numeric(
+        grid: &[Vec<Option<f64>>],
+        cells: impl Iterator<Item = (usize, usize)>,
+    ) -> anyhow::Result<()> {
+        let invalid: Vec<String> = cells
+            .filter(|&(r, c)| grid[r][c].is_none())
+            .map(|(r, c)| format!("({r}, {c})"))
+            .collect();
+        if !invalid.is_empty() {
+            bail!("Cells are not numbers: {}", invalid.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Replace the cells of `rows` x `cols` with the values from `grid`.
+    fn apply_grid(
+        &mut self,
+        grid: &[Vec<f64>],
+        rows: RangeInclusive<usize>,
+        cols: RangeInclusive<usize>,
+    ) {
+        let width = self.x_head.len();
+        for r in rows {
+            for c in cols.clone() {
+                self.data[r * width + c] = self.format_data(grid[r][c]);
+                self.invalid.remove(&EditSource::Data(r * width + c));
+                self.typed_edits.remove(&EditSource::Data(r * width + c));
+            }
+        }
+        self.update_data_range();
+        self.update_widths();
+        self.chart_stale = true;
+        self.refresh_chart();
+    }
+
+    /// Fill the selected cells by interpolating between the selection's edges.
+    pub fn interpolate(&mut self) -> anyhow::Result<()> {
+        let Some(selection) = self.selection else {
+            bail!("No cells selected to interpolate");
+        };
+        let (rows, cols) = (selection.rows(), selection.cols());
+        let (row_start, col_start) = (*rows.start(), *cols.start());
+        self.load_rows(row_start..rows.end() + 1)?;
+        let grid = self.parsed_grid();
+
+        let sources = self
+            .interpolation
+            .sources(rows.clone().count(), cols.clone().count());
+        Self::require_numeric(
+            &grid,
+            sources
+                .into_iter()
+                .map(|(r, c)| (r + row_start, c + col_start)),
+        )?;
+
+        let mut block: Vec<Vec<f64>> = grid[rows.clone()]
+            .iter()
+            .map(|row| {
+                row[cols.clone()]
+                    .iter()
+                    .map(|v| v.unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        interpolate(&mut block, self.interpolation);
+
+        let mut grid: Vec<Vec<f64>> = grid
+            .into_iter()
+            .map(|row| row.into_iter().map(|v| v.unwrap_or_default()).collect())
+            .collect();
+        for (r, row) in block.into_iter().enumerate() {
+            grid[r + row_start][col_start..col_start + row.len()].copy_from_slice(&row);
+        }
+        self.apply_grid(&grid, rows, cols);
+        Ok(())
+    }
+
+    /// Apply a bulk operation to the targeted cells, results are clamped to what can be stored.
+    pub fn apply_bulk_op(&mut self, op: BulkOp, target: Target) -> anyhow::Result<()> {
+        let (rows, cols) = self.target_region(target);
+        // Smoothing also reads the rows on either side
+        self.load_rows(rows.start().saturating_sub(1)..rows.end() + 2)?;
+        let grid = self.parsed_grid();
+
+        match op {
+            BulkOp::Smooth(strength) => {
+                // The kernel reads one cell past the region on every side
+                let last_row = grid.len().saturating_sub(1);
+                let last_col = self.x_head.len().saturating_sub(1);
+                let read_rows = rows.start().saturating_sub(1)..=(rows.end() + 1).min(last_row);
+                let read_cols = cols.start().saturating_sub(1)..=(cols.end() + 1).min(last_col);
+                Self::require_numeric(
+                    &grid,
+                    read_rows.flat_map(|r| read_cols.clone().map(move |c| (r, c))),
+                )?;
+
+                let mut grid: Vec<Vec<f64>> = grid
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+                    .collect();
+                smooth(&mut grid, rows.clone(), cols.clone(), strength);
+                self.apply_grid(&grid, rows, cols);
+            }
+            op => {
+                let cells = || rows.clone().flat_map(|r| cols.clone().map(move |c| (r, c)));
+                if op.reads_cells() {
+                    Self::require_numeric(&grid, cells())?;
+                }
+
+                let range = self.table.z.range();
+                let mut grid: Vec<Vec<f64>> = grid
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+                    .collect();
+                for (r, c) in cells() {
+                    if let Some(value) = op.apply(grid[r][c]) {
+                        grid[r][c] = clamp_to(value, range);
+                    }
+                }
+                self.apply_grid(&grid, rows, cols);
+                self.header_menu = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Combine clipboard text with the cells starting at the top left of the selection, or the
+    /// current cell. Values outside the selection or table are left out.
+    pub fn paste(&mut self, text: &str) -> anyhow::Result<()> {
+        let pasted = parse_tsv(text);
+        // Pasted rows follow the rows on screen
+        let pasted = if self.transposed {
+            transpose_rows(&pasted)
+        } else {
+            pasted
+        };
+        let pasted_rows = pasted.len();
+        let pasted_cols = pasted.iter().map(Vec::len).max().unwrap_or(0);
+        if pasted_rows == 0 || pasted_cols == 0 {
+            bail!("Clipboard has no values to paste");
+        }
+
+        let (height, width) = (self.y_head.len(), self.x_head.len());
+        let (start, limit) = match self.selection {
+            Some(s) => (
+                (*s.rows().start(), *s.cols().start()),
+                (*s.rows().end() + 1, *s.cols().end() + 1),
+            ),
+            None => (
+                self.current().map_or((0, 0), |i| self.coords(i)),
+                (height, width),
+            ),
+        };
+        let rows = start.0..=(start.0 + pasted_rows).min(limit.0) - 1;
+        let cols = start.1..=(start.1 + pasted_cols).min(limit.1) - 1;
+        let (fit_rows, fit_cols) = (rows.clone().count(), cols.clone().count());
+
+        let cells = || rows.clone().flat_map(|r| cols.clone().map(move |c| (r, c)));
+        let not_numbers: Vec<String> = cells()
+            .filter(|&(r, c)| {
+                let pasted = pasted[r - start.0].get(c - start.1);
+                !pasted.is_some_and(Option::is_some)
+            })
+            .map(|(r, c)| format!("({}, {})", r - start.0, c - start.1))
+            .collect();
+        if !not_numbers.is_empty() {
+            bail!("Pasted cells are not numbers: {}", not_numbers.join(", "));
+        }
+        self.load_rows(*rows.start()..rows.end() + 1)?;
+        let grid = self.parsed_grid();
+        if self.paste_mode.reads_cells() {
+            Self::require_numeric(&grid, cells())?;
+        }
+
+        let range = self.table.z.range();
+        let mut grid: Vec<Vec<f64>> = grid
+            .into_iter()
+            .map(|row| row.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+            .collect();
+        for (r, c) in cells() {
+            let pasted = pasted[r - start.0][c - start.1].unwrap_or_default();
+            grid[r][c] = clamp_to(self.paste_mode.combine(grid[r][c], pasted), range);
+        }
+        self.apply_grid(&grid, rows, cols);
+
+        let selected = self.selection.map(|s| (s.rows().count(), s.cols().count()));
+        self.paste_notice = if (fit_rows, fit_cols) != (pasted_rows, pasted_cols) {
+            Some(format!(
+                "Pasted {pasted_rows}x{pasted_cols} values, only {fit_rows}x{fit_cols} fit"
+            ))
+        } else if selected.is_some_and(|size| size != (fit_rows, fit_cols)) {
+            Some(format!(
+                "Pasted {pasted_rows}x{pasted_cols} values into a larger selection"
+            ))
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Combine one value with every selected cell using the paste mode.
+    pub fn paste_value(&mut self, value: f64) -> anyhow::Result<()> {
+        let Some(selection) = self.selection else {
+            bail!("No cells selected to paste into");
+        };
+        if !value.is_finite() {
+            bail!("Cannot paste {value}, it is not a finite number");
+        }
+        let (rows, cols) = (selection.rows(), selection.cols());
+        let cells = || rows.clone().flat_map(|r| cols.clone().map(move |c| (r, c)));
+        self.load_rows(*rows.start()..rows.end() + 1)?;
+        let grid = self.parsed_grid();
+        if self.paste_mode.reads_cells() {
+            Self::require_numeric(&grid, cells())?;
+        }
+
+        let range = self.table.z.range();
+        let mut grid: Vec<Vec<f64>> = grid
+            .into_iter()
+            .map(|row| row.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+            .collect();
+        for (r, c) in cells() {
+            grid[r][c] = clamp_to(self.paste_mode.combine(grid[r][c], value), range);
+        }
+        self.apply_grid(&grid, rows, cols);
+        self.paste_notice = None;
+        Ok(())
+    }
+
+    fn operations_bar(&self) -> Element<Message> {
+        let selected = match self.selection {
+            Some(s) => format!(
+                "Selected rows {}-{}, columns {}-{}",
+                s.rows().start(),
+                s.rows().end(),
+                s.cols().start(),
+                s.cols().end()
+            ),
+            None => "Right click and drag to select cells".to_string(),
+        };
+
+        let bar = row![
+            text(selected),
+            pick_list(Interpolation::ALL, Some(self.interpolation), |mode| {
+                Message::SetInterpolation {
+                    pane: self.pane_id,
+                    mode,
+                }
+            }),
+            button("Interpolate").on_press_maybe(
+                self.selection
+                    .map(|_| Message::Interpolate { pane: self.pane_id })
+            ),
+            button("Smooth").on_press(Message::ApplyBulkOp {
+                pane: self.pane_id,
+                op: BulkOp::Smooth(self.smooth_strength / 100.0),
+                target: Target::Selection,
+            }),
+            slider(0.0..=100.0, self.smooth_strength, |strength| {
+                Message::SetSmoothStrength {
+                    pane: self.pane_id,
+                    strength,
+                }
+            })
+            .width(Length::Fixed(100.0)),
+            text(format!("{:.0}%", self.smooth_strength)),
+            button("Clear selection").on_press_maybe(self.selection.map(|_| Message::Select {
+                pane: self.pane_id,
+                action: SelectAction::Clear,
+            })),
+            pick_list(PasteMode::ALL, Some(self.paste_mode), |mode| {
+                Message::SetPasteMode {
+                    pane: self.pane_id,
+                    mode,
+                }
+            }),
+            button("Paste").on_press(Message::PasteClipboard { pane: self.pane_id }),
+            text(self.paste_notice.clone().unwrap_or_default()),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+
+        match self.header_menu {
+            Some(target) => column![bar, self.header_menu_bar(target)].spacing(5).into(),
+            None => bar.into(),
+        }
+    }
+
+    fn note_bar(&self, (row, col): (usize, usize), draft: &str) -> Element<Message> {
+        let existing = self.notes.contains_key(&(row, col));
+        row![
+            text(format!("Note for row {row}, column {col}")),
+            TextInput::new("Note", draft)
+                .on_input(|text| Message::EditNote {
+                    pane: self.pane_id,
+                    text,
+                })
+                .on_submit(Message::SaveNote { pane: self.pane_id }),
+            button("Save").on_press(Message::SaveNote { pane: self.pane_id }),
+            button("Remove")
+                .on_press_maybe(existing.then_some(Message::RemoveNote { pane: self.pane_id })),
+            button("Cancel").on_press(Message::OpenNote {
+                pane: self.pane_id,
+                cell: None,
+            }),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Position and full precision value of the focused cell with a wide input for it.
+    fn formula_bar(&self) -> Element<Message> {
+        let Some(source) = self.focused else {
+            return text("Click a cell and type or move to it to edit it here").into();
+        };
+        let position = match source {
+            EditSource::XHead(c) => format!("x axis, column {c}"),
+            EditSource::YHead(r) => format!("y axis, row {r}"),
+            EditSource::Data(i) => {
+                let (r, c) = self.coords(i);
+                format!(
+                    "x = {}, y = {} (row {r}, column {c})",
+                    self.x_head[c], self.y_head[r]
+                )
+            }
+        };
+        // Value the stored integer converts back to, without the display rounding
+        let axis = self.axis(source);
+        let exact = self
+            .value(source)
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|v| {
+                axis.to_raw(v)
+                    .and_then(|raw| axis.from_raw(raw))
+                    .unwrap_or(v)
+            })
+            .map_or("-".to_string(), |v| v.to_string());
+
+        let value = match &self.formula {
+            Some(formula) => formula.clone(),
+            None => self.display_value(source),
+        };
+        let mut input = TextInput::new("Value, or +5, *1.03, =12.5", &value);
+        if self.editable(source) {
+            input = input
+                .on_input(|value| Message::EditFormula {
+                    pane: self.pane_id,
+                    value,
+                })
+                .on_submit(Message::CommitFormula { pane: self.pane_id });
+        }
+        row![
+            text(position),
+            text(format!("= {exact}")),
+            input.width(Length::Fixed(300.0)),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Inputs for an operating point and the value interpolated there from the current cells.
+    fn lookup_bar(&self) -> Element<Message> {
+        let one_row = self.y_head.len() == 1;
+        let values = TableValues {
+            x: parse_values(&self.x_head),
+            y: parse_values(&self.y_head),
+            data: parse_values(&self.data),
+        };
+        let x = self.lookup_x.trim().parse::<f64>();
+        let y = if one_row {
+            Ok(values.y[0])
+        } else {
+            self.lookup_y.trim().parse::<f64>()
+        };
+
+        let result = match (x, y) {
+            _ if self.lookup_x.is_empty() => String::new(),
+            (Ok(x), Ok(y)) => match values.interpolate(x, y) {
+                Some(lookup) => {
+                    let [(r0, c0), .., (r1, c1)] = lookup.cells;
+                    let cells = if one_row {
+                        format!("columns {c0}-{c1}")
+                    } else {
+                        format!("rows {r0}-{r1}, columns {c0}-{c1}")
+                    };
+                    format!(
+                        "= {} {} from {cells}",
+                        self.table.z.format(lookup.value),
+                        self.table.z.units
+                    )
+                }
+                None => "Values around this point are not numbers".to_string(),
+            },
+            _ => "Enter numbers to look up".to_string(),
+        };
+
+        let pane = self.pane_id;
+        let x_input = TextInput::new(&self.table.x.units, &self.lookup_x)
+            .on_input(move |x| Message::SetLookup {
+                pane,
+                x,
+                y: self.lookup_y.clone(),
+            })
+            .width(100);
+        let y_input = (!one_row).then(|| {
+            TextInput::new(&self.table.y.units, &self.lookup_y)
+                .on_input(move |y| Message::SetLookup {
+                    pane,
+                    x: self.lookup_x.clone(),
+                    y,
+                })
+                .width(100)
+        });
+
+        row![text("Lookup"), x_input]
+            .push_maybe(y_input)
+            .push(text(result))
+            .spacing(5)
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
+    fn toolbar(&self) -> Element<Message> {
+        let modified = self.modified_count();
+        let units: Vec<String> = [
+            ("x", &self.table.x),
+            ("y", &self.table.y),
+            ("z", &self.table.z),
+        ]
+        .into_iter()
+        .filter(|(_, axis)| !axis.units.is_empty())
+        .map(|(name, axis)| format!("{name}: {}", axis.units))
+        .collect();
+
+        container(
+            row![
+                button("Write").on_press_maybe(
+                    self.can_write()
+                        .then_some(Message::WriteTable { pane: self.pane_id })
+                ),
+                button("Revert").on_press_maybe(
+                    (modified > 0).then_some(Message::RevertTable { pane: self.pane_id })
+                ),
+                button("Reload").on_press(Message::ReloadTable { pane: self.pane_id }),
+                button("Export CSV").on_press(Message::ExportTable { pane: self.pane_id }),
+                button("Export image").on_press(Message::ExportTableImage { pane: self.pane_id }),
+                pick_list(CsvPrecision::ALL, Some(self.csv_precision), |precision| {
+                    Message::SetCsvPrecision {
+                        pane: self.pane_id,
+                        precision,
+                    }
+                }),
+                button("Import CSV").on_press(Message::ImportTable { pane: self.pane_id }),
+                button("Compare with reference")
+                    .on_press(Message::CompareTable { pane: self.pane_id }),
+                button("Jump to min").on_press(Message::JumpTo {
+                    pane: self.pane_id,
+                    extreme: Extreme::Min,
+                }),
+                button("Jump to max").on_press(Message::JumpTo {
+                    pane: self.pane_id,
+                    extreme: Extreme::Max,
+                }),
+                text(format!("{modified} cells modified")),
+                text(self.rounded_note.clone().unwrap_or_default()),
+                text(units.join("  ")),
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding(5)
+        .style(style::title_bar_active)
+        .into()
+    }
+
+    /// Add, multiply or set a whole row or column by the typed operand.
+    fn header_menu_bar(&self, target: Target) -> Element<Message> {
+        let label = match target {
+            Target::Row(r) => format!("Row {r} ({})", self.y_head[r]),
+            Target::Column(c) => format!("Column {c} ({})", self.x_head[c]),
+            Target::Selection => "Selection".to_string(),
+        };
+        let operand: Option<f64> = self.operand.trim().parse().ok();
+        let apply = |op: fn(f64) -> BulkOp| {
+            operand.map(|n| Message::ApplyBulkOp {
+                pane: self.pane_id,
+                op: op(n),
+                target,
+            })
+        };
+
+        row![
+            text(label),
+            TextInput::new("Value", &self.operand)
+                .on_input(|value| Message::SetOperand {
+                    pane: self.pane_id,
+                    value,
+                })
+                .width(Length::Fixed(100.0)),
+            button("Add").on_press_maybe(apply(BulkOp::Add)),
+            button("Multiply").on_press_maybe(apply(BulkOp::Multiply)),
+            button("Set").on_press_maybe(apply(BulkOp::Set)),
+            button("Cancel").on_press(Message::HeaderMenu {
+                pane: self.pane_id,
+                target: None,
+            }),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Checkboxes to read stored axes as signed, disabled while there are edits as
+    /// they would be lost.
+    fn signed_toggles(&self) -> Element<Message> {
+        let toggles = [
+            (TableAxis::X, "x", &self.table.x),
+            (TableAxis::Y, "y", &self.table.y),
+            (TableAxis::Z, "z", &self.table.z),
+        ]
+        .into_iter()
+        .filter(|(_, _, axis)| axis.writeable())
+        .map(|(id, name, axis)| {
+            checkbox(name, axis.signed())
+                .on_toggle_maybe(
+                    (!self.is_dirty()).then_some(move |_| Message::ToggleSigned {
+                        pane: self.pane_id,
+                        axis: Some(id),
+                    }),
+                )
+                .into()
+        });
+        row(std::iter::once(text("Signed:").into()).chain(toggles))
+            .spacing(10)
+            .into()
+    }
+
+    /// Inputs for the display scale of the data, disabled while there are edits as they
+    /// would be lost.
+    fn display_scale_bar(&self) -> Element<Message> {
+        let pane = self.pane_id;
+        let input = |value: &str, field| {
+            TextInput::new("", value).width(60).on_input_maybe(
+                (!self.is_dirty()).then_some(move |value| Message::EditDisplayScale {
+                    pane,
+                    field,
+                    value,
+                }),
+            )
+        };
+        let current = self.table.z.display();
+        let changed = self.display_scale.trim().parse() != Ok(current.scale)
+            || self.display_offset.trim().parse() != Ok(current.offset);
+        row![
+            text("Display:"),
+            input(&self.display_scale, DisplayField::Scale),
+            text("× value +"),
+            input(&self.display_offset, DisplayField::Offset),
+            button("Apply").on_press_maybe(
+                (changed && !self.is_dirty()).then_some(Message::ApplyDisplayScale { pane })
+            ),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Text and markers of a cell as drawn in an exported image
+    fn image_cell(&self, source: EditSource, background: Option<Color>) -> ImageCell {
+        let border = if self.invalid.contains(&source) {
+            Some(Color::from_rgb(0.85, 0.2, 0.2))
+        } else if self.is_modified(source) {
+            Some(Color::from_rgb(1.0, 0.6, 0.0))
+        } else {
+            None
+        };
+        ImageCell {
+            text: self.display_value(source),
+            background,
+            border,
+        }
+    }
+
+    /// The grid as it is shown, for exporting as an image. Every row is read to be drawn.
+    pub fn grid_image(&mut self, caption: String) -> std::io::Result<GridImage> {
+        self.load_rows(0..self.y_head.len())?;
+        let (width, height) = self.display_size();
+        let columns = (0..width)
+            .map(|x| self.image_cell(self.stored_at(EditSource::XHead(x)), None))
+            .collect();
+        let rows = (0..height)
+            .map(|y| {
+                let header = self.image_cell(self.stored_at(EditSource::YHead(y)), None);
+                let cells = (0..width)
+                    .map(|x| {
+                        let source = self.stored_at(EditSource::Data(y * width + x));
+                        let EditSource::Data(i) = source else {
+                            unreachable!("data cells are transposed to data cells")
+                        };
+                        let background = self.cell_background(i);
+                        self.image_cell(source, background)
+                    })
+                    .collect();
+                (header, cells)
+            })
+            .collect();
+        Ok(GridImage {
+            caption,
+            columns,
+            rows,
+            column_widths: self.column_widths.clone(),
+            header_width: self.header_column_width,
+        })
+    }
+
+    /// Heatmap colour of a data cell. In change mode increases and decreases from the
+    /// baseline are coloured apart instead, the same way as comparisons with the reference.
+    fn cell_background(&self, i: usize) -> Option<Color> {
+        if !self.heatmap {
+            return None;
+        }
+        if !self.show_change {
+            return Some(self.heat_color(&self.data[i]));
+        }
+        let (Ok(value), Ok(base)) = (
+            self.data[i].parse::<f64>(),
+            self.baseline.data[i].parse::<f64>(),
+        ) else {
+            return None;
+        };
+        match value.partial_cmp(&base) {
+            Some(std::cmp::Ordering::Greater) => Some(Color::from_rgba(1.0, 0.3, 0.2, 0.25)),
+            Some(std::cmp::Ordering::Less) => Some(Color::from_rgba(0.2, 0.5, 1.0, 0.25)),
+            _ => None,
+        }
+    }
+
+    fn heat_color(&self, value: &str) -> Color {
+        match (value.parse::<f64>(), self.data_range) {
+            // Translucent so text stays readable on both light and dark themes
+            (Ok(v), Some(range)) => self.chart.colormap.iced_color(normalize(v, range), 0.35),
+            _ => Color::from_rgba(0.5, 0.5, 0.5, 0.2),
+        }
+    }
+
+    fn cell<'a>(
+        &'a self,
+        source: EditSource,
+        writeable: bool,
+        state: CellState,
+    ) -> Element<'a, Message> {
+        let width = self.cell_width(source);
+        let mut text_box = TextInput::new("", &self.display_value(source))
+            .id(self.cell_id(source))
+            .size(self.density.text_size())
+            .padding(self.density.padding())
+            .width(Length::Fixed(width));
+
+        if writeable {
+            text_box = text_box
+                .on_submit(Message::CommitCellAndMove {
+                    pane: self.pane_id,
+                    source,
+                })
+                .on_input(move |value| Message::EditCell {
+                    value,
+                    pane: self.pane_id,
+                    source,
+                });
+        }
+
+        text_box = text_box.style(move |theme, status| {
+            let mut style = iced::widget::text_input::default(theme, status);
+            if !writeable {
+                style.value = iced::widget::text_input::default(theme, Status::Active).value;
+            }
+            if let Some(color) = state.background {
+                style.background = color.into();
+            }
+            if state.invalid {
+                style.border.color = theme.extended_palette().danger.strong.color;
+                style.border.width = 2.0;
+            } else if state.selected {
+                style.border.color = theme.extended_palette().primary.strong.color;
+                style.border.width = 2.0;
+            } else if state.modified {
+                style.border.color = Color::from_rgb(1.0, 0.6, 0.0);
+                style.border.width = 2.0;
+            }
+            style
+        });
+
+        let cell = mouse_area(text_box);
+        let header_menu = |target| Message::HeaderMenu {
+            pane: self.pane_id,
+            target: Some(target),
+        };
+
+        let cell = match source {
+            EditSource::Data(i) => {
+                let select = |action| Message::Select {
+                    pane: self.pane_id,
+                    action,
+                };
+                let mut cell = cell
+                    .on_right_press(select(SelectAction::Start(i)))
+                    .on_right_release(select(SelectAction::End));
+                if self.selecting {
+                    cell = cell.on_enter(select(SelectAction::Hover(i)));
+                }
+                cell
+            }
+            EditSource::XHead(x) => cell.on_right_press(header_menu(Target::Column(x))),
+            EditSource::YHead(y) => cell.on_right_press(header_menu(Target::Row(y))),
+        };
+
+        let note = match source {
+            EditSource::Data(i) => self.notes.get(&self.coords(i)),
+            EditSource::XHead(_) | EditSource::YHead(_) => None,
+        };
+        let info = match note {
+            Some(note) => format!("{}\nNote: {note}", self.cell_info(source)),
+            None => self.cell_info(source),
+        };
+        let cell = tooltip(cell, text(info), Position::FollowCursor).style(container::rounded_box);
+
+        let cell: Element<Message> = if note.is_some() {
+            let marker = text("◥").size(10).style(|_: &iced::Theme| text::Style {
+                color: Some(Color::from_rgb(1.0, 0.6, 0.0)),
+            });
+            let marker = container(marker)
+                .width(Length::Fixed(width))
+                .align_x(iced::alignment::Horizontal::Right)
+                .padding(2);
+            stack![cell, marker].into()
+        } else {
+            cell.into()
+        };
+
+        container(cell).height(Length::Fixed(ROW_HEIGHT)).into()
+    }
+
+    /// Whether data cells are checkboxes, raw and change modes keep the text cells.
+    fn shows_flags(&self) -> bool {
+        self.table.is_boolean() && !self.raw && !self.show_change
+    }
+
+    /// Checkbox for a data cell of a flag table, toggling it edits the cell to 1 or 0.
+    fn flag_cell(&self, source: EditSource, writeable: bool, state: CellState) -> Element<Message> {
+        let pane = self.pane_id;
+        let checked = self.value(source).parse::<f64>().is_ok_and(|v| v != 0.0);
+        let toggle = checkbox("", checked).on_toggle_maybe(writeable.then_some(move |on| {
+            Message::EditCell {
+                pane,
+                source,
+                value: if on { "1" } else { "0" }.to_string(),
+            }
+        }));
+        let cell = container(toggle)
+            .width(Length::Fixed(self.cell_width(source)))
+            .height(Length::Fixed(ROW_HEIGHT))
+            .align_x(iced::alignment::Horizontal::Center)
+            .align_y(iced::alignment::Vertical::Center)
+            .style(move |theme: &iced::Theme| {
+                let palette = theme.extended_palette();
+                let border = if state.invalid {
+                    Some(palette.danger.strong.color)
+                } else if state.selected {
+                    Some(palette.primary.strong.color)
+                } else if state.modified {
+                    Some(Color::from_rgb(1.0, 0.6, 0.0))
+                } else {
+                    None
+                };
+                container::Style {
+                    border: border.map_or_else(Default::default, |color| iced::Border {
+                        color,
+                        width: 2.0,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            });
+        tooltip(cell, text(self.cell_info(source)), Position::FollowCursor)
+            .style(container::rounded_box)
+            .into()
+    }
+
+    fn header_cell(&self, source: EditSource) -> Element<Message> {
+        let state = CellState {
+            modified: self.is_modified(source),
+            invalid: self.invalid.contains(&source),
+            ..Default::default()
+        };
+        self.cell(source, self.editable(source), state)
+    }
+
+    /// Row header and data cells of one displayed row
+    fn data_row(&self, y: usize) -> Element<Message> {
+        let data_writeable = self.table.z.writeable();
+        let flags = self.shows_flags();
+        let (width, _) = self.display_size();
+
+        let cells = (0..width).map(|x| {
+            let source = self.stored_at(EditSource::Data(y * width + x));
+            let EditSource::Data(i) = source else {
+                unreachable!("data cells are transposed to data cells")
+            };
+            let state = CellState {
+                background: self.cell_background(i),
+                selected: self.selection.is_some_and(|s| s.contains(self.coords(i))),
+                modified: self.is_modified(source),
+                invalid: self.invalid.contains(&source),
+            };
+            if flags {
+                self.flag_cell(source, data_writeable, state)
+            } else {
+                self.cell(source, data_writeable, state)
+            }
+        });
+
+        let header = self.header_cell(self.stored_at(EditSource::YHead(y)));
+        let header = if self.show_indices {
+            row![
+                container(index_label(y))
+                    .width(INDEX_WIDTH)
+                    .height(ROW_HEIGHT)
+                    .align_y(iced::alignment::Vertical::Center),
+                header
+            ]
+            .into()
+        } else {
+            header
+        };
+        row(std::iter::once(header).chain(cells)).into()
+    }
+
+    /// Only the rows in or near the visible part of the grid are built.
+    fn cell_grid(&self) -> Element<Message> {
+        let (width, total) = self.display_size();
+        let header = (0..width).map(|x| self.header_cell(self.stored_at(EditSource::XHead(x))));
+        let header =
+            row(std::iter::once(Space::new(self.header_width(), ROW_HEIGHT).into()).chain(header));
+        let indices = self.show_indices.then(|| {
+            let labels = (0..width).map(|x| {
+                container(index_label(x))
+                    .width(self.column_widths[x])
+                    .height(INDEX_HEIGHT)
+                    .align_x(iced::alignment::Horizontal::Center)
+                    .into()
+            });
+            row(std::iter::once(Space::with_width(self.header_width()).into()).chain(labels)).into()
+        });
+
+        let visible = visible_rows(
+            self.scroll_y - self.header_height(),
+            self.viewport_height.unwrap_or(DEFAULT_VIEWPORT_HEIGHT),
+            ROW_HEIGHT,
+            total,
+            OVERSCAN,
+        );
+
+        grid_body(
+            indices.into_iter().chain([header.into()]),
+            visible,
+            total,
+            |y| self.data_row(y),
+        )
+    }
+
+    /// Chart settings, only shown with the chart.
+    fn chart_controls(&self) -> Element<Message> {
+        column![
+            text("Chart width:"),
+            slider(CHART_FRACTION_RANGE, self.chart_fraction, |fraction| {
+                Message::SetChartWidth {
+                    pane: self.pane_id,
+                    fraction,
+                }
+            })
+            .step(0.05f32)
+            .width(Length::Fixed(300.0)),
+            checkbox("Histogram", self.show_histogram)
+                .on_toggle(|_| Message::ToggleHistogram { pane: self.pane_id }),
+            pick_list(Overlay::ALL, Some(self.overlay), |overlay| {
+                Message::SetComparison {
+                    pane: self.pane_id,
+                    overlay,
+                }
+            }),
+            pick_list(Colormap::ALL, Some(self.chart.colormap), |colormap| {
+                Message::SetColormap {
+                    pane: self.pane_id,
+                    colormap,
+                }
+            }),
+            iced::widget::text("Pitch:"),
+            iced::widget::slider(-MAX_PITCH..=MAX_PITCH, self.chart.pitch, |v| {
+                Message::GraphPitch(self.pane_id, v)
+            })
+            .step(PI / 300.0)
+            .width(Length::Fixed(300.0)),
+            iced::widget::text("Yaw:"),
+            iced::widget::slider(-PI..=PI, self.chart.yaw, |v| {
+                Message::GraphYaw(self.pane_id, v)
+            })
+            .step(PI / 300.0)
+            .width(Length::Fixed(300.0)),
+            iced::widget::text("Zoom:"),
+            iced::widget::slider(SCALE_RANGE, self.chart.scale, |v| {
+                Message::GraphScale(self.pane_id, v)
+            })
+            .step(0.01)
+            .width(Length::Fixed(300.0)),
+            row![
+                button("Export chart").on_press(Message::ExportChart { pane: self.pane_id }),
+                pick_list(ImageSize::ALL, Some(self.chart_image_size), |size| {
+                    Message::SetChartImageSize {
+                        pane: self.pane_id,
+                        size,
+                    }
+                }),
+            ]
+            .spacing(5),
+        ]
+        .into()
+    }
+
+    fn chart_panel(&self) -> Element<Message> {
+        if self.show_histogram {
+            ChartWidget::new(&self.histogram).into()
+        } else if self.table.is_boolean() {
+            text("Flag table, chart hidden").into()
+        } else if self.chart.too_small() {
+            text("Too few values to chart").into()
+        } else if self.chart.plottable() {
+            Element::from(ChartWidget::new(&self.chart))
+        } else {
+            text("Values not numeric, chart hidden").into()
+        }
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let chart_portion = (self.chart_fraction * 100.0).round() as u16;
+        let grid_width = if self.show_chart {
+            Length::FillPortion(100 - chart_portion)
+        } else {
+            Length::Fill
+        };
+        let body = row![
+            iced::widget::scrollable(
+                container(self.cell_grid()).padding(Padding::new(0.0).bottom(15).right(15)),
+            )
+            .id(self.scroll_id())
+            .width(grid_width)
+            .direction(Direction::Both {
+                vertical: Scrollbar::new(),
+                horizontal: Scrollbar::new(),
+            })
+            .on_scroll(|viewport| Message::TableScrolled {
+                pane: self.pane_id,
+                viewport,
+            }),
+            column![
+                button(if self.show_chart {
+                    "Hide chart"
+                } else {
+                    "Show chart"
+                })
+                .on_press(Message::ToggleChart { pane: self.pane_id }),
+                checkbox("Heatmap", self.heatmap)
+                    .on_toggle(|_| Message::ToggleHeatmap { pane: self.pane_id }),
+                checkbox("Raw hex", self.raw)
+                    .on_toggle(|_| Message::ToggleRaw { pane: self.pane_id }),
+                checkbox("Change from baseline", self.show_change)
+                    .on_toggle(|_| Message::ToggleChange { pane: self.pane_id }),
+                pick_list(DeltaMode::ALL, Some(self.change_mode), |mode| {
+                    Message::SetChangeMode {
+                        pane: self.pane_id,
+                        mode,
+                    }
+                }),
+                pick_list(
+                    Decimals::ALL,
+                    Some(Decimals::from(self.table.z.decimals())),
+                    |decimals| Message::SetDecimals {
+                        pane: self.pane_id,
+                        decimals,
+                    }
+                ),
+                pick_list(Density::ALL, Some(self.density), |density| {
+                    Message::SetDensity {
+                        pane: self.pane_id,
+                        density,
+                    }
+                }),
+                checkbox("Indices", self.show_indices)
+                    .on_toggle(|_| Message::ToggleIndices { pane: self.pane_id }),
+                checkbox("Lock axes", self.axes_locked)
+                    .on_toggle(|_| Message::ToggleAxesLocked { pane: self.pane_id }),
+                checkbox("Transpose", self.transposed)
+                    .on_toggle(|_| Message::ToggleTransposed { pane: self.pane_id }),
+                self.signed_toggles(),
+                self.display_scale_bar(),
+            ]
+            .push_maybe(self.show_chart.then(|| self.chart_controls())),
+        ]
+        .push_maybe(
+            self.show_chart
+                .then(|| container(self.chart_panel()).width(Length::FillPortion(chart_portion))),
+        );
+
+        column![self.toolbar(), self.operations_bar()]
+            .push_maybe(
+                self.note_editor
+                    .as_ref()
+                    .map(|(cell, draft)| self.note_bar(*cell, draft)),
+            )
+            .push(self.lookup_bar())
+            .push(self.formula_bar())
+            .push(body)
+            .spacing(5)
+            .padding(5)
+            .into()
+    }
+}
+
+/// Faint row or column number
+fn index_label<'a>(index: usize) -> Element<'a, Message> {
+    text(index.to_string())
+        .size(11)
+        .style(|theme: &iced::Theme| text::Style {
+            color: Some(theme.extended_palette().background.strong.color),
+        })
+        .into()
+}
+
+/// Position of `value` within `min..=max` as a number from 0 to 1.
+/// A flat range places everything in the middle.
+pub fn normalize(value: f64, (min, max): (f64, f64)) -> f64 {
+    if max - min <= f64::EPSILON {
+        0.5
+    } else {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct Chart2D {
+    pane_id: usize,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    z: Vec<Vec<f64>>,
+    /// Ranges of the finite values, computed when the data changes rather than every redraw
+    x_range: Range<f64>,
+    y_range: Range<f64>,
+    z_range: Range<f64>,
+    /// Position of every z value within `z_range` from 0 to 1, used for the surface colour
+    z_norm: Vec<Vec<f64>>,
+    /// Second set of z values drawn as a wireframe over the surface, `z_range` covers both
+    comparison: Option<Vec<Vec<f64>>>,
+    cache: Cache,
+    pitch: f64,
+    yaw: f64,
+    /// Zoom of the 3D projection
+    scale: f64,
+    /// Colours of the surface, also used for the cell heatmap
+    colormap: Colormap,
+    /// (row, column) of the cell being edited, drawn as a marked point
+    highlight: Option<(usize, usize)>,
+    /// Position of every drawn point in the widget with its (row, column), updated each time
+    /// the chart is drawn so a click can be matched to a cell
+    points: RefCell<Vec<ChartPoint>>,
+    caption: String,
+    x_label: ChartAxis,
+    y_label: ChartAxis,
+    z_label: ChartAxis,
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Times chart ranges were worked out on this thread, redraws must not add to it
+    static RANGE_UPDATES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Description and tick formatting of one chart axis
+#[derive(Debug, Clone)]
+struct ChartAxis {
+    units: String,
+    precision: Option<usize>,
+    /// Label names of a categorical axis, ticks at their index show them
+    names: Vec<String>,
+}
+
+impl ChartAxis {
+    fn new(axis: &Axis) -> Self {
+        Self {
+            units: axis.units.clone(),
+            precision: axis.precision(),
+            names: axis.names.clone(),
+        }
+    }
+
+    /// Tick label with the axis' display precision, user defined axes get at most 3 decimals.
+    fn tick(&self, value: f64) -> String {
+        if value.fract() == 0.0 && value >= 0.0 {
+            if let Some(name) = self.names.get(value as usize) {
+                return name.clone();
+            }
+        }
+        match self.precision {
+            Some(p) => format!("{value:.p$}"),
+            None => {
+                let label = format!("{value:.3}");
+                label
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            }
+        }
+    }
+
+    /// Tick label followed by the units, for the 3D chart which has no axis descriptions.
+    fn tick_with_units(&self, value: f64) -> String {
+        if self.units.is_empty() {
+            self.tick(value)
+        } else {
+            format!("{} {}", self.tick(value), self.units)
+        }
+    }
+}
+
+/// Radians the 3D chart turns per pixel dragged
+const DRAG_SENSITIVITY: f64 = 0.01;
+/// Pitch is kept short of the poles so the chart does not flip over
+const MAX_PITCH: f64 = FRAC_PI_2;
+const SCALE_RANGE: RangeInclusive<f64> = 0.2..=2.0;
+/// Relative change of the scale per scroll wheel line
+const SCALE_STEP: f64 = 0.1;
+
+/// Pitch and yaw after dragging by `(dx, dy)` pixels, pitch is clamped and yaw wraps around.
+fn rotate(pitch: f64, yaw: f64, dx: f32, dy: f32) -> (f64, f64) {
+    let pitch = (pitch + dy as f64 * DRAG_SENSITIVITY).clamp(-MAX_PITCH, MAX_PITCH);
+    let yaw = (yaw + dx as f64 * DRAG_SENSITIVITY + PI).rem_euclid(2.0 * PI) - PI;
+    (pitch, yaw)
+}
+
+/// Scale after scrolling `lines` lines, positive zooms in.
+fn zoom(scale: f64, lines: f32) -> f64 {
+    (scale * (1.0 + SCALE_STEP).powf(lines as f64)).clamp(*SCALE_RANGE.start(), *SCALE_RANGE.end())
+}
+
+/// Mouse state of the chart widget
+#[derive(Debug, Default)]
+pub struct ChartState {
+    /// Cursor position the last rotation was computed from while dragging
+    drag_from: Option<Point>,
+    /// Where the left button went down, releasing it close by is a click rather than a drag
+    pressed_at: Option<Point>,
+}
 
-        let mut rows: Vec<GridRow<Message>> = Vec::new();
-        let mut first_row = GridRow::with_elements(vec![Element::from("")]);
-        for x in self
-            .x_head
-            .iter()
-            .enumerate()
-            .map(|(x, xv)| self.cell(xv, EditSource::XHead(x), x_writeable))
-        {
-            first_row = first_row.push(x);
-        }
-        rows.push(first_row);
+/// Pixels the cursor may move between press and release for it to still be a click
+const CLICK_DISTANCE: f32 = 4.0;
+/// Pixels from a chart point a click may land to select its cell
+const PICK_RADIUS: f32 = 24.0;
 
-        let mut i = 0;
-        for (y, yv) in self.y_head.iter().enumerate() {
-            let mut grid_row = Vec::new();
-            grid_row.push(self.cell(yv, EditSource::YHead(y), y_writeable));
+/// Position of a drawn chart point in the widget and the (row, column) of its cell
+type ChartPoint = ((i32, i32), (usize, usize));
 
-            for _ in 0..self.x_head.len() {
-                grid_row.push(self.cell(&self.data[i], EditSource::Data(i), data_writeable));
-                i += 1;
-            }
+/// Cell of the point nearest to `cursor`, `None` when no point is within `radius` pixels.
+fn nearest_point(points: &[ChartPoint], cursor: Point, radius: f32) -> Option<(usize, usize)> {
+    points
+        .iter()
+        .map(|&((x, y), cell)| (Point::new(x as f32, y as f32).distance(cursor), cell))
+        .filter(|&(distance, _)| distance <= radius)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, cell)| cell)
+}
 
-            rows.push(GridRow::with_elements(grid_row));
-        }
+/// Largest fraction of cells that may be missing before the chart is not drawn at all
+const MAX_INVALID_FRACTION: f64 = 0.25;
+/// Shares of the pane width the chart can be given
+const CHART_FRACTION_RANGE: RangeInclusive<f32> = 0.2..=0.8;
+/// Colour of the values overlaid on the chart for comparison
+const COMPARISON_COLOR: plotters::style::RGBColor = plotters::style::RGBColor(220, 40, 40);
 
-        row![
-            iced::widget::scrollable(
-                container(Grid::with_rows(rows)).padding(Padding::new(0.0).bottom(15).right(15)),
-            )
-            .direction(Direction::Both {
-                vertical: Scrollbar::new(),
-                horizontal: Scrollbar::new(),
-            }),
-            column![
-                iced::widget::text("Pitch:"),
-                iced::widget::slider(-PI..=PI, self.chart.pitch, |v| {
-                    Message::GraphPitch(self.pane_id, v)
-                })
-                .step(PI / 300.0)
-                .width(Length::Fixed(300.0)),
-                iced::widget::text("Yaw:"),
-                iced::widget::slider(-PI..=PI, self.chart.yaw, |v| {
-                    Message::GraphYaw(self.pane_id, v)
-                })
-                .step(PI / 300.0)
-                .width(Length::Fixed(300.0))
-            ],
-            ChartWidget::new(&self.chart)
-        ]
-        .padding(5)
-        .into()
-    }
+/// Parse chart values, anything that is not a number becomes NaN and is left out of the chart.
+fn parse_values(values: &[String]) -> Vec<f64> {
+    values
+        .iter()
+        .map(|v| v.trim().parse().unwrap_or(f64::NAN))
+        .collect()
 }
 
-#[derive(Debug)]
-pub struct Chart2D {
-    x: Vec<f64>,
-    y: Vec<f64>,
-    z: Vec<Vec<f64>>,
-    cache: Cache,
-    pitch: f64,
-    yaw: f64,
+/// Split row by row values into exactly `height` rows of `width`, so indexing by axis
+/// positions cannot go out of bounds. Missing values are NaN and extra values are dropped.
+fn grid(values: Vec<f64>, width: usize, height: usize) -> Vec<Vec<f64>> {
+    let mut values = values.into_iter();
+    (0..height)
+        .map(|_| {
+            let mut row: Vec<f64> = values.by_ref().take(width).collect();
+            row.resize(width, f64::NAN);
+            row
+        })
+        .collect()
+}
+
+/// Range covered by the finite values, `0..1` if there are none.
+/// Plotters cannot map an empty range so when all values are equal it is widened around them.
+fn finite_range<'a>(values: impl Iterator<Item = &'a f64>) -> std::ops::Range<f64> {
+    let range = values
+        .filter(|v| v.is_finite())
+        .fold(None, |range, &v| match range {
+            None => Some(v..v),
+            Some(r) => Some(r.start.min(v)..r.end.max(v)),
+        })
+        .unwrap_or(0.0..1.0);
+    if range.end > range.start {
+        range
+    } else {
+        let margin = (range.start.abs() * 0.1).max(1.0);
+        range.start - margin..range.end + margin
+    }
 }
 
 impl Chart2D {
-    fn new(x: &[String], y: &[String], z: &[String]) -> Self {
-        let x: Vec<f64> = x.iter().map(|f| f.parse().unwrap()).collect();
-        let y: Vec<f64> = y.iter().map(|f| f.parse().unwrap()).collect();
-        let z_flat: Vec<f64> = z.iter().map(|f| f.parse().unwrap()).collect();
+    /// Draw the chart to a PNG at `path`, with the rotation, zoom and colours it is shown with.
+    pub fn render_png(&self, path: &Path, size: ImageSize) -> anyhow::Result<()> {
+        use plotters::prelude::*;
 
-        let z = z_flat.chunks(x.len()).map(|c| c.to_vec()).collect();
+        let area = BitMapBackend::new(path, (size.width, size.height)).into_drawing_area();
+        area.fill(&WHITE)?;
+        // Drawing records where points end up for clicks, those of the widget are kept
+        let points = self.points.borrow().clone();
+        self.draw_chart(&ChartState::default(), area.clone());
+        *self.points.borrow_mut() = points;
+        area.present()?;
+        Ok(())
+    }
 
-        Self {
-            x,
-            y,
-            z,
+    fn new(pane_id: usize, table: &Table, x: &[String], y: &[String], z: &[String]) -> Self {
+        let mut chart = Self {
+            pane_id,
+            x: Vec::new(),
+            y: Vec::new(),
+            z: Vec::new(),
+            x_range: 0.0..1.0,
+            y_range: 0.0..1.0,
+            z_range: 0.0..1.0,
+            z_norm: Vec::new(),
+            comparison: None,
             pitch: 0.5,
             yaw: 0.5,
+            scale: 0.7,
+            colormap: Colormap::default(),
+            highlight: None,
+            points: RefCell::new(Vec::new()),
             cache: Cache::new(),
-        }
+            caption: table.name.clone(),
+            x_label: ChartAxis::new(&table.x),
+            y_label: ChartAxis::new(&table.y),
+            z_label: ChartAxis::new(&table.z),
+        };
+        chart.set_data(x, y, z);
+        chart
     }
-    pub fn update(&mut self, x: &[String], y: &[String], z: &[String]) {
-        self.x = x.iter().map(|f| f.parse().unwrap()).collect();
-        self.y = y.iter().map(|f| f.parse().unwrap()).collect();
+    pub fn set_data(&mut self, x: &[String], y: &[String], z: &[String]) {
+        self.x = parse_values(x);
+        self.y = parse_values(y);
+        self.z = grid(parse_values(z), self.x.len(), self.y.len());
+        self.highlight = self
+            .highlight
+            .filter(|&(y, x)| y < self.y.len() && x < self.x.len());
 
-        let z_flat: Vec<f64> = z.iter().map(|f| f.parse().unwrap()).collect();
-        self.z = z_flat.chunks(x.len()).map(|c| c.to_vec()).collect();
+        self.update_ranges();
+    }
+    /// Values to overlay on the chart, ignored unless they have the same shape as the data.
+    pub fn set_comparison(&mut self, z: Option<&[String]>) {
+        self.comparison = z
+            .filter(|z| z.len() == self.x.len() * self.y.len())
+            .map(|z| grid(parse_values(z), self.x.len(), self.y.len()));
+        self.update_ranges();
+    }
+    fn update_ranges(&mut self) {
+        self.x_range = finite_range(self.x.iter());
+        self.y_range = finite_range(self.y.iter());
+        let comparison = self.comparison.iter().flatten().flatten();
+        self.z_range = finite_range(self.z.iter().flatten().chain(comparison));
+        let Range { start, end } = self.z_range;
+        let span = (end - start).max(f64::EPSILON);
+        self.z_norm = self
+            .z
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|z| ((z - start) / span).clamp(0.0, 1.0))
+                    .collect()
+            })
+            .collect();
 
         self.cache.clear();
+        #[cfg(test)]
+        RANGE_UPDATES.with(|n| n.set(n.get() + 1));
+    }
+    /// Whether there are fewer than two points, a single cell has nothing to draw
+    pub fn too_small(&self) -> bool {
+        self.x.len() * self.y.len() < 2 || self.z.iter().flatten().count() < 2
+    }
+    /// Whether enough values are numbers for the chart to mean anything
+    pub fn plottable(&self) -> bool {
+        let values = self.x.iter().chain(&self.y).chain(self.z.iter().flatten());
+        let (total, invalid) = values.fold((0, 0), |(total, invalid), v| {
+            (total + 1, invalid + usize::from(!v.is_finite()))
+        });
+        total > 0 && invalid as f64 <= total as f64 * MAX_INVALID_FRACTION
+    }
+    /// Whether all corners of the surface quad starting at row `y` column `x` can be drawn
+    fn quad_finite(&self, y: usize, x: usize) -> bool {
+        [self.x[x], self.x[x + 1], self.y[y], self.y[y + 1]]
+            .into_iter()
+            .chain([
+                self.z[y][x],
+                self.z[y][x + 1],
+                self.z[y + 1][x],
+                self.z[y + 1][x + 1],
+            ])
+            .all(f64::is_finite)
     }
     pub fn yaw(&mut self, yaw: f64) {
         self.yaw = yaw;
         self.cache.clear();
     }
     pub fn pitch(&mut self, pitch: f64) {
-        self.pitch = pitch;
+        self.pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
         self.cache.clear();
     }
-    fn x_range(&self) -> std::ops::Range<f64> {
-        *self.x.iter().min_by(|a, b| a.total_cmp(b)).unwrap()
-            ..*self.x.iter().max_by(|a, b| a.total_cmp(b)).unwrap()
+    /// Trade the x and y axis labels, for data that is passed in transposed.
+    pub fn swap_axes(&mut self) {
+        std::mem::swap(&mut self.x_label, &mut self.y_label);
+        self.cache.clear();
     }
-    fn y_range(&self) -> std::ops::Range<f64> {
-        *self.y.iter().min_by(|a, b| a.total_cmp(b)).unwrap()
-            ..*self.y.iter().max_by(|a, b| a.total_cmp(b)).unwrap()
+    /// Mark the point of a cell, ignored if it is outside the table.
+    pub fn set_highlight(&mut self, cell: Option<(usize, usize)>) {
+        let cell = cell.filter(|&(y, x)| y < self.y.len() && x < self.x.len());
+        if cell != self.highlight {
+            self.highlight = cell;
+            self.cache.clear();
+        }
     }
-    fn z_range(&self) -> std::ops::Range<f64> {
-        *self
-            .z
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.colormap = colormap;
+        self.cache.clear();
+    }
+    pub fn scale(&mut self, scale: f64) {
+        self.scale = scale.clamp(*SCALE_RANGE.start(), *SCALE_RANGE.end());
+        self.cache.clear();
+    }
+    /// Points of the line drawn for a single row or column, the breakpoint of each value
+    /// paired with the value. Points with a missing coordinate are left out.
+    fn line_points(&self, z: &[Vec<f64>]) -> Vec<(f64, f64)> {
+        let (domain, values): (&[f64], Vec<f64>) = if self.x.len() == 1 {
+            (
+                &self.y,
+                z.iter().filter_map(|row| row.first().copied()).collect(),
+            )
+        } else {
+            (&self.x, z.first().cloned().unwrap_or_default())
+        };
+        domain
             .iter()
-            .filter_map(|r| r.iter().min_by(|a, b| a.total_cmp(b)))
-            .min_by(|a, b| a.total_cmp(b))
-            .unwrap()
-            ..*self
-                .z
-                .iter()
-                .filter_map(|r| r.iter().max_by(|a, b| a.total_cmp(b)))
-                .max_by(|a, b| a.total_cmp(b))
-                .unwrap()
+            .copied()
+            .zip(values)
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .collect()
+    }
+    /// Lines between neighbouring points of `z` along both axes, for drawing it as a wireframe.
+    /// Lines to a point with a missing coordinate are left out.
+    fn wireframe<'a>(
+        &'a self,
+        z: &'a [Vec<f64>],
+    ) -> impl Iterator<Item = [(f64, f64, f64); 2]> + 'a {
+        let point = move |y: usize, x: usize| (self.x[x], z[y][x], self.y[y]);
+        let finite = |p: &(f64, f64, f64)| p.0.is_finite() && p.1.is_finite() && p.2.is_finite();
+        let (width, height) = (self.x.len(), self.y.len());
+        let along_x = (0..height).flat_map(move |y| (1..width).map(move |x| [(y, x - 1), (y, x)]));
+        let along_y = (1..height).flat_map(move |y| (0..width).map(move |x| [(y - 1, x), (y, x)]));
+        along_x
+            .chain(along_y)
+            .map(move |[a, b]| [point(a.0, a.1), point(b.0, b.1)])
+            .filter(move |segment| segment.iter().all(finite))
+    }
+    /// Whether the table is drawn as a line instead of a 3D surface
+    fn is_flat(&self) -> bool {
+        self.x.len() == 1 || self.y.len() == 1
+    }
+    /// Drawable surface quads as chart coordinates with their colour from 0 to 1, farthest
+    /// first so nearer quads are painted over them. `depth` is the projected depth of a point
+    /// where larger is farther from the viewer.
+    fn quads_by_depth(&self, depth: impl Fn(f64, f64, f64) -> i32) -> Vec<(Quad, f64)> {
+        let mut quads: Vec<(i64, Quad, f64)> = (0..self.y.len().saturating_sub(1))
+            .flat_map(|y| std::iter::repeat(y).zip(0..self.x.len().saturating_sub(1)))
+            .filter(|&(y, x)| self.quad_finite(y, x))
+            .map(|(y, x)| {
+                let cells = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+                let corners = cells.map(|(x, y)| (self.x[x], self.z[y][x], self.y[y]));
+                let depth = corners.iter().map(|&(x, z, y)| depth(x, z, y) as i64).sum();
+                let color = cells.iter().map(|&(x, y)| self.z_norm[y][x]).sum::<f64>() / 4.0;
+                (depth, corners, color)
+            })
+            .collect();
+        quads.sort_by_key(|q| std::cmp::Reverse(q.0));
+        quads
+            .into_iter()
+            .map(|(_, corners, color)| (corners, color))
+            .collect()
     }
 }
 
+/// Corners of one surface quad in chart coordinates
+type Quad = [(f64, f64, f64); 4];
+
 impl Chart<Message> for Chart2D {
-    type State = ();
+    type State = ChartState;
 
     #[inline]
     fn draw<R: plotters_iced::Renderer, F: Fn(&mut Frame)>(
@@ -217,6 +3365,93 @@ impl Chart<Message> for Chart2D {
     ) -> Geometry {
         renderer.draw_cache(&self.cache, bounds, draw_fn)
     }
+    /// Drag to rotate the 3D chart and scroll to zoom it, click a point to focus its cell.
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if let Some(position) = cursor.position_over(bounds) {
+                    state.pressed_at = Some(position);
+                    if !self.is_flat() {
+                        state.drag_from = Some(position);
+                    }
+                    return (event::Status::Captured, None);
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.drag_from = None;
+                if let Some(from) = state.pressed_at.take() {
+                    let clicked = cursor
+                        .position()
+                        .is_some_and(|position| position.distance(from) <= CLICK_DISTANCE);
+                    let message = cursor
+                        .position_in(bounds)
+                        .filter(|_| clicked)
+                        .and_then(|position| {
+                            nearest_point(&self.points.borrow(), position, PICK_RADIUS)
+                        })
+                        .map(|cell| Message::FocusChartPoint {
+                            pane: self.pane_id,
+                            cell,
+                        });
+                    return (event::Status::Captured, message);
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let Some(from) = state.drag_from.replace(position) {
+                    let (pitch, yaw) = rotate(
+                        self.pitch,
+                        self.yaw,
+                        position.x - from.x,
+                        position.y - from.y,
+                    );
+                    let message = Message::GraphRotate {
+                        pane: self.pane_id,
+                        pitch,
+                        yaw,
+                    };
+                    return (event::Status::Captured, Some(message));
+                }
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta })
+                if !self.is_flat() && cursor.is_over(bounds) =>
+            {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / ROW_HEIGHT,
+                };
+                let message = Message::GraphScale(self.pane_id, zoom(self.scale, lines));
+                return (event::Status::Captured, Some(message));
+            }
+            _ => {}
+        }
+        (event::Status::Ignored, None)
+    }
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if self.is_flat() {
+            if cursor.is_over(bounds) {
+                mouse::Interaction::Pointer
+            } else {
+                mouse::Interaction::Idle
+            }
+        } else if state.drag_from.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::Idle
+        }
+    }
     fn build_chart<DB: plotters_iced::DrawingBackend>(
         &self,
         _state: &Self::State,
@@ -224,29 +3459,19 @@ impl Chart<Message> for Chart2D {
     ) {
         use plotters::prelude::*;
 
-        if self.x.len() == 1 || self.y.len() == 1 {
-            let x = if self.x.len() == 1 { &self.y } else { &self.x };
-            let y = if self.y.len() == 1 {
-                &self.z[0]
+        if self.is_flat() {
+            // Values are always z, plotted along whichever axis has more than one breakpoint
+            let (domain_range, domain_label) = if self.x.len() == 1 {
+                (self.y_range.clone(), &self.y_label)
             } else {
-                &self.y
+                (self.x_range.clone(), &self.x_label)
             };
             let mut chart = builder
-                .x_label_area_size(28)
-                .y_label_area_size(28)
+                .caption(&self.caption, ("sans-serif", 16))
+                .x_label_area_size(40)
+                .y_label_area_size(50)
                 .margin(20)
-                .build_cartesian_2d(
-                    if self.x.len() == 1 {
-                        self.y_range()
-                    } else {
-                        self.x_range()
-                    },
-                    if self.y.len() == 1 {
-                        self.z_range()
-                    } else {
-                        self.y_range()
-                    },
-                )
+                .build_cartesian_2d(domain_range, self.z_range.clone())
                 .expect("failed to build chart");
             chart
                 .configure_mesh()
@@ -255,25 +3480,69 @@ impl Chart<Message> for Chart2D {
                 .axis_style(
                     ShapeStyle::from(plotters::style::colors::BLUE.mix(0.45)).stroke_width(1),
                 )
+                .x_desc(&domain_label.units)
+                .y_desc(&self.z_label.units)
+                .x_label_formatter(&|v| domain_label.tick(*v))
+                .y_label_formatter(&|v| self.z_label.tick(*v))
                 .draw()
                 .expect("failed to draw chart mesh");
-            let series = LineSeries::new(x.clone().into_iter().zip(y.clone()), BLACK);
+            let series = LineSeries::new(self.line_points(&self.z), BLACK);
+            *self.points.borrow_mut() = (0..self.y.len())
+                .flat_map(|y| (0..self.x.len()).map(move |x| (y, x)))
+                .filter_map(|(y, x)| {
+                    let domain = if self.x.len() == 1 {
+                        self.y[y]
+                    } else {
+                        self.x[x]
+                    };
+                    let z = self.z[y][x];
+                    (domain.is_finite() && z.is_finite())
+                        .then(|| (chart.backend_coord(&(domain, z)), (y, x)))
+                })
+                .collect();
 
             chart
                 .draw_series(series)
                 .expect("failed to draw chart data");
+            if let Some(comparison) = &self.comparison {
+                let style = ShapeStyle::from(COMPARISON_COLOR).stroke_width(2);
+                chart
+                    .draw_series(LineSeries::new(self.line_points(comparison), style))
+                    .expect("failed to draw chart data");
+            }
+
+            if let Some((y, x)) = self.highlight {
+                let point = if self.x.len() == 1 {
+                    (self.y[y], self.z[y][0])
+                } else {
+                    (self.x[x], self.z[0][x])
+                };
+                if point.0.is_finite() && point.1.is_finite() {
+                    chart
+                        .draw_series([
+                            Circle::new(point, 7, BLACK.filled()),
+                            Circle::new(point, 5, WHITE.filled()),
+                        ])
+                        .expect("failed to draw chart data");
+                }
+            }
         } else {
             let mut chart = builder
+                .caption(&self.caption, ("sans-serif", 16))
                 .x_label_area_size(28)
                 .y_label_area_size(28)
                 .margin(20)
-                .build_cartesian_3d(self.x_range(), self.z_range(), self.y_range())
+                .build_cartesian_3d(
+                    self.x_range.clone(),
+                    self.z_range.clone(),
+                    self.y_range.clone(),
+                )
                 .expect("failed to build chart");
 
             chart.with_projection(|mut pb| {
                 pb.pitch = self.pitch;
                 pb.yaw = self.yaw;
-                pb.scale = 0.7;
+                pb.scale = self.scale;
                 pb.into_matrix()
             });
 
@@ -281,109 +3550,925 @@ impl Chart<Message> for Chart2D {
                 .configure_axes()
                 .bold_grid_style(plotters::style::colors::BLUE.mix(0.1))
                 .light_grid_style(plotters::style::colors::BLUE.mix(0.05))
+                .x_formatter(&|v| self.x_label.tick_with_units(*v))
+                .y_formatter(&|v| self.z_label.tick_with_units(*v))
+                .z_formatter(&|v| self.y_label.tick_with_units(*v))
                 // .axis_panel_style(
                 //     ShapeStyle::from(plotters::style::colors::BLUE.mix(0.45)).stroke_width(1),
                 // )
                 .draw()
                 .expect("failed to draw chart mesh");
 
-            let iter = (0..(self.y.len() - 1))
-                .map(|y| std::iter::repeat(y).zip(0..(self.x.len() - 1)))
-                .flatten();
+            let coord = chart.as_coord_spec();
+            *self.points.borrow_mut() = (0..self.y.len())
+                .flat_map(|y| (0..self.x.len()).map(move |x| (y, x)))
+                .filter_map(|(y, x)| {
+                    let point = (self.x[x], self.z[y][x], self.y[y]);
+                    [point.0, point.1, point.2]
+                        .iter()
+                        .all(|v| v.is_finite())
+                        .then(|| (coord.translate(&point), (y, x)))
+                })
+                .collect();
+            let quads = self.quads_by_depth(|x, z, y| coord.projected_depth(&x, &z, &y));
 
-            chart
-                .draw_series(iter.clone().map(|(y, x)| {
-                    Polygon::new(
-                        [
-                            (self.x[x], self.z[y][x], self.y[y]),
-                            (self.x[x + 1], self.z[y][x + 1], self.y[y]),
-                            (self.x[x + 1], self.z[y + 1][x + 1], self.y[y + 1]),
-                            (self.x[x], self.z[y + 1][x], self.y[y + 1]),
-                        ],
-                        ShapeStyle {
-                            color: RGBAColor(
-                                (((self.z[y][x] + self.z[y + 1][x + 1]) / 2.0
-                                    - self.z_range().start)
-                                    / (self.z_range().end - self.z_range().start)
-                                    * 255.0) as u8,
-                                ((1.0
-                                    - (((self.z[y][x] + self.z[y + 1][x + 1]) / 2.0
-                                        - self.z_range().start)
-                                        / (self.z_range().end - self.z_range().start)))
-                                    * 255.0) as u8,
-                                0,
-                                0.5,
-                            ),
-                            filled: false,
-                            stroke_width: 10,
-                        },
-                    )
-                }))
-                .expect("failed to draw chart data");
-            let x_int = (self.x_range().end - self.x_range().start) / 300.0;
-            let y_int = (self.y_range().end - self.y_range().start) / 300.0;
-            chart
-                .draw_series(iter.clone().map(|(y, x)| {
-                    Polygon::new(
-                        [
-                            (self.x[x], self.z[y][x], self.y[y]),
-                            (self.x[x + 1], self.z[y][x + 1], self.y[y]),
-                            (self.x[x + 1], self.z[y][x + 1], self.y[y] + y_int),
-                            (self.x[x], self.z[y][x], self.y[y] + y_int),
-                        ],
-                        BLACK,
-                    )
-                }))
-                .unwrap();
-            chart
-                .draw_series(iter.clone().map(|(y, x)| {
-                    Polygon::new(
-                        [
-                            (self.x[x], self.z[y][x], self.y[y]),
-                            (self.x[x], self.z[y + 1][x], self.y[y + 1]),
-                            (self.x[x] + x_int, self.z[y + 1][x], self.y[y + 1]),
-                            (self.x[x] + x_int, self.z[y][x], self.y[y]),
-                        ],
-                        BLACK,
-                    )
-                }))
-                .unwrap();
-            chart
-                .draw_series(iter.clone().map(|(y, x)| {
-                    Polygon::new(
-                        [
-                            (self.x[x], self.z[y + 1][x], self.y[y + 1]),
-                            (self.x[x + 1], self.z[y + 1][x + 1], self.y[y + 1]),
-                            (self.x[x + 1], self.z[y + 1][x + 1], self.y[y + 1] + y_int),
-                            (self.x[x], self.z[y + 1][x], self.y[y + 1] + y_int),
-                        ],
-                        BLACK,
-                    )
-                }))
-                .unwrap();
-            chart
-                .draw_series(iter.map(|(y, x)| {
-                    Polygon::new(
-                        [
-                            (self.x[x + 1], self.z[y][x + 1], self.y[y]),
-                            (self.x[x + 1], self.z[y + 1][x + 1], self.y[y + 1]),
-                            (self.x[x + 1] + x_int, self.z[y + 1][x + 1], self.y[y + 1]),
-                            (self.x[x + 1] + x_int, self.z[y][x + 1], self.y[y]),
-                        ],
-                        BLACK,
+            for (corners, t) in quads {
+                let color = self.colormap.map(t);
+                chart
+                    .draw_series(std::iter::once(Polygon::new(corners, color.filled())))
+                    .expect("failed to draw chart data");
+                chart
+                    .draw_series(std::iter::once(PathElement::new(
+                        [&corners[..], &corners[..1]].concat(),
+                        BLACK.mix(0.5),
+                    )))
+                    .expect("failed to draw chart data");
+            }
+
+            if let Some(comparison) = &self.comparison {
+                chart
+                    .draw_series(
+                        self.wireframe(comparison)
+                            .map(|segment| PathElement::new(segment, COMPARISON_COLOR)),
                     )
-                }))
-                .unwrap();
-            chart
-                .draw_series(
-                    (0..self.y.len())
-                        .map(|y| std::iter::repeat(y).zip(0..self.x.len()))
-                        .flatten()
-                        .map(|(y, x)| {
-                            Circle::new((self.x[x], self.z[y][x], self.y[y]), 4, BLACK.filled())
-                        }),
-                )
-                .unwrap();
+                    .expect("failed to draw chart data");
+            }
+
+            if let Some((y, x)) = self.highlight {
+                let point = (self.x[x], self.z[y][x], self.y[y]);
+                if [point.0, point.1, point.2].iter().all(|v| v.is_finite()) {
+                    chart
+                        .draw_series([
+                            Circle::new(point, 7, BLACK.filled()),
+                            Circle::new(point, 5, WHITE.filled()),
+                        ])
+                        .expect("failed to draw chart data");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Seek};
+
+    use super::*;
+    use crate::definitions::AxisData;
+
+    fn owned(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn differs_numerically() {
+        assert!(!differs("12.50", "12.5"));
+        assert!(!differs(" 3", "3.0"));
+        assert!(differs("12.6", "12.5"));
+        assert!(differs("abc", "12.5"));
+        assert!(!differs("abc", "abc"));
+    }
+
+    #[test]
+    fn count_modified_cells() {
+        let baseline = owned(&["1", "2.5", "3", "4"]);
+        assert_eq!(
+            count_modified(&owned(&["1.0", "2.50", "3", "4"]), &baseline),
+            0
+        );
+        assert_eq!(
+            count_modified(&owned(&["1", "2.6", "3", "5"]), &baseline),
+            2
+        );
+        assert_eq!(count_modified(&owned(&["", "2.5", "x", "4"]), &baseline), 2);
+        assert_eq!(count_modified(&[], &baseline), 0);
+    }
+
+    /// 8x8 ignition table of the demo, data from -48 to 143.25
+    fn demo_view() -> TableView {
+        let table = crate::demo::definition().tables.remove(0);
+        TableView::new(0, table, FileGuard::from(crate::demo::binary())).unwrap()
+    }
+
+    #[test]
+    fn invalid_cells_block_writing() {
+        let mut view = demo_view();
+        view.edit(EditSource::Data(10), "12..5".to_string())
+            .unwrap();
+        view.edit(EditSource::Data(3), "500".to_string()).unwrap();
+        view.edit(EditSource::XHead(1), String::new()).unwrap();
+        assert!(!view.can_write());
+        let error = view.check_valid().unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Not writing, invalid values at: x axis 1, (0, 3), (1, 2)"
+        );
+    }
+
+    #[test]
+    fn fixed_cells_allow_writing() {
+        let mut view = demo_view();
+        view.edit(EditSource::Data(10), "12..5".to_string())
+            .unwrap();
+        assert!(view.check_valid().is_err());
+        view.edit(EditSource::Data(10), "12.75".to_string())
+            .unwrap();
+        assert!(view.check_valid().is_ok());
+        assert!(view.can_write());
+    }
+
+    #[test]
+    fn unchanged_table_is_not_writable() {
+        let view = demo_view();
+        assert!(view.check_valid().is_ok());
+        assert!(!view.can_write());
+    }
+
+    #[test]
+    fn valid_values() {
+        assert!(valid_value("12.5", Some((0.0, 20.0))));
+        assert!(valid_value("20", Some((20.0, 0.0))));
+        assert!(!valid_value("20.5", Some((0.0, 20.0))));
+        assert!(!valid_value("12..5", None));
+        assert!(!valid_value("", None));
+        assert!(!valid_value("NaN", None));
+        assert!(valid_value("-1e3", None));
+    }
+
+    fn push(history: &mut EditHistory, i: usize, old: &str, new: &str) {
+        history.push(EditSource::Data(i), old.to_string(), new.to_string());
+    }
+
+    fn edit(edit: Option<CellEdit>) -> Option<(EditSource, String, String)> {
+        edit.map(|e| (e.source, e.old, e.new))
+    }
+
+    fn change(i: usize, old: &str, new: &str) -> Option<(EditSource, String, String)> {
+        Some((EditSource::Data(i), old.to_string(), new.to_string()))
+    }
+
+    #[test]
+    fn history_coalesces_same_cell() {
+        let mut history = EditHistory::default();
+        push(&mut history, 0, "1", "12");
+        push(&mut history, 0, "12", "12.5");
+        push(&mut history, 1, "3", "4");
+        assert_eq!(edit(history.undo()), change(1, "3", "4"));
+        assert_eq!(edit(history.undo()), change(0, "1", "12.5"));
+        assert_eq!(edit(history.undo()), None);
+    }
+
+    #[test]
+    fn history_redo() {
+        let mut history = EditHistory::default();
+        push(&mut history, 0, "1", "2");
+        push(&mut history, 1, "1", "2");
+        history.undo();
+        history.undo();
+        assert_eq!(edit(history.redo()), change(0, "1", "2"));
+        assert_eq!(edit(history.redo()), change(1, "1", "2"));
+        assert_eq!(edit(history.redo()), None);
+    }
+
+    #[test]
+    fn history_new_edit_clears_redo() {
+        let mut history = EditHistory::default();
+        push(&mut history, 0, "1", "2");
+        history.undo();
+        push(&mut history, 2, "5", "6");
+        assert_eq!(edit(history.redo()), None);
+    }
+
+    #[test]
+    fn history_undo_stops_coalescing() {
+        let mut history = EditHistory::default();
+        push(&mut history, 2, "5", "6");
+        push(&mut history, 3, "1", "2");
+        history.undo();
+        // The cell edited before the undo starts a new edit instead of extending the old one
+        push(&mut history, 2, "6", "7");
+        assert_eq!(edit(history.undo()), change(2, "6", "7"));
+        assert_eq!(edit(history.undo()), change(2, "5", "6"));
+    }
+
+    #[test]
+    fn history_bounded_and_cleared() {
+        let mut history = EditHistory::default();
+        for i in 0..MAX_HISTORY + 10 {
+            push(&mut history, i, "0", "1");
+        }
+        let mut undone = 0;
+        while history.undo().is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_HISTORY);
+
+        push(&mut history, 0, "0", "1");
+        history.clear();
+        assert_eq!(edit(history.undo()), None);
+        assert_eq!(edit(history.redo()), None);
+    }
+
+    #[test]
+    fn normalize_range() {
+        assert_eq!(normalize(10.0, (10.0, 20.0)), 0.0);
+        assert_eq!(normalize(15.0, (10.0, 20.0)), 0.5);
+        assert_eq!(normalize(20.0, (10.0, 20.0)), 1.0);
+        assert_eq!(normalize(-5.0, (-10.0, 10.0)), 0.25);
+    }
+
+    #[test]
+    fn normalize_clamps() {
+        assert_eq!(normalize(5.0, (10.0, 20.0)), 0.0);
+        assert_eq!(normalize(25.0, (10.0, 20.0)), 1.0);
+    }
+
+    #[test]
+    fn normalize_all_equal() {
+        // A flat table has no range to divide by, every cell gets the middle colour
+        assert_eq!(normalize(7.0, (7.0, 7.0)), 0.5);
+        assert_eq!(normalize(0.0, (0.0, 0.0)), 0.5);
+        assert!(normalize(7.0, (7.0, 7.0)).is_finite());
+    }
+
+    fn strings(values: &[f64]) -> Vec<String> {
+        values.iter().map(f64::to_string).collect()
+    }
+
+    /// Chart of a `width` x `height` table counting up from 0
+    fn chart(width: usize, height: usize) -> Chart2D {
+        let table = &crate::demo::definition().tables[0];
+        let x: Vec<f64> = (0..width).map(|i| i as f64).collect();
+        let y: Vec<f64> = (0..height).map(|i| i as f64).collect();
+        let z: Vec<f64> = (0..width * height).map(|i| i as f64).collect();
+        Chart2D::new(0, table, &strings(&x), &strings(&y), &strings(&z))
+    }
+
+    #[test]
+    fn highlight_out_of_bounds_is_ignored() {
+        let mut chart = chart(3, 2);
+        chart.set_highlight(Some((1, 2)));
+        assert_eq!(chart.highlight, Some((1, 2)));
+        chart.set_highlight(Some((2, 0)));
+        assert_eq!(chart.highlight, None);
+        chart.set_highlight(Some((0, 3)));
+        assert_eq!(chart.highlight, None);
+    }
+
+    #[test]
+    fn highlight_clamped_when_shape_changes() {
+        let mut chart = chart(4, 4);
+        chart.set_highlight(Some((3, 1)));
+        // Same shape keeps the highlight
+        chart.set_data(
+            &strings(&[0.0; 4]),
+            &strings(&[0.0; 4]),
+            &strings(&[1.0; 16]),
+        );
+        assert_eq!(chart.highlight, Some((3, 1)));
+        // Fewer rows drop it
+        chart.set_data(
+            &strings(&[0.0; 4]),
+            &strings(&[0.0; 2]),
+            &strings(&[1.0; 8]),
+        );
+        assert_eq!(chart.highlight, None);
+
+        chart.set_highlight(Some((1, 3)));
+        chart.set_data(
+            &strings(&[0.0; 2]),
+            &strings(&[0.0; 2]),
+            &strings(&[1.0; 4]),
+        );
+        assert_eq!(chart.highlight, None);
+    }
+
+    #[test]
+    fn text_values_are_not_plottable() {
+        let table = &crate::demo::definition().tables[0];
+        let axis = owned(&["0", "1", "2"]);
+        let z = owned(&["abc", "", "2", "", "abc", "5", "6", "", "8"]);
+        let chart = Chart2D::new(0, table, &axis, &axis, &z);
+        assert!(!chart.plottable());
+        assert!(chart.z[0][0].is_nan() && chart.z[0][1].is_nan());
+        assert_eq!(chart.z_range, 2.0..8.0);
+    }
+
+    #[test]
+    fn quads_farthest_first() {
+        let chart = chart(3, 3);
+        let quads = chart.quads_by_depth(|x, _, y| (x + y) as i32);
+        let first_corners: Vec<_> = quads.iter().map(|(corners, _)| corners[0]).collect();
+        assert_eq!(
+            first_corners,
+            [
+                (1.0, 4.0, 1.0),
+                (1.0, 1.0, 0.0),
+                (0.0, 3.0, 1.0),
+                (0.0, 0.0, 0.0)
+            ]
+        );
+        assert_eq!(quads[0].1, 0.75);
+        assert_eq!(quads[3].1, 0.25);
+    }
+
+    fn chart_axis(precision: Option<usize>, names: &[&str], units: &str) -> ChartAxis {
+        ChartAxis {
+            units: units.to_string(),
+            precision,
+            names: owned(names),
+        }
+    }
+
+    #[test]
+    fn tick_fixed_precision() {
+        let axis = chart_axis(Some(2), &[], "");
+        assert_eq!(axis.tick(1.5), "1.50");
+        assert_eq!(axis.tick(-3.0), "-3.00");
+    }
+
+    #[test]
+    fn tick_trims_without_precision() {
+        let axis = chart_axis(None, &[], "");
+        assert_eq!(axis.tick(1.5), "1.5");
+        assert_eq!(axis.tick(2.0), "2");
+        assert_eq!(axis.tick(0.12345), "0.123");
+        assert_eq!(axis.tick(-4.25), "-4.25");
+    }
+
+    #[test]
+    fn tick_categorical_names() {
+        let axis = chart_axis(Some(1), &["Off", "On"], "");
+        assert_eq!(axis.tick(0.0), "Off");
+        assert_eq!(axis.tick(1.0), "On");
+        // Between or past the names ticks are numbers again
+        assert_eq!(axis.tick(0.5), "0.5");
+        assert_eq!(axis.tick(2.0), "2.0");
+    }
+
+    #[test]
+    fn tick_units_appended() {
+        assert_eq!(
+            chart_axis(Some(1), &[], "kPa").tick_with_units(101.3),
+            "101.3 kPa"
+        );
+        assert_eq!(chart_axis(None, &[], "").tick_with_units(3.0), "3");
+        assert_eq!(
+            chart_axis(None, &["Off"], "bar").tick_with_units(0.0),
+            "Off bar"
+        );
+    }
+
+    #[test]
+    fn rotate_clamps_pitch() {
+        assert_eq!(rotate(0.5, 0.0, 0.0, 10_000.0).0, FRAC_PI_2);
+        assert_eq!(rotate(-0.5, 0.0, 0.0, -10_000.0).0, -FRAC_PI_2);
+        let (pitch, yaw) = rotate(0.5, 0.5, 10.0, 20.0);
+        assert!((pitch - 0.7).abs() < 1e-9);
+        assert!((yaw - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rotate_wraps_yaw() {
+        let (_, yaw) = rotate(0.0, PI - 0.05, 10.0, 0.0);
+        assert!((yaw - (0.05 - PI)).abs() < 1e-9);
+        let (_, yaw) = rotate(0.0, 0.05 - PI, -10.0, 0.0);
+        assert!((yaw - (PI - 0.05)).abs() < 1e-9);
+        let (_, yaw) = rotate(0.0, 0.0, 10_000.0, 0.0);
+        assert!((-PI..PI).contains(&yaw));
+    }
+
+    #[test]
+    fn zoom_bounded() {
+        assert!((zoom(1.0, 1.0) - 1.1).abs() < 1e-9);
+        assert!((zoom(1.0, -1.0) - 1.0 / 1.1).abs() < 1e-9);
+        assert_eq!(zoom(1.0, 100.0), *SCALE_RANGE.end());
+        assert_eq!(zoom(1.0, -100.0), *SCALE_RANGE.start());
+        assert_eq!(zoom(0.7, 0.0), 0.7);
+    }
+
+    #[test]
+    fn ranges_only_updated_with_data() {
+        let updates = || RANGE_UPDATES.with(|n| n.get());
+        let mut chart = chart(4, 4);
+        let after_new = updates();
+        chart.set_highlight(Some((1, 1)));
+        chart.set_colormap(Colormap::Plasma);
+        chart.pitch(1.0);
+        chart.yaw(-1.0);
+        chart.scale(1.5);
+        chart.swap_axes();
+        assert_eq!(updates(), after_new);
+        chart.set_comparison(None);
+        assert_eq!(updates(), after_new + 1);
+
+        let mut view = demo_view();
+        let after_new = updates();
+        let _ = view.focus_chart_point((0, 3)).unwrap();
+        assert_eq!(updates(), after_new);
+        view.edit(EditSource::Data(3), "10".to_string()).unwrap();
+        view.refresh_chart();
+        assert!(updates() > after_new);
+    }
+
+    #[test]
+    fn finite_range_widens_equal_values() {
+        assert_eq!(finite_range([0.0; 16].iter()), -1.0..1.0);
+        assert_eq!(finite_range([5.0; 4].iter()), 4.0..6.0);
+        assert_eq!(finite_range([-100.0, -100.0].iter()), -110.0..-90.0);
+        assert_eq!(
+            finite_range([f64::NAN, 3.0, f64::INFINITY].iter()),
+            2.0..4.0
+        );
+        assert_eq!(finite_range([f64::NAN].iter()), 0.0..1.0);
+        assert_eq!(finite_range([2.0, -1.0, f64::NAN].iter()), -1.0..2.0);
+    }
+
+    #[test]
+    fn all_zero_table_is_drawn() {
+        let table = &crate::demo::definition().tables[0];
+        let axis = strings(&[0.0, 1.0, 2.0, 3.0]);
+        let chart = Chart2D::new(0, table, &axis, &axis, &strings(&[0.0; 16]));
+        assert!(!chart.too_small());
+        assert!(chart.plottable());
+        assert_eq!(chart.z_range, -1.0..1.0);
+        assert!(chart.z_norm.iter().flatten().all(|&v| v == 0.5));
+    }
+
+    #[test]
+    fn single_cell_is_too_small() {
+        assert!(chart(1, 1).too_small());
+        assert!(!chart(2, 1).too_small());
+        assert!(!chart(1, 2).too_small());
+        let table = &crate::demo::definition().tables[0];
+        let chart = Chart2D::new(
+            0,
+            table,
+            &strings(&[7.0; 3]),
+            &strings(&[0.0]),
+            &strings(&[1.0; 3]),
+        );
+        assert!(!chart.too_small());
+        assert_eq!(chart.x_range, 6.0..8.0);
+    }
+
+    #[test]
+    fn line_points_of_single_row() {
+        let table = &crate::demo::definition().tables[0];
+        let chart = Chart2D::new(
+            0,
+            table,
+            &owned(&["10", "20", "", "40"]),
+            &owned(&["0"]),
+            &owned(&["1", "2", "3", "x"]),
+        );
+        assert_eq!(chart.line_points(&chart.z), [(10.0, 1.0), (20.0, 2.0)]);
+    }
+
+    #[test]
+    fn line_points_of_single_column() {
+        let table = &crate::demo::definition().tables[0];
+        let chart = Chart2D::new(
+            0,
+            table,
+            &owned(&["0"]),
+            &owned(&["500", "1000", "1500"]),
+            &owned(&["4", "5", "6"]),
+        );
+        assert_eq!(
+            chart.line_points(&chart.z),
+            [(500.0, 4.0), (1000.0, 5.0), (1500.0, 6.0)]
+        );
+        let comparison = [vec![7.0], vec![f64::NAN], vec![9.0]];
+        assert_eq!(
+            chart.line_points(&comparison),
+            [(500.0, 7.0), (1500.0, 9.0)]
+        );
+    }
+
+    #[test]
+    fn grid_pads_short_input() {
+        let rows = grid(vec![1.0, 2.0, 3.0], 2, 2);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], [1.0, 2.0]);
+        assert_eq!(rows[1][0], 3.0);
+        assert!(rows[1][1].is_nan());
+        assert!(grid(Vec::new(), 3, 1)[0].iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn grid_truncates_long_input() {
+        let rows = grid((0..10).map(f64::from).collect(), 3, 2);
+        assert_eq!(rows, [vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 5.0]]);
+        assert!(grid(vec![1.0, 2.0], 2, 0).is_empty());
+    }
+
+    #[test]
+    fn nearest_point_within_radius() {
+        let points = [((10, 10), (0, 0)), ((40, 10), (0, 1)), ((10, 40), (1, 0))];
+        assert_eq!(
+            nearest_point(&points, Point::new(12.0, 11.0), 24.0),
+            Some((0, 0))
+        );
+        assert_eq!(
+            nearest_point(&points, Point::new(30.0, 10.0), 24.0),
+            Some((0, 1))
+        );
+        assert_eq!(
+            nearest_point(&points, Point::new(10.0, 30.0), 24.0),
+            Some((1, 0))
+        );
+    }
+
+    #[test]
+    fn nearest_point_radius_cutoff() {
+        let points = [((10, 10), (0, 0))];
+        assert_eq!(
+            nearest_point(&points, Point::new(10.0, 34.0), 24.0),
+            Some((0, 0))
+        );
+        assert_eq!(nearest_point(&points, Point::new(10.0, 35.0), 24.0), None);
+        assert_eq!(nearest_point(&[], Point::new(10.0, 10.0), 24.0), None);
+    }
+
+    #[test]
+    fn next_cell_down() {
+        // 3 wide, 2 high
+        let down = |source| next_cell(source, MoveDirection::Down, 3, 2);
+        assert_eq!(down(EditSource::XHead(2)), Some(EditSource::Data(2)));
+        assert_eq!(down(EditSource::Data(1)), Some(EditSource::Data(4)));
+        assert_eq!(down(EditSource::YHead(0)), Some(EditSource::YHead(1)));
+        // Last row and last cell
+        assert_eq!(down(EditSource::Data(3)), None);
+        assert_eq!(down(EditSource::Data(5)), None);
+        assert_eq!(down(EditSource::YHead(1)), None);
+        // No data rows below the x axis
+        assert_eq!(
+            next_cell(EditSource::XHead(0), MoveDirection::Down, 3, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn next_cell_up() {
+        let up = |source| next_cell(source, MoveDirection::Up, 3, 2);
+        assert_eq!(up(EditSource::Data(5)), Some(EditSource::Data(2)));
+        assert_eq!(up(EditSource::Data(2)), Some(EditSource::XHead(2)));
+        assert_eq!(up(EditSource::Data(0)), Some(EditSource::XHead(0)));
+        assert_eq!(up(EditSource::YHead(1)), Some(EditSource::YHead(0)));
+        assert_eq!(up(EditSource::XHead(1)), None);
+        assert_eq!(up(EditSource::YHead(0)), None);
+    }
+
+    #[test]
+    fn commit_and_move_does_not_write() {
+        let mut view = demo_view();
+        view.edit(EditSource::Data(63), "10".to_string()).unwrap();
+        // Enter on the last cell commits and stays there
+        view.commit_edit();
+        let _ = view
+            .move_focus(EditSource::Data(63), MoveDirection::Down)
+            .unwrap();
+        assert_eq!(view.focused, Some(EditSource::Data(63)));
+        let _ = view
+            .move_focus(EditSource::Data(63), MoveDirection::Up)
+            .unwrap();
+        assert_eq!(view.focused, Some(EditSource::Data(55)));
+        assert_eq!(view.value(EditSource::Data(63)), "10");
+        assert_eq!(view.modified_count(), 1);
+        let mut source = view.source.clone();
+        let mut binary = Vec::new();
+        source.rewind().unwrap();
+        source.read_to_end(&mut binary).unwrap();
+        assert_eq!(binary, crate::demo::binary());
+    }
+
+    #[test]
+    fn find_extreme_first_of_equal() {
+        let values = owned(&["3", "-1.5", "7", "-1.5", "7"]);
+        assert_eq!(find_extreme(&values, |_| false, Extreme::Min), Some(1));
+        assert_eq!(find_extreme(&values, |_| false, Extreme::Max), Some(2));
+    }
+
+    #[test]
+    fn find_extreme_skips_nan_and_invalid() {
+        let values = owned(&["NaN", "abc", "", "4", "-20", "12"]);
+        assert_eq!(find_extreme(&values, |_| false, Extreme::Min), Some(4));
+        assert_eq!(find_extreme(&values, |i| i == 4, Extreme::Min), Some(3));
+        assert_eq!(find_extreme(&values, |i| i == 5, Extreme::Max), Some(3));
+        assert_eq!(find_extreme(&values, |i| i > 2, Extreme::Max), None);
+        assert_eq!(find_extreme(&[], |_| false, Extreme::Max), None);
+    }
+
+    #[test]
+    fn parse_change_with_markers() {
+        assert_eq!(parse_change("2.5"), Some(2.5));
+        assert_eq!(parse_change(" -10 % "), Some(-10.0));
+        assert_eq!(parse_change("Δ3"), Some(3.0));
+        assert_eq!(parse_change("Δ-0.75"), Some(-0.75));
+        assert_eq!(parse_change("+4%"), Some(4.0));
+        assert_eq!(parse_change("%"), None);
+        assert_eq!(parse_change("abc"), None);
+    }
+
+    #[test]
+    fn change_typed_in_either_direction() {
+        let mut view = demo_view();
+        view.toggle_change();
+        view.set_change_mode(DeltaMode::Absolute);
+        let base: f64 = view.baseline.data[9].parse().unwrap();
+        view.edit(EditSource::Data(9), "1.5".to_string()).unwrap();
+        assert_eq!(
+            view.value(EditSource::Data(9)),
+            view.format_data(base + 1.5)
+        );
+        view.edit(EditSource::Data(9), "-1.5".to_string()).unwrap();
+        assert_eq!(
+            view.value(EditSource::Data(9)),
+            view.format_data(base - 1.5)
+        );
+        view.set_change_mode(DeltaMode::Percent);
+        view.edit(EditSource::Data(9), "-50%".to_string()).unwrap();
+        let halved = apply_delta(-50.0, base, DeltaMode::Percent);
+        assert_eq!(view.value(EditSource::Data(9)), view.format_data(halved));
+    }
+
+    #[test]
+    fn change_mode_switch_keeps_edits() {
+        let mut view = demo_view();
+        view.toggle_change();
+        view.set_change_mode(DeltaMode::Percent);
+        view.edit(EditSource::Data(9), "20%".to_string()).unwrap();
+        view.edit(EditSource::Data(10), "Δ1.5".to_string()).unwrap();
+        let edited = (view.data[9].clone(), view.data[10].clone());
+        view.set_change_mode(DeltaMode::Absolute);
+        assert_eq!((view.data[9].clone(), view.data[10].clone()), edited);
+        view.toggle_change();
+        assert_eq!((view.data[9].clone(), view.data[10].clone()), edited);
+        assert_eq!(view.modified_count(), 2);
+        assert!(view.can_write());
+    }
+
+    #[test]
+    fn visible_rows_with_overscan() {
+        assert_eq!(visible_rows(0.0, 100.0, 10.0, 50, 2), 0..12);
+        assert_eq!(visible_rows(105.0, 100.0, 10.0, 50, 2), 8..23);
+        assert_eq!(visible_rows(-30.0, 100.0, 10.0, 50, 0), 0..7);
+        assert_eq!(visible_rows(480.0, 100.0, 10.0, 50, 2), 46..50);
+        assert_eq!(visible_rows(0.0, 100.0, 10.0, 0, 2), 0..0);
+    }
+
+    #[test]
+    fn transpose_square() {
+        // 3x3, row 0 column 1 ends up at row 1 column 0
+        assert_eq!(
+            transpose_source(EditSource::Data(1), 3, 3),
+            EditSource::Data(3)
+        );
+        assert_eq!(
+            transpose_source(EditSource::Data(4), 3, 3),
+            EditSource::Data(4)
+        );
+        assert_eq!(
+            transpose_source(EditSource::Data(8), 3, 3),
+            EditSource::Data(8)
+        );
+        assert_eq!(
+            transpose_source(EditSource::XHead(2), 3, 3),
+            EditSource::YHead(2)
+        );
+        assert_eq!(
+            transpose_source(EditSource::YHead(0), 3, 3),
+            EditSource::XHead(0)
+        );
+    }
+
+    #[test]
+    fn transpose_non_square() {
+        // 3 wide and 2 high, row 1 column 1 is at row 1 column 1 of the 2 wide result
+        assert_eq!(
+            transpose_source(EditSource::Data(4), 3, 2),
+            EditSource::Data(3)
+        );
+        // Row 0 column 2 moves to row 2 column 0
+        assert_eq!(
+            transpose_source(EditSource::Data(2), 3, 2),
+            EditSource::Data(4)
+        );
+        assert_eq!(
+            transpose_source(EditSource::Data(5), 3, 2),
+            EditSource::Data(5)
+        );
+    }
+
+    #[test]
+    fn transpose_round_trip() {
+        for (width, height) in [(1, 1), (3, 3), (4, 2), (1, 5), (16, 12)] {
+            let sources = (0..width * height)
+                .map(EditSource::Data)
+                .chain((0..width).map(EditSource::XHead))
+                .chain((0..height).map(EditSource::YHead));
+            let mut seen = HashSet::new();
+            for source in sources {
+                let transposed = transpose_source(source, width, height);
+                assert!(seen.insert(transposed), "{source:?} collides");
+                assert_eq!(transpose_source(transposed, height, width), source);
+            }
         }
     }
+
+    #[test]
+    fn column_widths_fit_longest_value() {
+        let rows = [
+            vec!["1", "12345"],
+            vec!["12", "1"],
+            vec!["12", "12", "12345"],
+        ];
+        let widths = column_widths(rows, Density::Comfortable);
+        assert_eq!(widths.len(), 3);
+        assert_eq!(widths[0], MIN_CELL_WIDTH);
+        assert!(widths[1] > MIN_CELL_WIDTH);
+        assert_eq!(widths[1], widths[2]);
+    }
+
+    #[test]
+    fn column_widths_clamped() {
+        let long = "1".repeat(100);
+        let widths = column_widths([["", long.as_str()]], Density::Comfortable);
+        assert_eq!(widths, vec![MIN_CELL_WIDTH, MAX_CELL_WIDTH]);
+        assert!(column_widths(Vec::<Vec<&str>>::new(), Density::Compact).is_empty());
+    }
+
+    #[test]
+    fn column_widths_by_density() {
+        let rows = [["-123.456"]];
+        let comfortable = column_widths(rows, Density::Comfortable)[0];
+        let compact = column_widths(rows, Density::Compact)[0];
+        assert!(compact < comfortable);
+    }
+
+    #[test]
+    fn hex_entry_outside_raw_mode() {
+        let mut view = demo_view();
+        // 0xFF is the largest stored integer, 143.25 shown at the expression's precision
+        let converted = view.table.z.format(143.25);
+        view.edit(EditSource::Data(0), "0xff".to_string()).unwrap();
+        assert_eq!(view.value(EditSource::Data(0)), converted);
+        assert_eq!(view.display_value(EditSource::Data(0)), "0xff");
+        assert!(!view.invalid.contains(&EditSource::Data(0)));
+        view.commit_edit();
+        assert_eq!(view.display_value(EditSource::Data(0)), converted);
+    }
+
+    #[test]
+    fn hex_entry_must_fit_element() {
+        let mut view = demo_view();
+        view.edit(EditSource::Data(0), "0x100".to_string()).unwrap();
+        view.commit_edit();
+        assert!(view.invalid.contains(&EditSource::Data(0)));
+        assert_eq!(view.display_value(EditSource::Data(0)), "0x100");
+        view.edit(EditSource::Data(0), "12".to_string()).unwrap();
+        assert!(!view.invalid.contains(&EditSource::Data(0)));
+        assert_eq!(view.display_value(EditSource::Data(0)), "12");
+    }
+
+    #[test]
+    fn row_runs_group_consecutive_rows() {
+        assert_eq!(row_runs([0, 1, 2, 5, 7, 8]), vec![0..3, 5..6, 7..9]);
+        assert_eq!(row_runs([4]), vec![4..5]);
+        assert!(row_runs([]).is_empty());
+    }
+
+    /// 300x300 table of one byte cells, too large to be read up front
+    fn lazy_view() -> TableView {
+        let axis = |n: usize| Axis {
+            units: String::new(),
+            data: AxisData::User((0..n).map(|v| v as f64).collect()),
+            names: Vec::new(),
+        };
+        let z = Axis {
+            units: String::new(),
+            data: AxisData::Binary {
+                address: 0,
+                element_size: 1,
+                count: 300 * 300,
+                expression: "X".to_string(),
+                row_stride: None,
+                big_endian: false,
+                signed: false,
+                display: DisplayScale::default(),
+                flag: false,
+                decimals: None,
+                type_flags: None,
+            },
+            names: Vec::new(),
+        };
+        let table = Table {
+            name: "Large".to_string(),
+            description: String::new(),
+            x: axis(300),
+            y: axis(300),
+            z,
+            categories: Vec::new(),
+        };
+        let binary = (0..300 * 300).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        TableView::new(0, table, FileGuard::from(binary)).unwrap()
+    }
+
+    /// Shown value of a cell of [`lazy_view`] as it is stored
+    fn stored(view: &TableView, i: usize) -> String {
+        view.table.z.format((i % 251) as f64)
+    }
+
+    #[test]
+    fn large_tables_read_visible_rows() {
+        let view = lazy_view();
+        assert!(!view.show_chart);
+        assert!(view.row_loaded(0));
+        assert!(!view.row_loaded(299));
+        assert_eq!(view.data[1], stored(&view, 1));
+        assert_eq!(view.data[299 * 300], "");
+        assert_eq!(view.modified_count(), 0);
+        // Whole numbers of a whole table are shown without decimals, compare the values
+        let stored = view.table.z.read(&mut view.source.clone()).unwrap();
+        assert_eq!(parse_values(&view.z_values().unwrap()), stored);
+    }
+
+    #[test]
+    fn edit_reads_row_first() {
+        let mut view = lazy_view();
+        let cell = 299 * 300 + 5;
+        view.edit(EditSource::Data(cell), "7".to_string()).unwrap();
+        assert!(view.row_loaded(299));
+        assert_eq!(view.data[cell - 1], stored(&view, cell - 1));
+        assert_eq!(view.modified_count(), 1);
+        assert!(view.can_write());
+    }
+
+    #[test]
+    fn scrolling_forgets_unedited_rows() {
+        let mut view = lazy_view();
+        view.edit(EditSource::Data(3), "7".to_string()).unwrap();
+        view.scroll_y = 200.0 * ROW_HEIGHT;
+        view.load_visible().unwrap();
+        assert!(view.row_loaded(200));
+        assert!(view.row_loaded(0));
+        assert!(!view.row_loaded(1));
+        assert_eq!(view.data[300], "");
+        assert_eq!(view.modified_count(), 1);
+        let data = view.z_values().unwrap();
+        assert_eq!(data[3], "7");
+        assert_eq!(data[300], stored(&view, 300));
+    }
+
+    #[test]
+    fn heatmap_range_covers_rows_not_read() {
+        let mut view = lazy_view();
+        assert_eq!(view.data_range, Some((0.0, 250.0)));
+        view.scroll_y = 200.0 * ROW_HEIGHT;
+        view.load_visible().unwrap();
+        assert_eq!(view.data_range, Some((0.0, 250.0)));
+        // Edits of rows that are read still widen it
+        view.edit(EditSource::Data(3), "300".to_string()).unwrap();
+        assert_eq!(view.data_range, Some((0.0, 300.0)));
+    }
+
+    #[test]
+    fn hidden_chart_is_not_rebuilt() {
+        let mut view = demo_view();
+        let before = view.chart.z[0][0];
+        view.toggle_chart().unwrap();
+        view.edit(EditSource::Data(0), "10".to_string()).unwrap();
+        assert!(view.chart_stale);
+        assert_eq!(view.chart.z[0][0], before);
+        view.toggle_chart().unwrap();
+        assert!(!view.chart_stale);
+        assert_eq!(view.chart.z[0][0], 10.0);
+    }
+
+    #[test]
+    fn chart_layout_applied() {
+        let mut view = demo_view();
+        view.set_chart_layout(ChartLayout {
+            visible: false,
+            fraction: 0.9,
+        })
+        .unwrap();
+        assert_eq!(
+            view.chart_layout(),
+            ChartLayout {
+                visible: false,
+                fraction: 0.8
+            }
+        );
+        let mut view = lazy_view();
+        view.set_chart_layout(ChartLayout {
+            visible: true,
+            fraction: 0.3,
+        })
+        .unwrap();
+        assert!(view.show_chart);
+        assert!(!view.chart_stale);
+        assert_eq!(view.chart_fraction, 0.3);
+    }
 }